@@ -1,14 +1,20 @@
-use crate::components::{core, reentrancy};
-use crate::errors::ContractError;
+use crate::components::{core, fee_override, reentrancy};
+use crate::errors::{ContractError, FeeError, InvoiceError};
 use crate::events;
 use crate::types::{
-    DataKey, MerchantAnalytics, MerchantAnalyticsSummary, OracleConfig, PendingFee, TokenAnalytics,
+    DataKey, FeeDataKey, FeeOverridePriority, FeeSchedule, InvoiceDataKey, MerchantAnalytics,
+    MerchantAnalyticsSummary, OracleConfig, PendingFee, PendingVolumeDiscounts, TokenAnalytics,
+    VolumeBucket, VolumeDiscount,
 };
 use soroban_sdk::{panic_with_error, token, Address, Env, Vec};
 
 pub const FEE_UPDATE_DELAY: u64 = 172_800; // 48 hours in seconds
 pub const DAY_IN_SECONDS: u64 = 86400;
+pub const DEFAULT_FIAT_QUOTE_WINDOW: u64 = 300; // 5 minutes
 pub const WEEK_IN_SECONDS: u64 = 604800;
+pub const MAX_VOLUME_DISCOUNT_TIERS: u32 = 10;
+pub const DEFAULT_VOLUME_WINDOW_DAYS: u32 = 30;
+pub const MAX_VOLUME_WINDOW_DAYS: u32 = 365;
 
 // TODO: create the functionality for withdrawing revenue by admin.
 
@@ -106,6 +112,7 @@ pub fn set_account_wasm_hash(env: &Env, admin: &Address, wasm_hash: &soroban_sdk
     reentrancy::exit(env);
 }
 
+/// Replaces the token's fee schedule with a plain `fee` bps rate.
 pub fn set_fee(env: &Env, admin: &Address, token: &Address, fee: i128) {
     reentrancy::enter(env);
     core::assert_admin(env, admin);
@@ -114,25 +121,61 @@ pub fn set_fee(env: &Env, admin: &Address, token: &Address, fee: i128) {
         panic_with_error!(env, ContractError::TokenNotAccepted);
     }
 
+    let schedule = percentage_fee(fee);
+    validate_fee_schedule(env, &schedule);
     env.storage()
         .persistent()
-        .set(&DataKey::TokenFee(token.clone()), &fee);
+        .set(&FeeDataKey::FeeSchedule(token.clone()), &schedule);
 
     events::publish_fee_set_event(
         env,
         admin.clone(),
         token.clone(),
-        fee,
+        &schedule,
         env.ledger().timestamp(),
     );
     reentrancy::exit(env);
 }
 
+/// The bps part of the token's fee schedule.
 pub fn get_fee(env: &Env, token: &Address) -> i128 {
+    get_fee_schedule(env, token).fee_bps
+}
+
+// Rates set before fee schedules existed are stored under `TokenFee`.
+pub fn get_fee_schedule(env: &Env, token: &Address) -> FeeSchedule {
     env.storage()
         .persistent()
-        .get(&DataKey::TokenFee(token.clone()))
-        .unwrap_or(0)
+        .get(&FeeDataKey::FeeSchedule(token.clone()))
+        .unwrap_or_else(|| {
+            percentage_fee(
+                env.storage()
+                    .persistent()
+                    .get(&DataKey::TokenFee(token.clone()))
+                    .unwrap_or(0),
+            )
+        })
+}
+
+fn percentage_fee(fee_bps: i128) -> FeeSchedule {
+    FeeSchedule {
+        flat_fee: 0,
+        fee_bps,
+        min_fee: 0,
+        max_fee: 0,
+    }
+}
+
+pub fn validate_fee_schedule(env: &Env, schedule: &FeeSchedule) {
+    if schedule.flat_fee < 0
+        || schedule.fee_bps < 0
+        || schedule.fee_bps > 10_000
+        || schedule.min_fee < 0
+        || schedule.max_fee < 0
+        || (schedule.max_fee > 0 && schedule.max_fee < schedule.min_fee)
+    {
+        panic_with_error!(env, FeeError::InvalidFeeSchedule);
+    }
 }
 
 pub fn set_platform_account(env: &Env, admin: &Address, account: &Address) {
@@ -186,22 +229,222 @@ pub fn get_token_oracle(env: &Env, token: &Address) -> OracleConfig {
         .unwrap_or_else(|| panic_with_error!(env, ContractError::OracleNotConfigured))
 }
 
+/// How long a quote pinned with `lock_fiat_quote` stays valid. Capped at a
+/// day so a locked price cannot drift far from the oracle.
+pub fn set_fiat_quote_window(env: &Env, admin: &Address, window: u64) {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
+
+    if window == 0 || window > DAY_IN_SECONDS {
+        panic_with_error!(env, InvoiceError::InvalidQuoteWindow);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::FiatQuoteWindow, &window);
+    events::publish_fiat_quote_window_set_event(
+        env,
+        admin.clone(),
+        window,
+        env.ledger().timestamp(),
+    );
+    reentrancy::exit(env);
+}
+
+pub fn get_fiat_quote_window(env: &Env) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::FiatQuoteWindow)
+        .unwrap_or(DEFAULT_FIAT_QUOTE_WINDOW)
+}
+
+/// Fee on a payment of `amount` by `merchant` in `token`, from the merchant's
+/// fee override if one is in force and the token's fee schedule otherwise.
+/// It never exceeds `amount`, so a payment can always cover its own fee.
 pub fn calculate_fee(env: &Env, merchant: &Address, token: &Address, amount: i128) -> i128 {
-    let fee_bps: i128 = get_fee(env, token);
-    if fee_bps == 0 {
-        return 0;
+    quote_fee(env, merchant, token, amount).0
+}
+
+/// `calculate_fee` for a payment that is being made. Reports payments priced
+/// by a merchant override in a `FeeDiscountAppliedEvent`.
+pub fn charge_fee(env: &Env, merchant: &Address, token: &Address, amount: i128) -> i128 {
+    let (fee, standard_fee) = quote_fee(env, merchant, token, amount);
+    if let Some(standard_fee) = standard_fee {
+        events::publish_fee_discount_applied_event(
+            env,
+            merchant.clone(),
+            token.clone(),
+            amount,
+            standard_fee,
+            fee,
+            env.ledger().timestamp(),
+        );
+    }
+    fee
+}
+
+// Returns the fee and, when a merchant override was in force, the fee the
+// token's schedule would have charged without it.
+fn quote_fee(env: &Env, merchant: &Address, token: &Address, amount: i128) -> (i128, Option<i128>) {
+    if amount <= 0 {
+        return (0, None);
     }
+    let volume = get_window_volume(env, merchant, token);
+    let tiers = get_volume_discounts(env, token);
+    let standard_fee = schedule_fee(&get_fee_schedule(env, token), amount, volume, &tiers);
 
-    let volume = get_merchant_volume(env, merchant, token);
-    let discounted_bps = apply_volume_discount(fee_bps, volume);
+    let Some(fee_override) = fee_override::active_fee_override(env, merchant, token) else {
+        return (standard_fee, None);
+    };
+    let no_tiers = Vec::new(env);
+    let fee = match fee_override.priority {
+        FeeOverridePriority::OverrideOnly => {
+            schedule_fee(&fee_override.schedule, amount, volume, &no_tiers)
+        }
+        FeeOverridePriority::StackVolumeDiscount => {
+            schedule_fee(&fee_override.schedule, amount, volume, &tiers)
+        }
+        FeeOverridePriority::LowestFee => {
+            schedule_fee(&fee_override.schedule, amount, volume, &no_tiers).min(standard_fee)
+        }
+    };
+    (fee, Some(standard_fee))
+}
 
-    (amount * discounted_bps) / 10_000i128
+fn schedule_fee(
+    schedule: &FeeSchedule,
+    amount: i128,
+    volume: i128,
+    tiers: &Vec<VolumeDiscount>,
+) -> i128 {
+    let discounted_bps = apply_volume_discount(schedule.fee_bps, volume, tiers);
+    let mut fee = schedule.flat_fee + (amount * discounted_bps) / 10_000i128;
+    fee = fee.max(schedule.min_fee);
+    if schedule.max_fee > 0 {
+        fee = fee.min(schedule.max_fee);
+    }
+    fee.min(amount)
 }
 
 pub fn get_merchant_volume(env: &Env, merchant: &Address, token: &Address) -> i128 {
     get_merchant_analytics(env, merchant, token).total_volume
 }
 
+/// Number of days of volume that count towards volume discounts.
+pub fn set_volume_window(env: &Env, admin: &Address, days: u32) {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
+
+    if days == 0 || days > MAX_VOLUME_WINDOW_DAYS {
+        panic_with_error!(env, FeeError::InvalidVolumeWindow);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&FeeDataKey::VolumeWindowDays, &days);
+    events::publish_volume_window_set_event(env, admin.clone(), days, env.ledger().timestamp());
+    reentrancy::exit(env);
+}
+
+pub fn get_volume_window(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::VolumeWindowDays)
+        .unwrap_or(DEFAULT_VOLUME_WINDOW_DAYS)
+}
+
+// First day still inside the volume window, today included.
+fn volume_window_start(env: &Env) -> u64 {
+    let today = env.ledger().timestamp() / DAY_IN_SECONDS;
+    (today + 1).saturating_sub(get_volume_window(env) as u64)
+}
+
+fn volume_buckets(env: &Env, merchant: &Address, token: &Address) -> Vec<VolumeBucket> {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::MerchantVolumeBuckets(
+            merchant.clone(),
+            token.clone(),
+        ))
+        .unwrap_or(Vec::new(env))
+}
+
+fn save_volume_buckets(
+    env: &Env,
+    merchant: &Address,
+    token: &Address,
+    buckets: &Vec<VolumeBucket>,
+) {
+    env.storage().persistent().set(
+        &FeeDataKey::MerchantVolumeBuckets(merchant.clone(), token.clone()),
+        buckets,
+    );
+}
+
+/// Volume the merchant took in `token` over the volume window, net of
+/// refunds. Volume discounts are based on this rather than lifetime volume.
+pub fn get_window_volume(env: &Env, merchant: &Address, token: &Address) -> i128 {
+    let start = volume_window_start(env);
+    volume_buckets(env, merchant, token)
+        .iter()
+        .filter(|bucket| bucket.day >= start)
+        .map(|bucket| bucket.volume)
+        .sum()
+}
+
+// Adds to today's bucket and drops buckets that have left the window, so the
+// stored list never holds more than a window's worth of days.
+fn add_window_volume(env: &Env, merchant: &Address, token: &Address, amount: i128) {
+    let start = volume_window_start(env);
+    let today = env.ledger().timestamp() / DAY_IN_SECONDS;
+    let mut buckets = Vec::new(env);
+    for bucket in volume_buckets(env, merchant, token).iter() {
+        if bucket.day >= start {
+            buckets.push_back(bucket);
+        }
+    }
+
+    match buckets.last() {
+        Some(mut bucket) if bucket.day == today => {
+            bucket.volume += amount;
+            buckets.set(buckets.len() - 1, bucket);
+        }
+        _ => buckets.push_back(VolumeBucket {
+            day: today,
+            volume: amount,
+        }),
+    }
+    save_volume_buckets(env, merchant, token, &buckets);
+}
+
+/// Takes a refund out of the volume window. It comes off the day of the
+/// original payment and earlier days, or the latest days when the payment
+/// date is unknown. Volume that has left the window is not touched.
+pub fn record_merchant_refund(
+    env: &Env,
+    merchant: &Address,
+    token: &Address,
+    amount: i128,
+    paid_at: Option<u64>,
+) {
+    let mut buckets = volume_buckets(env, merchant, token);
+    let paid_day = paid_at.map(|paid_at| paid_at / DAY_IN_SECONDS);
+    let mut remaining = amount;
+    let mut index = buckets.len();
+    while index > 0 && remaining > 0 {
+        index -= 1;
+        let mut bucket = buckets.get_unchecked(index);
+        if paid_day.is_some_and(|paid_day| bucket.day > paid_day) {
+            continue;
+        }
+        let taken = remaining.min(bucket.volume);
+        bucket.volume -= taken;
+        remaining -= taken;
+        buckets.set(index, bucket);
+    }
+    save_volume_buckets(env, merchant, token, &buckets);
+}
+
 pub fn get_merchant_analytics(env: &Env, merchant: &Address, token: &Address) -> MerchantAnalytics {
     env.storage()
         .persistent()
@@ -215,6 +458,8 @@ pub fn get_merchant_analytics(env: &Env, merchant: &Address, token: &Address) ->
                 .get(&DataKey::MerchantVolume(merchant.clone(), token.clone()))
                 .unwrap_or(0),
             total_fees: 0,
+            total_tax: 0,
+            total_credit: 0,
             transaction_count: 0,
             last_updated: 0,
         })
@@ -228,6 +473,8 @@ pub fn get_merchant_analytics_summary(env: &Env, merchant: &Address) -> Merchant
             merchant: merchant.clone(),
             total_volume: 0,
             total_fees: 0,
+            total_tax: 0,
+            total_credit: 0,
             transaction_count: 0,
             last_updated: 0,
         })
@@ -239,12 +486,15 @@ pub fn record_merchant_payment(
     token: &Address,
     volume_amount: i128,
     fee_amount: i128,
+    tax_amount: i128,
 ) {
     let mut analytics = get_merchant_analytics(env, merchant, token);
     analytics.total_volume += volume_amount;
     analytics.total_fees += fee_amount;
+    analytics.total_tax += tax_amount;
     analytics.transaction_count += 1;
     analytics.last_updated = env.ledger().timestamp();
+    add_window_volume(env, merchant, token, volume_amount);
 
     env.storage().persistent().set(
         &DataKey::MerchantAnalytics(merchant.clone(), token.clone()),
@@ -258,6 +508,7 @@ pub fn record_merchant_payment(
     let mut summary = get_merchant_analytics_summary(env, merchant);
     summary.total_volume += volume_amount;
     summary.total_fees += fee_amount;
+    summary.total_tax += tax_amount;
     summary.transaction_count += 1;
     summary.last_updated = analytics.last_updated;
 
@@ -270,6 +521,26 @@ pub fn record_merchant_payment(
     record_token_payment(env, token, volume_amount, fee_amount);
 }
 
+/// Records part of an invoice settled with a credit note. No tokens move, so
+/// volume, fees and the transaction count are left alone.
+pub fn record_merchant_credit(env: &Env, merchant: &Address, token: &Address, amount: i128) {
+    let mut analytics = get_merchant_analytics(env, merchant, token);
+    analytics.total_credit += amount;
+    analytics.last_updated = env.ledger().timestamp();
+    env.storage().persistent().set(
+        &DataKey::MerchantAnalytics(merchant.clone(), token.clone()),
+        &analytics,
+    );
+
+    let mut summary = get_merchant_analytics_summary(env, merchant);
+    summary.total_credit += amount;
+    summary.last_updated = analytics.last_updated;
+    env.storage().persistent().set(
+        &DataKey::MerchantAnalyticsSummary(merchant.clone()),
+        &summary,
+    );
+}
+
 pub fn get_token_analytics(env: &Env, token: &Address) -> TokenAnalytics {
     env.storage()
         .persistent()
@@ -293,11 +564,11 @@ pub fn get_token_volume(env: &Env, token: &Address) -> i128 {
 
 fn record_token_payment(env: &Env, token: &Address, volume_amount: i128, fee_amount: i128) {
     let mut analytics = get_token_analytics(env, token);
-    
+
     // Check if this is a new merchant for this token
     let current_volume = get_token_volume(env, token);
     let is_new_merchant = current_volume == 0;
-    
+
     analytics.total_volume += volume_amount;
     analytics.total_fees += fee_amount;
     analytics.transaction_count += 1;
@@ -306,11 +577,10 @@ fn record_token_payment(env: &Env, token: &Address, volume_amount: i128, fee_amo
     }
     analytics.last_updated = env.ledger().timestamp();
 
-    env.storage().persistent().set(
-        &DataKey::TokenAnalytics(token.clone()),
-        &analytics,
-    );
-    
+    env.storage()
+        .persistent()
+        .set(&DataKey::TokenAnalytics(token.clone()), &analytics);
+
     env.storage().persistent().set(
         &DataKey::TokenVolume(token.clone()),
         &analytics.total_volume,
@@ -352,12 +622,12 @@ pub fn get_token_dominance_metrics(env: &Env, tokens: &Vec<Address>) -> Vec<(Add
 pub fn get_top_tokens_by_volume(env: &Env, limit: u32) -> Vec<(Address, i128)> {
     let accepted_tokens = crate::components::admin::get_accepted_tokens(env);
     let mut all_metrics = get_token_dominance_metrics(env, &accepted_tokens);
-    
+
     // Truncate to specified limit
     while all_metrics.len() > limit {
         all_metrics.pop_back();
     }
-    
+
     all_metrics
 }
 
@@ -366,51 +636,171 @@ pub fn get_token_market_share(env: &Env, token: &Address) -> i128 {
     if token_volume == 0 {
         return 0;
     }
-    
+
     let accepted_tokens = crate::components::admin::get_accepted_tokens(env);
     let mut total_volume: i128 = 0;
-    
+
     for t in accepted_tokens.iter() {
         total_volume += get_token_volume(env, &t);
     }
-    
+
     if total_volume == 0 {
         return 0;
     }
-    
+
     // Return market share as basis points (10000 = 100%)
     (token_volume * 10000) / total_volume
 }
 
-fn apply_volume_discount(fee_bps: i128, volume: i128) -> i128 {
-    let discount_percentage = if volume >= 200_000 {
-        50 // 50% discount
-    } else if volume >= 50_000 {
-        25 // 25% discount
-    } else if volume >= 10_000 {
-        10 // 10% discount
-    } else {
-        0
+// Tiers are sorted by `min_volume`, so the last one reached wins.
+fn apply_volume_discount(fee_bps: i128, volume: i128, tiers: &Vec<VolumeDiscount>) -> i128 {
+    let mut discount_bps = 0;
+    for tier in tiers.iter() {
+        if volume < tier.min_volume {
+            break;
+        }
+        discount_bps = tier.discount_bps;
+    }
+    (fee_bps * (10_000 - discount_bps)) / 10_000
+}
+
+/// Discount tiers for `token`. Tokens that never had a table set keep the
+/// tiers that used to be built in; an empty table set by the admin turns
+/// discounts off.
+pub fn get_volume_discounts(env: &Env, token: &Address) -> Vec<VolumeDiscount> {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::VolumeDiscounts(token.clone()))
+        .unwrap_or_else(|| default_volume_discounts(env))
+}
+
+// 10% off the fee from 10k volume, 25% from 50k and 50% from 200k.
+fn default_volume_discounts(env: &Env) -> Vec<VolumeDiscount> {
+    Vec::from_array(
+        env,
+        [
+            VolumeDiscount {
+                min_volume: 10_000,
+                discount_bps: 1_000,
+            },
+            VolumeDiscount {
+                min_volume: 50_000,
+                discount_bps: 2_500,
+            },
+            VolumeDiscount {
+                min_volume: 200_000,
+                discount_bps: 5_000,
+            },
+        ],
+    )
+}
+
+fn validate_volume_discounts(env: &Env, tiers: &Vec<VolumeDiscount>) {
+    if tiers.len() > MAX_VOLUME_DISCOUNT_TIERS {
+        panic_with_error!(env, FeeError::InvalidVolumeTiers);
+    }
+    let mut previous: Option<VolumeDiscount> = None;
+    for tier in tiers.iter() {
+        if tier.min_volume < 0 || tier.discount_bps <= 0 || tier.discount_bps > 10_000 {
+            panic_with_error!(env, FeeError::InvalidVolumeTiers);
+        }
+        if let Some(previous) = previous {
+            if tier.min_volume <= previous.min_volume || tier.discount_bps < previous.discount_bps {
+                panic_with_error!(env, FeeError::InvalidVolumeTiers);
+            }
+        }
+        previous = Some(tier);
+    }
+}
+
+/// Queues a new discount tier table for `token`, replacing the current one
+/// once `FEE_UPDATE_DELAY` has passed. An empty table removes all discounts.
+pub fn propose_volume_discounts(
+    env: &Env,
+    admin: &Address,
+    token: &Address,
+    tiers: &Vec<VolumeDiscount>,
+) {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
+
+    if !is_accepted_token(env, token) {
+        panic_with_error!(env, ContractError::TokenNotAccepted);
+    }
+    validate_volume_discounts(env, tiers);
+
+    let pending = PendingVolumeDiscounts {
+        token: token.clone(),
+        tiers: tiers.clone(),
+        proposed_at: env.ledger().timestamp(),
     };
+    env.storage()
+        .persistent()
+        .set(&FeeDataKey::PendingVolumeDiscounts(token.clone()), &pending);
+
+    events::publish_volume_tiers_proposed_event(
+        env,
+        admin.clone(),
+        token.clone(),
+        tiers.clone(),
+        env.ledger().timestamp(),
+    );
+    reentrancy::exit(env);
+}
+
+pub fn execute_volume_discounts(env: &Env, admin: &Address, token: &Address) {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
 
-    if discount_percentage == 0 {
-        fee_bps
-    } else {
-        (fee_bps * (100 - discount_percentage)) / 100
+    let pending = get_pending_volume_discounts(env, token);
+    let elapsed = env.ledger().timestamp() - pending.proposed_at;
+    if elapsed < FEE_UPDATE_DELAY {
+        panic_with_error!(env, ContractError::FeeUpdateTooEarly);
     }
+
+    env.storage()
+        .persistent()
+        .set(&FeeDataKey::VolumeDiscounts(token.clone()), &pending.tiers);
+    env.storage()
+        .persistent()
+        .remove(&FeeDataKey::PendingVolumeDiscounts(token.clone()));
+
+    events::publish_volume_tiers_set_event(
+        env,
+        admin.clone(),
+        token.clone(),
+        pending.tiers,
+        env.ledger().timestamp(),
+    );
+    reentrancy::exit(env);
 }
 
+pub fn get_pending_volume_discounts(env: &Env, token: &Address) -> PendingVolumeDiscounts {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::PendingVolumeDiscounts(token.clone()))
+        .unwrap_or_else(|| panic_with_error!(env, ContractError::NoPendingFeeUpdate))
+}
+
+/// Proposes a plain `fee` bps rate; see `propose_fee_schedule`.
 pub fn propose_fee(env: &Env, admin: &Address, token: &Address, fee: i128) {
+    propose_fee_schedule(env, admin, token, &percentage_fee(fee));
+}
+
+/// Queues a new fee schedule for `token`; `execute_fee` applies it once
+/// `FEE_UPDATE_DELAY` has passed. A newer proposal replaces a pending one.
+pub fn propose_fee_schedule(env: &Env, admin: &Address, token: &Address, schedule: &FeeSchedule) {
     reentrancy::enter(env);
     core::assert_admin(env, admin);
 
     if !is_accepted_token(env, token) {
         panic_with_error!(env, ContractError::TokenNotAccepted);
     }
+    validate_fee_schedule(env, schedule);
 
     let pending = PendingFee {
         token: token.clone(),
-        fee,
+        schedule: schedule.clone(),
         proposed_at: env.ledger().timestamp(),
     };
 
@@ -422,7 +812,7 @@ pub fn propose_fee(env: &Env, admin: &Address, token: &Address, fee: i128) {
         env,
         admin.clone(),
         token.clone(),
-        fee,
+        schedule,
         env.ledger().timestamp(),
     );
     reentrancy::exit(env);
@@ -445,7 +835,7 @@ pub fn execute_fee(env: &Env, admin: &Address, token: &Address) {
 
     env.storage()
         .persistent()
-        .set(&DataKey::TokenFee(token.clone()), &pending.fee);
+        .set(&FeeDataKey::FeeSchedule(token.clone()), &pending.schedule);
 
     env.storage()
         .persistent()
@@ -455,7 +845,7 @@ pub fn execute_fee(env: &Env, admin: &Address, token: &Address) {
         env,
         admin.clone(),
         token.clone(),
-        pending.fee,
+        &pending.schedule,
         env.ledger().timestamp(),
     );
     reentrancy::exit(env);
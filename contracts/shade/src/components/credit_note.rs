@@ -0,0 +1,256 @@
+use crate::components::{admin, invoice, merchant, split_bill};
+use crate::errors::{ContractError, InvoiceError};
+use crate::events;
+use crate::types::{
+    CreditNote, CreditNoteStatus, CreditRedemption, DataKey, Invoice, InvoiceDataKey, InvoiceStatus,
+};
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+/// Issues store credit to `customer`. Credit issued against one of the
+/// merchant's paid invoices must be in the token it was paid in and is taken
+/// out of what can still be refunded on it.
+pub fn issue_credit_note(
+    env: &Env,
+    merchant_address: &Address,
+    customer: &Address,
+    token: &Address,
+    amount: i128,
+    invoice_id: Option<u64>,
+    expires_at: Option<u64>,
+) -> u64 {
+    merchant_address.require_auth();
+    if !merchant::is_merchant(env, merchant_address) {
+        panic_with_error!(env, ContractError::MerchantNotFound);
+    }
+    if amount <= 0 {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+    if !admin::is_accepted_token(env, token) {
+        panic_with_error!(env, ContractError::TokenNotAccepted);
+    }
+    let now = env.ledger().timestamp();
+    if expires_at.is_some_and(|expires_at| expires_at <= now) {
+        panic_with_error!(env, InvoiceError::CreditNoteExpired);
+    }
+
+    let merchant_id = merchant::get_merchant_id(env, merchant_address);
+    if let Some(invoice_id) = invoice_id {
+        let mut invoice = invoice::get_invoice(env, invoice_id);
+        check_credit_invoice(env, &invoice, merchant_id, customer, token, amount);
+        invoice.credit_issued += amount;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Invoice(invoice_id), &invoice);
+    }
+
+    let credit_note_id: u64 = env
+        .storage()
+        .persistent()
+        .get(&InvoiceDataKey::CreditNoteCount)
+        .unwrap_or(0)
+        + 1;
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::CreditNoteCount, &credit_note_id);
+
+    let credit_note = CreditNote {
+        id: credit_note_id,
+        merchant_id,
+        merchant: merchant_address.clone(),
+        customer: customer.clone(),
+        token: token.clone(),
+        amount,
+        amount_redeemed: 0,
+        invoice_id,
+        expires_at,
+        issued_at: now,
+        status: CreditNoteStatus::Active,
+    };
+    save_credit_note(env, &credit_note);
+
+    events::publish_credit_note_issued_event(
+        env,
+        credit_note_id,
+        merchant_address.clone(),
+        customer.clone(),
+        invoice_id,
+        amount,
+        token.clone(),
+        expires_at,
+        now,
+    );
+    credit_note_id
+}
+
+fn check_credit_invoice(
+    env: &Env,
+    invoice: &Invoice,
+    merchant_id: u64,
+    customer: &Address,
+    token: &Address,
+    amount: i128,
+) {
+    if invoice.merchant_id != merchant_id {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+    if invoice.status != InvoiceStatus::Paid && invoice.status != InvoiceStatus::PartiallyRefunded {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+    let paid_by_customer = if invoice.split_bill {
+        split_bill::get_invoice_contributions(env, invoice.id)
+            .iter()
+            .any(|contribution| contribution.payer == *customer)
+    } else {
+        invoice.payer.as_ref() == Some(customer)
+    };
+    if !paid_by_customer || invoice::settlement_token(invoice) != *token {
+        panic_with_error!(env, InvoiceError::CreditNoteMismatch);
+    }
+    if amount > invoice::refundable_amount(invoice) {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+}
+
+pub fn get_credit_note(env: &Env, credit_note_id: u64) -> CreditNote {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::CreditNote(credit_note_id))
+        .unwrap_or_else(|| panic_with_error!(env, InvoiceError::CreditNoteNotFound))
+}
+
+/// Cancels whatever credit is left on the note.
+pub fn void_credit_note(env: &Env, merchant_address: &Address, credit_note_id: u64) {
+    merchant_address.require_auth();
+    let mut credit_note = get_credit_note(env, credit_note_id);
+    if credit_note.merchant != *merchant_address {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+    if credit_note.status != CreditNoteStatus::Active {
+        panic_with_error!(env, InvoiceError::InvalidCreditNoteStatus);
+    }
+    credit_note.status = CreditNoteStatus::Voided;
+    save_credit_note(env, &credit_note);
+
+    events::publish_credit_note_voided_event(
+        env,
+        credit_note_id,
+        merchant_address.clone(),
+        credit_note.amount - credit_note.amount_redeemed,
+        env.ledger().timestamp(),
+    );
+}
+
+/// Draws up to `amount` from the note towards `invoice` for `customer` and
+/// returns how much was applied. The caller settles the rest in tokens.
+pub fn redeem_credit_note(
+    env: &Env,
+    credit_note_id: u64,
+    customer: &Address,
+    invoice: &Invoice,
+    amount: i128,
+) -> i128 {
+    let mut credit_note = get_credit_note(env, credit_note_id);
+    if credit_note.status != CreditNoteStatus::Active {
+        panic_with_error!(env, InvoiceError::InvalidCreditNoteStatus);
+    }
+    if credit_note
+        .expires_at
+        .is_some_and(|expires_at| env.ledger().timestamp() >= expires_at)
+    {
+        panic_with_error!(env, InvoiceError::CreditNoteExpired);
+    }
+    if credit_note.customer != *customer {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+    if credit_note.merchant_id != invoice.merchant_id || credit_note.token != invoice.token {
+        panic_with_error!(env, InvoiceError::CreditNoteMismatch);
+    }
+
+    let applied = amount.min(credit_note.amount - credit_note.amount_redeemed);
+    credit_note.amount_redeemed += applied;
+    if credit_note.amount_redeemed == credit_note.amount {
+        credit_note.status = CreditNoteStatus::Redeemed;
+    }
+    save_credit_note(env, &credit_note);
+    admin::record_merchant_credit(env, &credit_note.merchant, &credit_note.token, applied);
+    record_redemption(env, invoice.id, credit_note_id, applied);
+
+    events::publish_credit_note_redeemed_event(
+        env,
+        credit_note_id,
+        invoice.id,
+        customer.clone(),
+        applied,
+        credit_note.amount - credit_note.amount_redeemed,
+        env.ledger().timestamp(),
+    );
+    applied
+}
+
+fn invoice_redemptions(env: &Env, invoice_id: u64) -> Vec<CreditRedemption> {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::InvoiceCreditRedemptions(invoice_id))
+        .unwrap_or(Vec::new(env))
+}
+
+fn record_redemption(env: &Env, invoice_id: u64, credit_note_id: u64, amount: i128) {
+    let mut redemptions = invoice_redemptions(env, invoice_id);
+    match redemptions
+        .iter()
+        .position(|redemption| redemption.credit_note_id == credit_note_id)
+    {
+        Some(index) => {
+            let mut redemption = redemptions.get_unchecked(index as u32);
+            redemption.amount += amount;
+            redemptions.set(index as u32, redemption);
+        }
+        None => redemptions.push_back(CreditRedemption {
+            credit_note_id,
+            amount,
+        }),
+    }
+    env.storage().persistent().set(
+        &InvoiceDataKey::InvoiceCreditRedemptions(invoice_id),
+        &redemptions,
+    );
+}
+
+/// Puts credit redeemed on `invoice` back on the notes it came from, for an
+/// invoice that is being unwound. Fully redeemed notes become active again;
+/// voided notes stay voided. Returns the total credit restored.
+pub fn restore_invoice_credit(env: &Env, invoice: &Invoice) -> i128 {
+    let mut restored = 0;
+    for redemption in invoice_redemptions(env, invoice.id).iter() {
+        let mut credit_note = get_credit_note(env, redemption.credit_note_id);
+        credit_note.amount_redeemed -= redemption.amount;
+        if credit_note.status == CreditNoteStatus::Redeemed {
+            credit_note.status = CreditNoteStatus::Active;
+        }
+        save_credit_note(env, &credit_note);
+        admin::record_merchant_credit(
+            env,
+            &credit_note.merchant,
+            &credit_note.token,
+            -redemption.amount,
+        );
+        events::publish_credit_note_restored_event(
+            env,
+            credit_note.id,
+            invoice.id,
+            redemption.amount,
+            env.ledger().timestamp(),
+        );
+        restored += redemption.amount;
+    }
+    env.storage()
+        .persistent()
+        .remove(&InvoiceDataKey::InvoiceCreditRedemptions(invoice.id));
+    restored
+}
+
+fn save_credit_note(env: &Env, credit_note: &CreditNote) {
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::CreditNote(credit_note.id), credit_note);
+}
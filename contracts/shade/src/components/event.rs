@@ -1,4 +1,4 @@
-use crate::components::{admin, merchant};
+use crate::components::{admin, merchant, referral, treasury};
 use crate::errors::ContractError;
 use crate::events;
 use crate::types::{DataKey, Event, Merchant, Ticket};
@@ -115,22 +115,23 @@ pub fn purchase_ticket(env: &Env, event_id: &u64, buyer: &Address) -> u64 {
 
     let merchant_address = merchant_id_to_address(env, event.merchant_id);
     let merchant_account = merchant::get_merchant_account(env, event.merchant_id);
-    let platform_account = admin::get_platform_account(env);
 
     let amount = event.ticket_price;
-    let fee = admin::calculate_fee(env, &merchant_address, &event.token, amount);
+    let fee = admin::charge_fee(env, &merchant_address, &event.token, amount);
     if fee < 0 || fee >= amount {
         panic_with_error!(env, ContractError::InvalidAmount);
     }
     let merchant_amount = amount - fee;
 
-    let token_client = token::TokenClient::new(env, &event.token);
-    token_client.transfer(buyer, &merchant_account, &merchant_amount);
-    if fee > 0 {
-        token_client.transfer(buyer, &platform_account, &fee);
+    let mut payouts = Vec::from_array(env, [(merchant_account, merchant_amount)]);
+    let (platform_fee, referral_share) =
+        referral::split_platform_fee(env, &merchant_address, &event.token, fee);
+    if let Some(referral_share) = referral_share {
+        payouts.push_back(referral_share);
     }
+    treasury::route_payment(env, &event.token, buyer, false, platform_fee, &payouts);
 
-    admin::record_merchant_payment(env, &merchant_address, &event.token, amount, fee);
+    admin::record_merchant_payment(env, &merchant_address, &event.token, amount, fee, 0);
 
     let new_ticket_id = env
         .storage()
@@ -195,7 +196,9 @@ pub fn resell_ticket(
     ticket_id: u64,
     resale_price: i128,
 ) {
+    // The buyer pays the resale price, so both sides must sign.
     seller.require_auth();
+    buyer.require_auth();
 
     if resale_price <= 0 {
         panic_with_error!(env, ContractError::InvalidResalePrice);
@@ -377,5 +380,7 @@ pub fn purchase_tickets_bulk(
     token_client.transfer(buyer, merchant_account, &net);
 
     event.sold = event.sold.saturating_add(quantity);
-    env.storage().persistent().set(&DataKey::Event(*event_id), &event);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Event(*event_id), &event);
 }
@@ -0,0 +1,100 @@
+use crate::components::{access_control, admin, merchant};
+use crate::errors::{ContractError, FeeError};
+use crate::events;
+use crate::types::{FeeDataKey, MerchantFeeOverride, Role};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+fn assert_fee_manager(env: &Env, caller: &Address) {
+    caller.require_auth();
+    if !access_control::has_role(env, caller, Role::Manager) {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+}
+
+/// Gives `merchant` negotiated pricing in `token`, replacing any earlier
+/// override. Open to the admin and to holders of `Role::Manager`.
+pub fn set_merchant_fee_override(
+    env: &Env,
+    caller: &Address,
+    merchant_address: &Address,
+    token: &Address,
+    fee_override: &MerchantFeeOverride,
+) {
+    assert_fee_manager(env, caller);
+    if !merchant::is_merchant(env, merchant_address) {
+        panic_with_error!(env, ContractError::MerchantNotFound);
+    }
+    if !admin::is_accepted_token(env, token) {
+        panic_with_error!(env, ContractError::TokenNotAccepted);
+    }
+    admin::validate_fee_schedule(env, &fee_override.schedule);
+    if fee_override
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= fee_override.effective_from)
+    {
+        panic_with_error!(env, FeeError::InvalidFeeOverride);
+    }
+
+    env.storage().persistent().set(
+        &FeeDataKey::MerchantFeeOverride(merchant_address.clone(), token.clone()),
+        fee_override,
+    );
+    events::publish_fee_override_set_event(
+        env,
+        merchant_address.clone(),
+        token.clone(),
+        caller.clone(),
+        fee_override.effective_from,
+        fee_override.expires_at,
+        env.ledger().timestamp(),
+    );
+}
+
+pub fn remove_merchant_fee_override(
+    env: &Env,
+    caller: &Address,
+    merchant_address: &Address,
+    token: &Address,
+) {
+    assert_fee_manager(env, caller);
+    let key = FeeDataKey::MerchantFeeOverride(merchant_address.clone(), token.clone());
+    if !env.storage().persistent().has(&key) {
+        panic_with_error!(env, FeeError::InvalidFeeOverride);
+    }
+    env.storage().persistent().remove(&key);
+    events::publish_fee_override_removed_event(
+        env,
+        merchant_address.clone(),
+        token.clone(),
+        caller.clone(),
+        env.ledger().timestamp(),
+    );
+}
+
+pub fn get_merchant_fee_override(
+    env: &Env,
+    merchant_address: &Address,
+    token: &Address,
+) -> Option<MerchantFeeOverride> {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::MerchantFeeOverride(
+            merchant_address.clone(),
+            token.clone(),
+        ))
+}
+
+/// The merchant's override for `token` if it is in force right now.
+pub fn active_fee_override(
+    env: &Env,
+    merchant_address: &Address,
+    token: &Address,
+) -> Option<MerchantFeeOverride> {
+    let now = env.ledger().timestamp();
+    get_merchant_fee_override(env, merchant_address, token).filter(|fee_override| {
+        now >= fee_override.effective_from
+            && fee_override
+                .expires_at
+                .is_none_or(|expires_at| now < expires_at)
+    })
+}
@@ -0,0 +1,188 @@
+use crate::components::{invoice, merchant};
+use crate::errors::{ContractError, InvoiceError};
+use crate::events;
+use crate::types::{
+    DataKey, Installment, InstallmentPlan, Invoice, InvoiceDataKey, InvoicePricingMode,
+    InvoiceStatus, PaymentTermsData,
+};
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+pub const MAX_INSTALLMENTS: u32 = 24;
+
+/// Attaches an installment plan to an invoice that has not received any
+/// payment yet. The amounts must add up to the invoice total and the due
+/// dates must be in the future and strictly increasing. Passing an empty
+/// schedule removes the plan.
+pub fn set_invoice_installments(
+    env: &Env,
+    merchant_address: &Address,
+    invoice_id: u64,
+    installments: &Vec<Installment>,
+) {
+    merchant_address.require_auth();
+
+    let mut invoice = invoice::get_invoice(env, invoice_id);
+    let merchant_id = merchant::get_merchant_id(env, merchant_address);
+    if invoice.merchant_id != merchant_id {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+
+    if (invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Draft)
+        || invoice.amount_paid > 0
+    {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+
+    let key = InvoiceDataKey::InvoiceInstallments(invoice_id);
+    if installments.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        validate_schedule(env, &invoice, installments);
+        let plan = InstallmentPlan {
+            installments: installments.clone(),
+            paid_count: 0,
+            overdue: false,
+        };
+        env.storage().persistent().set(&key, &plan);
+    }
+
+    let (amount, description) = (invoice.amount, invoice.description.clone());
+    invoice::record_revision(env, &mut invoice, merchant_address, amount, description);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+
+    events::publish_invoice_installments_set_event(
+        env,
+        invoice_id,
+        merchant_id,
+        installments.len(),
+        env.ledger().timestamp(),
+    );
+}
+
+fn validate_schedule(env: &Env, invoice: &Invoice, installments: &Vec<Installment>) {
+    // A fiat invoice's token amount moves with the oracle price, and payment
+    // terms move the total at payment time, so a fixed schedule could never
+    // be relied on to add up.
+    if invoice.pricing_mode == InvoicePricingMode::FixedFiat
+        || installments.len() > MAX_INSTALLMENTS
+        || invoice.payment_terms != PaymentTermsData::None
+    {
+        panic_with_error!(env, InvoiceError::InvalidInstallmentSchedule);
+    }
+
+    let mut total: i128 = 0;
+    let mut previous_due_date = env.ledger().timestamp();
+    for installment in installments.iter() {
+        if installment.amount <= 0 || installment.due_date <= previous_due_date {
+            panic_with_error!(env, InvoiceError::InvalidInstallmentSchedule);
+        }
+        total = total
+            .checked_add(installment.amount)
+            .unwrap_or_else(|| panic_with_error!(env, InvoiceError::InvalidInstallmentSchedule));
+        previous_due_date = installment.due_date;
+    }
+
+    if total != invoice.amount {
+        panic_with_error!(env, InvoiceError::InvalidInstallmentSchedule);
+    }
+}
+
+pub fn get_invoice_installments(env: &Env, invoice_id: u64) -> InstallmentPlan {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::InvoiceInstallments(invoice_id))
+        .unwrap_or_else(|| panic_with_error!(env, InvoiceError::InstallmentPlanNotFound))
+}
+
+pub fn has_installment_plan(env: &Env, invoice_id: u64) -> bool {
+    env.storage()
+        .persistent()
+        .has(&InvoiceDataKey::InvoiceInstallments(invoice_id))
+}
+
+/// Changing an invoice's total would leave its plan out of step, so the plan
+/// has to be cleared before the invoice is amended.
+pub fn assert_no_installment_plan(env: &Env, invoice_id: u64) {
+    if has_installment_plan(env, invoice_id) {
+        panic_with_error!(env, InvoiceError::InvalidInstallmentSchedule);
+    }
+}
+
+/// Checks a payment against the invoice's plan, if it has one. Each payment
+/// must at least cover whatever is left of the next unpaid installment.
+/// `amount_paid` is the invoice total paid once the payment is applied.
+pub fn record_installment_payment(env: &Env, invoice: &Invoice, amount_paid: i128) {
+    let key = InvoiceDataKey::InvoiceInstallments(invoice.id);
+    let mut plan: InstallmentPlan = match env.storage().persistent().get(&key) {
+        Some(plan) => plan,
+        None => return,
+    };
+
+    let mut covered: i128 = 0;
+    let mut paid_count = 0;
+    for installment in plan.installments.iter() {
+        if covered + installment.amount > amount_paid {
+            break;
+        }
+        covered += installment.amount;
+        paid_count += 1;
+    }
+
+    if paid_count <= plan.paid_count {
+        panic_with_error!(env, InvoiceError::InstallmentUnderpaid);
+    }
+
+    plan.paid_count = paid_count;
+    refresh_overdue(env, invoice, &mut plan, amount_paid);
+    env.storage().persistent().set(&key, &plan);
+}
+
+/// Flags the invoice overdue if its next unpaid installment is past due.
+/// Anyone may call this. Returns the invoice's current overdue state.
+pub fn check_installments_overdue(env: &Env, invoice_id: u64) -> bool {
+    let key = InvoiceDataKey::InvoiceInstallments(invoice_id);
+    let mut plan = get_invoice_installments(env, invoice_id);
+
+    let invoice = invoice::get_invoice(env, invoice_id);
+    if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::PartiallyPaid {
+        return plan.overdue;
+    }
+
+    let was_overdue = plan.overdue;
+    refresh_overdue(env, &invoice, &mut plan, invoice.amount_paid);
+    if plan.overdue != was_overdue {
+        env.storage().persistent().set(&key, &plan);
+    }
+    plan.overdue
+}
+
+fn refresh_overdue(env: &Env, invoice: &Invoice, plan: &mut InstallmentPlan, amount_paid: i128) {
+    let now = env.ledger().timestamp();
+    let next = match plan.installments.get(plan.paid_count) {
+        Some(next) if next.due_date < now => next,
+        _ => {
+            plan.overdue = false;
+            return;
+        }
+    };
+
+    if !plan.overdue {
+        plan.overdue = true;
+
+        let mut due_through_next: i128 = 0;
+        for installment in plan.installments.iter().take(plan.paid_count as usize + 1) {
+            due_through_next += installment.amount;
+        }
+        events::publish_installment_overdue_event(
+            env,
+            invoice.id,
+            invoice.merchant_id,
+            plan.paid_count,
+            next.due_date,
+            due_through_next - amount_paid,
+            now,
+        );
+    }
+}
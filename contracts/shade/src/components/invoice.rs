@@ -1,12 +1,17 @@
-use crate::components::{access_control, admin, history, merchant, signature_util};
-use crate::errors::ContractError;
+use crate::components::{
+    access_control, admin, credit_note, history, installments, invoice_index, merchant,
+    payment_terms, referral, refund_policy, signature_util, split_bill, tax, tips, treasury,
+};
+use crate::errors::{ContractError, InvoiceError};
 use crate::events;
 use crate::types::{
-    DataKey, FiatPricing, FiatPricingData, Invoice, InvoiceFilter, InvoicePricingMode,
-    InvoiceStatus, Role, Transaction, TransactionType,
+    DataKey, FiatPricing, FiatPricingData, FiatQuote, Invoice, InvoiceDataKey, InvoiceFilter,
+    InvoiceIndexKey, InvoiceOptions, InvoicePricingMode, InvoiceRevision, InvoiceStatus,
+    InvoiceTemplate, LineItem, OracleConfig, PaymentTermsData, Role, SignedInvoicePayload,
+    Transaction, TransactionType,
 };
 use soroban_sdk::token::TokenClient;
-use soroban_sdk::{contractclient, panic_with_error, token, Address, BytesN, Env, String, Vec};
+use soroban_sdk::{contractclient, panic_with_error, Address, BytesN, Env, String, Vec};
 
 #[contractclient(name = "MerchantAccountRefundClient")]
 pub trait MerchantAccountRefund {
@@ -18,7 +23,10 @@ pub trait PriceOracle {
     fn get_price(env: Env, token: Address, quote_currency: String) -> i128;
 }
 
-pub const MAX_REFUND_DURATION: u64 = 604_800; // 7 days
+pub const MAX_LINE_ITEMS: u32 = 50;
+pub const MAX_SKU_LENGTH: u32 = 32;
+pub const MAX_EXTERNAL_REF_LEN: u32 = 64;
+const MAX_BPS: u32 = 10_000;
 
 fn scale_factor(decimals: u32) -> i128 {
     let mut factor = 1i128;
@@ -28,19 +36,21 @@ fn scale_factor(decimals: u32) -> i128 {
     factor
 }
 
-fn resolve_fiat_invoice_amount(env: &Env, invoice: &Invoice) -> i128 {
-    let fiat_pricing = match invoice.fiat_pricing.clone() {
-        FiatPricingData::Some(fp) => fp,
-        FiatPricingData::None => panic_with_error!(env, ContractError::OraclePriceUnavailable),
-    };
-    let oracle_config = admin::get_token_oracle(env, &invoice.token);
+fn oracle_price(env: &Env, token: &Address, currency: &String) -> (i128, OracleConfig) {
+    let oracle_config = admin::get_token_oracle(env, token);
     let oracle_client = PriceOracleClient::new(env, &oracle_config.contract);
-    let price = oracle_client.get_price(&invoice.token, &fiat_pricing.currency);
+    let price = oracle_client.get_price(token, currency);
 
     if price <= 0 {
         panic_with_error!(env, ContractError::OraclePriceUnavailable);
     }
 
+    (price, oracle_config)
+}
+
+fn fiat_amount_in_token(env: &Env, fiat_pricing: &FiatPricing, token: &Address) -> i128 {
+    let (price, oracle_config) = oracle_price(env, token, &fiat_pricing.currency);
+
     let numerator = fiat_pricing.amount
         * scale_factor(oracle_config.token_decimals)
         * scale_factor(oracle_config.price_decimals);
@@ -54,24 +64,255 @@ fn resolve_fiat_invoice_amount(env: &Env, invoice: &Invoice) -> i128 {
     resolved_amount
 }
 
-fn refresh_fiat_invoice_quote(env: &Env, invoice: &mut Invoice) {
+fn resolve_fiat_invoice_amount(env: &Env, invoice: &Invoice) -> i128 {
+    match &invoice.fiat_pricing {
+        FiatPricingData::Some(fiat_pricing) => {
+            fiat_amount_in_token(env, fiat_pricing, &invoice.token)
+        }
+        FiatPricingData::None => panic_with_error!(env, ContractError::OraclePriceUnavailable),
+    }
+}
+
+/// Converts `amount` of `from` into `to` by valuing both tokens in
+/// `quote_currency` through their configured oracles.
+fn convert_token_amount(
+    env: &Env,
+    amount: i128,
+    from: &Address,
+    to: &Address,
+    quote_currency: &String,
+) -> i128 {
+    let (from_price, from_config) = oracle_price(env, from, quote_currency);
+    let (to_price, to_config) = oracle_price(env, to, quote_currency);
+
+    let numerator = amount
+        .checked_mul(from_price)
+        .and_then(|value| value.checked_mul(scale_factor(to_config.token_decimals)))
+        .and_then(|value| value.checked_mul(scale_factor(to_config.price_decimals)))
+        .unwrap_or_else(|| panic_with_error!(env, ContractError::InvalidAmount));
+    let denominator = to_price
+        .checked_mul(scale_factor(from_config.token_decimals))
+        .and_then(|value| value.checked_mul(scale_factor(from_config.price_decimals)))
+        .unwrap_or_else(|| panic_with_error!(env, ContractError::InvalidAmount));
+    let converted = numerator / denominator;
+
+    if converted <= 0 {
+        panic_with_error!(env, ContractError::OraclePriceUnavailable);
+    }
+
+    converted
+}
+
+/// Token the invoice was actually paid in. Refunds are made in this token.
+pub fn settlement_token(invoice: &Invoice) -> Address {
+    invoice
+        .paid_token
+        .clone()
+        .unwrap_or_else(|| invoice.token.clone())
+}
+
+/// Total paid in `settlement_token`, which refunds are capped at. Amounts
+/// settled with credit, or compensated by issuing credit, are not refundable.
+fn settlement_amount(invoice: &Invoice) -> i128 {
+    let paid = match invoice.paid_token {
+        Some(_) => invoice.amount_paid,
+        None => invoice.amount,
+    };
+    paid - invoice.credit_applied - invoice.credit_issued
+}
+
+/// Approximate ledger close time, used to turn the quote window into a TTL.
+const LEDGER_CLOSE_SECONDS: u64 = 5;
+
+/// Pre-tax amount in `token` that `payer` locked in with `lock_fiat_quote`,
+/// if the lock was taken for that token and has not lapsed yet.
+fn locked_fiat_quote(env: &Env, invoice_id: u64, payer: &Address, token: &Address) -> Option<i128> {
+    let quote: FiatQuote = env
+        .storage()
+        .temporary()
+        .get(&InvoiceDataKey::FiatQuote(invoice_id, payer.clone()))?;
+    if env.ledger().timestamp() > quote.valid_until || quote.token != *token {
+        return None;
+    }
+    Some(quote.amount)
+}
+
+fn refresh_fiat_invoice_quote(env: &Env, invoice: &mut Invoice, payer: &Address) {
     if invoice.pricing_mode != InvoicePricingMode::FixedFiat || invoice.amount_paid > 0 {
         return;
     }
 
-    let resolved_amount = resolve_fiat_invoice_amount(env, invoice);
-    invoice.amount = resolved_amount;
+    let locked_amount = locked_fiat_quote(env, invoice.id, payer, &invoice.token);
+    let resolved_amount =
+        locked_amount.unwrap_or_else(|| resolve_fiat_invoice_amount(env, invoice));
+    invoice.subtotal = resolved_amount;
+    recompute_invoice_totals(env, invoice);
     env.storage()
         .persistent()
         .set(&DataKey::Invoice(invoice.id), &*invoice);
 
+    // Locked quotes were announced when they were taken, and are used up by
+    // the first payment.
+    if locked_amount.is_some() {
+        env.storage()
+            .temporary()
+            .remove(&InvoiceDataKey::FiatQuote(invoice.id, payer.clone()));
+    } else {
+        events::publish_fiat_invoice_priced_event(
+            env,
+            invoice.id,
+            invoice.token.clone(),
+            resolved_amount,
+            None,
+            env.ledger().timestamp(),
+        );
+    }
+}
+
+/// Pins the current oracle price of a pending fiat invoice for `payer` for
+/// the admin-configured quote window, in `pay_token` or else the invoice
+/// token. Returns the amount due in that token at the locked price.
+pub fn lock_fiat_quote(
+    env: &Env,
+    payer: &Address,
+    invoice_id: u64,
+    pay_token: Option<Address>,
+) -> i128 {
+    payer.require_auth();
+
+    let invoice = get_invoice(env, invoice_id);
+    check_invoice_payable(env, &invoice, payer);
+    if invoice.pricing_mode != InvoicePricingMode::FixedFiat || invoice.amount_paid > 0 {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+
+    let mut priced = invoice.clone();
+    if let Some(pay_token) = pay_token {
+        check_pay_token(env, &invoice, &pay_token);
+        priced.token = pay_token;
+    }
+    let window = admin::get_fiat_quote_window(env);
+    let quote = FiatQuote {
+        token: priced.token.clone(),
+        amount: resolve_fiat_invoice_amount(env, &priced),
+        valid_until: env.ledger().timestamp() + window,
+    };
+    // A quote is worthless once its window closes, so it is kept in temporary
+    // storage and left to lapse with the window.
+    let key = InvoiceDataKey::FiatQuote(invoice_id, payer.clone());
+    let ttl = window.div_ceil(LEDGER_CLOSE_SECONDS) as u32;
+    env.storage().temporary().set(&key, &quote);
+    env.storage().temporary().extend_ttl(&key, ttl, ttl);
+
     events::publish_fiat_invoice_priced_event(
         env,
-        invoice.id,
-        invoice.token.clone(),
-        resolved_amount,
+        invoice_id,
+        quote.token.clone(),
+        quote.amount,
+        Some(quote.valid_until),
         env.ledger().timestamp(),
     );
+
+    priced.subtotal = quote.amount;
+    recompute_invoice_totals(env, &mut priced);
+    apply_payment_terms(env, &mut priced, true);
+    priced.amount
+}
+
+/// Validates the line items and returns `(subtotal, tax)`. Tax is computed per
+/// line as `quantity * unit_price * tax_bps / 10_000`, rounded down.
+pub fn compute_line_item_totals(env: &Env, line_items: &Vec<LineItem>) -> (i128, i128) {
+    if line_items.is_empty() || line_items.len() > MAX_LINE_ITEMS {
+        panic_with_error!(env, InvoiceError::InvalidLineItems);
+    }
+
+    let mut subtotal: i128 = 0;
+    let mut tax: i128 = 0;
+    for item in line_items.iter() {
+        if item.quantity == 0 || item.unit_price <= 0 || item.tax_bps > MAX_BPS {
+            panic_with_error!(env, InvoiceError::InvalidLineItems);
+        }
+        if item.sku.is_empty() || item.sku.len() > MAX_SKU_LENGTH {
+            panic_with_error!(env, InvoiceError::InvalidLineItems);
+        }
+
+        let line_total = item
+            .unit_price
+            .checked_mul(item.quantity as i128)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::InvalidAmount));
+        let line_tax = line_total
+            .checked_mul(item.tax_bps as i128)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::InvalidAmount))
+            / MAX_BPS as i128;
+
+        subtotal = subtotal
+            .checked_add(line_total)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::InvalidAmount));
+        tax = tax
+            .checked_add(line_tax)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::InvalidAmount));
+    }
+
+    (subtotal, tax)
+}
+
+/// Recomputes `tax_amount` and `amount` from `subtotal`, the line items and the
+/// invoice-level tax rate. Itemized invoices also get their subtotal rebuilt.
+fn recompute_invoice_totals(env: &Env, invoice: &mut Invoice) {
+    let mut tax_amount = 0;
+    if !invoice.line_items.is_empty() {
+        let (subtotal, line_tax) = compute_line_item_totals(env, &invoice.line_items);
+        invoice.subtotal = subtotal;
+        tax_amount = line_tax;
+    }
+
+    let invoice_tax = invoice
+        .subtotal
+        .checked_mul(invoice.tax_rate_bps as i128)
+        .unwrap_or_else(|| panic_with_error!(env, ContractError::InvalidAmount))
+        / MAX_BPS as i128;
+    invoice.tax_amount = tax_amount + invoice_tax;
+    invoice.amount = invoice
+        .subtotal
+        .checked_add(invoice.tax_amount)
+        .unwrap_or_else(|| panic_with_error!(env, ContractError::InvalidAmount));
+}
+
+/// Resolves the invoice's discount and penalty terms for a payment made now.
+/// The discount is decided by the first payment and only earned if that
+/// payment settles the whole invoice. The penalty is charged once, by the
+/// first payment made after the due date, on the part of the invoice still
+/// outstanding then, so paying a little early does not escape it.
+fn apply_payment_terms(env: &Env, invoice: &mut Invoice, settles_in_full: bool) {
+    if invoice.payment_terms == PaymentTermsData::None {
+        return;
+    }
+
+    let (discount, penalty) = payment_terms::resolve_adjustment(env, invoice);
+    if invoice.amount_paid == 0 {
+        invoice.discount_amount = if settles_in_full { discount } else { 0 };
+        invoice.penalty_amount = penalty;
+        invoice.amount = invoice.subtotal + invoice.tax_amount - invoice.discount_amount + penalty;
+    } else if invoice.penalty_amount == 0 && penalty > 0 {
+        // A partially paid invoice never carries a discount, so `amount` is
+        // still subtotal plus tax here.
+        invoice.penalty_amount = penalty * (invoice.amount - invoice.amount_paid) / invoice.amount;
+        invoice.amount += invoice.penalty_amount;
+    }
+}
+
+/// Share of `tax_amount` carried by a payment of `amount` on top of
+/// `amount_paid`. Computed on the cumulative total, leaving out any penalty,
+/// so the portions of a partially paid invoice always add up to its full tax
+/// even when a penalty is added part way through.
+fn tax_portion(invoice: &Invoice, amount: i128) -> i128 {
+    if invoice.tax_amount == 0 {
+        return 0;
+    }
+    let taxed_amount = invoice.amount - invoice.penalty_amount;
+    let tax_through =
+        |paid: i128| (paid * invoice.tax_amount / taxed_amount).min(invoice.tax_amount);
+    tax_through(invoice.amount_paid + amount) - tax_through(invoice.amount_paid)
 }
 
 pub fn validate_invoice_creation(
@@ -121,40 +362,26 @@ pub fn validate_invoice_creation(
     }
 }
 
-pub fn create_invoice(
+/// Builds a pending, fixed-crypto invoice with no payments recorded. The ID is
+/// assigned by `store_new_invoice`. When `payer` is given, only that address
+/// can pay the invoice.
+fn new_invoice(
     env: &Env,
-    merchant_address: &Address,
+    merchant_id: u64,
     description: &String,
     amount: i128,
     token: &Address,
     expires_at: Option<u64>,
-) -> u64 {
-    merchant_address.require_auth();
-    validate_invoice_creation(
-        env,
-        merchant_address,
-        description,
-        amount,
-        token,
-        expires_at,
-    );
-
-    let merchant_id: u64 = merchant::get_merchant_id(env, merchant_address);
-
-    let invoice_count: u64 = env
-        .storage()
-        .persistent()
-        .get(&DataKey::InvoiceCount)
-        .unwrap_or(0);
-    let new_invoice_id = invoice_count + 1;
-    let invoice = Invoice {
-        id: new_invoice_id,
+    payer: Option<Address>,
+) -> Invoice {
+    Invoice {
+        id: 0,
         description: description.clone(),
         amount,
         token: token.clone(),
         status: InvoiceStatus::Pending,
         merchant_id,
-        payer: None,
+        payer,
         date_created: env.ledger().timestamp(),
         date_paid: None,
         amount_paid: 0,
@@ -162,71 +389,54 @@ pub fn create_invoice(
         expires_at,
         pricing_mode: InvoicePricingMode::FixedCrypto,
         fiat_pricing: FiatPricingData::None,
-    };
-    env.storage()
-        .persistent()
-        .set(&DataKey::Invoice(new_invoice_id), &invoice);
-    env.storage()
-        .persistent()
-        .set(&DataKey::InvoiceCount, &new_invoice_id);
-    events::publish_invoice_created_event(
-        env,
-        new_invoice_id,
-        merchant_address.clone(),
-        amount,
-        token.clone(),
-    );
-    new_invoice_id
+        line_items: Vec::new(env),
+        subtotal: amount,
+        tax_amount: 0,
+        tax_code: None,
+        tax_rate_bps: 0,
+        quote_currency: None,
+        paid_token: None,
+        payment_terms: PaymentTermsData::None,
+        discount_amount: 0,
+        penalty_amount: 0,
+        fee_amount: 0,
+        tax_paid: 0,
+        template_id: None,
+        external_ref: None,
+        split_bill: false,
+        tip_amount: 0,
+        credit_applied: 0,
+        credit_issued: 0,
+        revision: 0,
+    }
 }
 
-pub fn create_fiat_invoice(
+/// Builds a pending invoice as `new_invoice` does, with `options` applied.
+fn new_invoice_with_options(
     env: &Env,
-    merchant_address: &Address,
+    merchant_id: u64,
     description: &String,
-    fiat_amount: i128,
-    fiat_currency: &String,
-    fiat_decimals: u32,
+    amount: i128,
     token: &Address,
-    expires_at: Option<u64>,
-) -> u64 {
-    merchant_address.require_auth();
-
-    if fiat_amount <= 0 {
-        panic_with_error!(env, ContractError::InvalidAmount);
-    }
-
-    let mut invoice = Invoice {
-        id: 0,
-        description: description.clone(),
-        amount: 0,
-        token: token.clone(),
-        status: InvoiceStatus::Pending,
-        merchant_id: merchant::get_merchant_id(env, merchant_address),
-        payer: None,
-        date_created: env.ledger().timestamp(),
-        date_paid: None,
-        amount_paid: 0,
-        amount_refunded: 0,
-        expires_at,
-        pricing_mode: InvoicePricingMode::FixedFiat,
-        fiat_pricing: FiatPricingData::Some(FiatPricing {
-            currency: fiat_currency.clone(),
-            amount: fiat_amount,
-            decimals: fiat_decimals,
-        }),
-    };
-
-    invoice.amount = resolve_fiat_invoice_amount(env, &invoice);
-
-    validate_invoice_creation(
+    options: &InvoiceOptions,
+) -> Invoice {
+    let mut invoice = new_invoice(
         env,
-        merchant_address,
+        merchant_id,
         description,
-        invoice.amount,
+        amount,
         token,
-        expires_at,
+        options.expires_at,
+        options.payer.clone(),
     );
+    invoice.external_ref = options.external_ref.clone();
+    invoice.payment_terms = options.terms.clone();
+    invoice
+}
 
+/// Assigns the next invoice ID, persists the invoice and adds it to the
+/// secondary indexes.
+fn store_new_invoice(env: &Env, invoice: &mut Invoice) -> u64 {
     let invoice_count: u64 = env
         .storage()
         .persistent()
@@ -237,36 +447,31 @@ pub fn create_fiat_invoice(
 
     env.storage()
         .persistent()
-        .set(&DataKey::Invoice(new_invoice_id), &invoice);
+        .set(&DataKey::Invoice(new_invoice_id), &*invoice);
     env.storage()
         .persistent()
         .set(&DataKey::InvoiceCount, &new_invoice_id);
-
-    events::publish_invoice_created_event(
-        env,
-        new_invoice_id,
-        merchant_address.clone(),
-        invoice.amount,
-        token.clone(),
-    );
-    events::publish_fiat_invoice_priced_event(
-        env,
-        new_invoice_id,
-        token.clone(),
-        invoice.amount,
-        env.ledger().timestamp(),
-    );
+    invoice_index::index_new_invoice(env, invoice);
+    if let Some(external_ref) = &invoice.external_ref {
+        env.storage().persistent().set(
+            &InvoiceDataKey::InvoiceByReference(invoice.merchant_id, external_ref.clone()),
+            &new_invoice_id,
+        );
+    }
+    if let PaymentTermsData::Some(_) = invoice.payment_terms {
+        payment_terms::publish_terms_set(env, invoice);
+    }
 
     new_invoice_id
 }
 
-pub fn create_invoice_draft(
+pub fn create_invoice(
     env: &Env,
     merchant_address: &Address,
     description: &String,
     amount: i128,
     token: &Address,
-    expires_at: Option<u64>,
+    options: &InvoiceOptions,
 ) -> u64 {
     merchant_address.require_auth();
     validate_invoice_creation(
@@ -275,153 +480,389 @@ pub fn create_invoice_draft(
         description,
         amount,
         token,
-        expires_at,
+        options.expires_at,
     );
+    validate_invoice_options(env, merchant_address, options);
 
-    let merchant_id: u64 = merchant::get_merchant_id(env, merchant_address);
-
-    let invoice_count: u64 = env
-        .storage()
-        .persistent()
-        .get(&DataKey::InvoiceCount)
-        .unwrap_or(0);
-    let new_invoice_id = invoice_count + 1;
-    let invoice = Invoice {
-        id: new_invoice_id,
-        description: description.clone(),
-        amount,
-        token: token.clone(),
-        status: InvoiceStatus::Draft,
-        merchant_id,
-        payer: None,
-        date_created: env.ledger().timestamp(),
-        date_paid: None,
-        amount_paid: 0,
-        amount_refunded: 0,
-        expires_at,
-        pricing_mode: InvoicePricingMode::FixedCrypto,
-        fiat_pricing: FiatPricingData::None,
-    };
-    env.storage()
-        .persistent()
-        .set(&DataKey::Invoice(new_invoice_id), &invoice);
-    env.storage()
-        .persistent()
-        .set(&DataKey::InvoiceCount, &new_invoice_id);
-
-    // We intentionally don't emit InvoiceCreatedEvent here since it's a draft
-
-    new_invoice_id
+    store_invoice(env, merchant_address, description, amount, token, options)
 }
 
-pub fn finalize_invoice(env: &Env, merchant_address: &Address, invoice_id: u64) {
-    merchant_address.require_auth();
-
-    let mut invoice = get_invoice(env, invoice_id);
-
-    let merchant_id: u64 = merchant::get_merchant_id(env, merchant_address);
-
-    if invoice.merchant_id != merchant_id {
-        panic_with_error!(env, ContractError::NotAuthorized);
+/// Checks the reference and terms in `options`. The expiry is checked by
+/// `validate_invoice_creation`.
+fn validate_invoice_options(env: &Env, merchant_address: &Address, options: &InvoiceOptions) {
+    if let Some(external_ref) = &options.external_ref {
+        validate_external_ref(env, merchant_address, external_ref);
     }
-
-    if invoice.status != InvoiceStatus::Draft {
-        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    if let PaymentTermsData::Some(terms) = &options.terms {
+        payment_terms::validate_terms(env, terms);
     }
+}
 
-    invoice.status = InvoiceStatus::Pending;
-
-    env.storage()
+/// Rejects references that are empty, too long or already used by one of the
+/// merchant's invoices, so a retried create cannot produce a second invoice.
+fn validate_external_ref(env: &Env, merchant_address: &Address, external_ref: &String) {
+    if external_ref.is_empty() || external_ref.len() > MAX_EXTERNAL_REF_LEN {
+        panic_with_error!(env, InvoiceError::InvalidExternalRef);
+    }
+    let merchant_id = merchant::get_merchant_id(env, merchant_address);
+    if env
+        .storage()
         .persistent()
-        .set(&DataKey::Invoice(invoice_id), &invoice);
+        .has(&InvoiceDataKey::InvoiceByReference(
+            merchant_id,
+            external_ref.clone(),
+        ))
+    {
+        panic_with_error!(env, InvoiceError::DuplicateExternalRef);
+    }
+}
 
-    events::publish_invoice_created_event(
-        env,
-        invoice_id,
-        merchant_address.clone(),
-        invoice.amount,
-        invoice.token.clone(),
-    );
+/// Looks up one of the merchant's invoices by the reference it was created with.
+pub fn get_invoice_by_reference(
+    env: &Env,
+    merchant_address: &Address,
+    external_ref: &String,
+) -> Invoice {
+    let merchant_id = merchant::get_merchant_id(env, merchant_address);
+    let invoice_id: u64 = env
+        .storage()
+        .persistent()
+        .get(&InvoiceDataKey::InvoiceByReference(
+            merchant_id,
+            external_ref.clone(),
+        ))
+        .unwrap_or_else(|| panic_with_error!(env, ContractError::InvoiceNotFound));
+    get_invoice(env, invoice_id)
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn create_invoice_signed(
+/// Creates a pending token-priced invoice from already validated parameters.
+fn store_invoice(
     env: &Env,
-    caller: &Address,
-    merchant: &Address,
+    merchant_address: &Address,
     description: &String,
     amount: i128,
     token: &Address,
-    nonce: &BytesN<32>,
-    signature: &BytesN<64>,
+    options: &InvoiceOptions,
 ) -> u64 {
-    // Caller must be Manager or Admin
-    if !access_control::has_role(env, caller, Role::Manager) {
-        panic_with_error!(env, ContractError::NotAuthorized);
-    }
-    caller.require_auth();
-
-    // validate invoice creation
-    validate_invoice_creation(env, merchant, description, amount, token, None);
+    let merchant_id: u64 = merchant::get_merchant_id(env, merchant_address);
 
-    // Verify merchant's cryptographic signature
-    signature_util::verify_invoice_signature(
+    let mut invoice =
+        new_invoice_with_options(env, merchant_id, description, amount, token, options);
+    let new_invoice_id = store_new_invoice(env, &mut invoice);
+    events::publish_invoice_created_event(
         env,
-        merchant,
-        description,
+        new_invoice_id,
+        merchant_address.clone(),
         amount,
-        token,
-        nonce,
-        signature,
+        token.clone(),
     );
+    new_invoice_id
+}
 
-    // Standard invoice creation
-    let merchant_id: u64 = merchant::get_merchant_id(env, merchant);
-
-    let invoice_count: u64 = env
-        .storage()
-        .persistent()
-        .get(&DataKey::InvoiceCount)
-        .unwrap_or(0);
+/// Issues the next invoice from a recurring template, restricted to its payer.
+pub fn create_template_invoice(env: &Env, template: &InvoiceTemplate) -> u64 {
+    let mut invoice = new_invoice(
+        env,
+        template.merchant_id,
+        &template.description,
+        template.amount,
+        &template.token,
+        None,
+        Some(template.payer.clone()),
+    );
+    invoice.template_id = Some(template.id);
+    let new_invoice_id = store_new_invoice(env, &mut invoice);
+    events::publish_invoice_created_event(
+        env,
+        new_invoice_id,
+        template.merchant.clone(),
+        template.amount,
+        template.token.clone(),
+    );
+    new_invoice_id
+}
 
-    let new_invoice_id = invoice_count + 1;
+pub fn create_itemized_invoice(
+    env: &Env,
+    merchant_address: &Address,
+    description: &String,
+    line_items: &Vec<LineItem>,
+    token: &Address,
+    options: &InvoiceOptions,
+) -> u64 {
+    merchant_address.require_auth();
 
-    let invoice = Invoice {
-        id: new_invoice_id,
-        description: description.clone(),
+    let (subtotal, tax_amount) = compute_line_item_totals(env, line_items);
+    let amount = subtotal + tax_amount;
+    validate_invoice_creation(
+        env,
+        merchant_address,
+        description,
         amount,
-        token: token.clone(),
-        status: InvoiceStatus::Pending,
+        token,
+        options.expires_at,
+    );
+    validate_invoice_options(env, merchant_address, options);
+
+    let merchant_id: u64 = merchant::get_merchant_id(env, merchant_address);
+
+    let mut invoice =
+        new_invoice_with_options(env, merchant_id, description, amount, token, options);
+    invoice.line_items = line_items.clone();
+    invoice.subtotal = subtotal;
+    invoice.tax_amount = tax_amount;
+    let new_invoice_id = store_new_invoice(env, &mut invoice);
+    events::publish_invoice_created_event(
+        env,
+        new_invoice_id,
+        merchant_address.clone(),
+        amount,
+        token.clone(),
+    );
+    new_invoice_id
+}
+
+/// Creates an invoice priced in `reference_token` that can also be settled in
+/// any other token the merchant accepts, converted through the oracle prices
+/// of both tokens in `quote_currency`.
+pub fn create_multi_token_invoice(
+    env: &Env,
+    merchant_address: &Address,
+    description: &String,
+    amount: i128,
+    reference_token: &Address,
+    quote_currency: &String,
+    options: &InvoiceOptions,
+) -> u64 {
+    merchant_address.require_auth();
+    validate_invoice_creation(
+        env,
+        merchant_address,
+        description,
+        amount,
+        reference_token,
+        options.expires_at,
+    );
+    validate_invoice_options(env, merchant_address, options);
+    if quote_currency.is_empty() {
+        panic_with_error!(env, InvoiceError::TokenConversionUnavailable);
+    }
+
+    let merchant_id: u64 = merchant::get_merchant_id(env, merchant_address);
+
+    let mut invoice = new_invoice_with_options(
+        env,
         merchant_id,
-        payer: None,
-        date_created: env.ledger().timestamp(),
-        date_paid: None,
-        amount_paid: 0,
-        amount_refunded: 0,
-        expires_at: None,
-        pricing_mode: InvoicePricingMode::FixedCrypto,
-        fiat_pricing: FiatPricingData::None,
+        description,
+        amount,
+        reference_token,
+        options,
+    );
+    invoice.quote_currency = Some(quote_currency.clone());
+    let new_invoice_id = store_new_invoice(env, &mut invoice);
+    events::publish_invoice_created_event(
+        env,
+        new_invoice_id,
+        merchant_address.clone(),
+        amount,
+        reference_token.clone(),
+    );
+    new_invoice_id
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_fiat_invoice(
+    env: &Env,
+    merchant_address: &Address,
+    description: &String,
+    fiat_amount: i128,
+    fiat_currency: &String,
+    fiat_decimals: u32,
+    token: &Address,
+    options: &InvoiceOptions,
+) -> u64 {
+    merchant_address.require_auth();
+
+    if fiat_amount <= 0 {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+
+    let fiat = FiatPricing {
+        currency: fiat_currency.clone(),
+        amount: fiat_amount,
+        decimals: fiat_decimals,
     };
+    store_fiat_invoice(env, merchant_address, description, &fiat, token, options)
+}
 
-    env.storage()
-        .persistent()
-        .set(&DataKey::Invoice(new_invoice_id), &invoice);
-    env.storage()
-        .persistent()
-        .set(&DataKey::InvoiceCount, &new_invoice_id);
+/// Prices a fiat invoice at the current oracle rate, validates it and stores it.
+fn store_fiat_invoice(
+    env: &Env,
+    merchant_address: &Address,
+    description: &String,
+    fiat: &FiatPricing,
+    token: &Address,
+    options: &InvoiceOptions,
+) -> u64 {
+    let merchant_id = merchant::get_merchant_id(env, merchant_address);
+    let mut invoice = new_invoice_with_options(env, merchant_id, description, 0, token, options);
+    invoice.pricing_mode = InvoicePricingMode::FixedFiat;
+    invoice.fiat_pricing = FiatPricingData::Some(fiat.clone());
+
+    invoice.amount = resolve_fiat_invoice_amount(env, &invoice);
+    invoice.subtotal = invoice.amount;
+
+    validate_invoice_creation(
+        env,
+        merchant_address,
+        description,
+        invoice.amount,
+        token,
+        options.expires_at,
+    );
+    validate_invoice_options(env, merchant_address, options);
+
+    let new_invoice_id = store_new_invoice(env, &mut invoice);
 
-    // 7. Emit standardInvoiceCreated event
     events::publish_invoice_created_event(
         env,
         new_invoice_id,
-        merchant.clone(),
-        amount,
+        merchant_address.clone(),
+        invoice.amount,
+        token.clone(),
+    );
+    events::publish_fiat_invoice_priced_event(
+        env,
+        new_invoice_id,
         token.clone(),
+        invoice.amount,
+        None,
+        env.ledger().timestamp(),
+    );
+
+    new_invoice_id
+}
+
+pub fn create_invoice_draft(
+    env: &Env,
+    merchant_address: &Address,
+    description: &String,
+    amount: i128,
+    token: &Address,
+    options: &InvoiceOptions,
+) -> u64 {
+    merchant_address.require_auth();
+    validate_invoice_creation(
+        env,
+        merchant_address,
+        description,
+        amount,
+        token,
+        options.expires_at,
     );
+    validate_invoice_options(env, merchant_address, options);
+
+    let merchant_id: u64 = merchant::get_merchant_id(env, merchant_address);
+
+    let mut invoice =
+        new_invoice_with_options(env, merchant_id, description, amount, token, options);
+    invoice.status = InvoiceStatus::Draft;
+    let new_invoice_id = store_new_invoice(env, &mut invoice);
+
+    // We intentionally don't emit InvoiceCreatedEvent here since it's a draft
 
     new_invoice_id
 }
 
+pub fn finalize_invoice(env: &Env, merchant_address: &Address, invoice_id: u64) {
+    merchant_address.require_auth();
+
+    let mut invoice = get_invoice(env, invoice_id);
+
+    let merchant_id: u64 = merchant::get_merchant_id(env, merchant_address);
+
+    if invoice.merchant_id != merchant_id {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+
+    if invoice.status != InvoiceStatus::Draft {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+
+    invoice_index::set_status(env, &mut invoice, InvoiceStatus::Pending);
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+
+    events::publish_invoice_created_event(
+        env,
+        invoice_id,
+        merchant_address.clone(),
+        invoice.amount,
+        invoice.token.clone(),
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_invoice_signed(
+    env: &Env,
+    caller: &Address,
+    payload: &SignedInvoicePayload,
+    signature: &BytesN<64>,
+) -> u64 {
+    // Caller must be Manager or Admin
+    if !access_control::has_role(env, caller, Role::Manager) {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+    caller.require_auth();
+
+    // validate invoice creation; fiat invoices are validated once priced
+    match &payload.fiat_pricing {
+        FiatPricingData::None => validate_invoice_creation(
+            env,
+            &payload.merchant,
+            &payload.description,
+            payload.amount,
+            &payload.token,
+            payload.expires_at,
+        ),
+        FiatPricingData::Some(fiat) => {
+            if payload.amount != 0 || fiat.amount <= 0 {
+                panic_with_error!(env, ContractError::InvalidAmount);
+            }
+        }
+    }
+
+    // Verify merchant's cryptographic signature
+    signature_util::verify_invoice_signature(env, payload, signature);
+
+    let options = InvoiceOptions {
+        payer: payload.payer.clone(),
+        expires_at: payload.expires_at,
+        ..Default::default()
+    };
+
+    match &payload.fiat_pricing {
+        FiatPricingData::None => store_invoice(
+            env,
+            &payload.merchant,
+            &payload.description,
+            payload.amount,
+            &payload.token,
+            &options,
+        ),
+        FiatPricingData::Some(fiat) => store_fiat_invoice(
+            env,
+            &payload.merchant,
+            &payload.description,
+            fiat,
+            &payload.token,
+            &options,
+        ),
+    }
+}
+
 pub fn get_invoice(env: &Env, invoice_id: u64) -> Invoice {
     env.storage()
         .persistent()
@@ -430,10 +871,20 @@ pub fn get_invoice(env: &Env, invoice_id: u64) -> Invoice {
 }
 
 pub fn resolve_invoice_amount(env: &Env, invoice_id: u64) -> i128 {
-    let invoice = get_invoice(env, invoice_id);
+    amount_due(env, &get_invoice(env, invoice_id), None)
+}
+
+/// Invoice total if it were settled now. Fiat invoices use `payer`'s locked
+/// quote when there is one and the current oracle price otherwise.
+fn amount_due(env: &Env, invoice: &Invoice, payer: Option<&Address>) -> i128 {
+    let mut invoice = invoice.clone();
     if invoice.pricing_mode == InvoicePricingMode::FixedFiat && invoice.amount_paid == 0 {
-        return resolve_fiat_invoice_amount(env, &invoice);
+        invoice.subtotal = payer
+            .and_then(|payer| locked_fiat_quote(env, invoice.id, payer, &invoice.token))
+            .unwrap_or_else(|| resolve_fiat_invoice_amount(env, &invoice));
+        recompute_invoice_totals(env, &mut invoice);
     }
+    apply_payment_terms(env, &mut invoice, true);
 
     invoice.amount
 }
@@ -448,7 +899,7 @@ pub fn check_invoice_refund_eligibility(env: &Env, merchant_address: &Address, i
     }
 
     // check if the payer is available
-    if invoice.payer.is_none() {
+    if invoice.payer.is_none() && !invoice.split_bill {
         panic_with_error!(env, ContractError::PayerNotAvailable);
     }
 
@@ -457,18 +908,12 @@ pub fn check_invoice_refund_eligibility(env: &Env, merchant_address: &Address, i
         panic_with_error!(env, ContractError::InvoiceNotPaid);
     }
 
-    // Enforce refund window
-    if let Some(date_paid) = invoice.date_paid {
-        let elapsed = env.ledger().timestamp() - date_paid;
-        if elapsed > MAX_REFUND_DURATION {
-            panic_with_error!(env, ContractError::RefundPeriodExpired);
-        }
-    } else {
+    if invoice.date_paid.is_none() {
         panic_with_error!(env, ContractError::InvoiceNotPaid);
     }
+    check_refund_window(env, &invoice);
 
-    let amount_to_refund = invoice.amount - invoice.amount_refunded;
-    if amount_to_refund <= 0 {
+    if refundable_amount(&invoice) <= 0 {
         panic_with_error!(env, ContractError::InvalidAmount);
     }
 }
@@ -480,24 +925,23 @@ pub fn refund_invoice(env: &Env, merchant_address: &Address, invoice_id: u64) {
 
     // initiate refund
     let invoice = get_invoice(env, invoice_id);
-    let amount_to_refund = invoice.amount - invoice.amount_refunded;
+    let amount_to_refund = refundable_amount(&invoice);
+    let refund_token = settlement_token(&invoice);
 
-    let payer = invoice.payer.unwrap();
-    // transfer amount_to_refund from merchant account to payer
+    // transfer amount_to_refund from merchant account to the payer(s)
     // check if merchant account balance for the token is sufficient
     let merchant_account = merchant::get_merchant_account(env, invoice.merchant_id);
-    let token_client = TokenClient::new(env, &invoice.token);
+    let token_client = TokenClient::new(env, &refund_token);
     let merchant_balance = token_client.balance(&merchant_account);
     if merchant_balance < amount_to_refund {
         panic_with_error!(env, ContractError::InsufficientBalance);
     }
-    let refund_client = MerchantAccountRefundClient::new(env, &merchant_account);
-    refund_client.refund(&invoice.token, &amount_to_refund, &payer);
+    split_bill::refund_payers(env, &invoice, amount_to_refund, None);
 
     // update invoice
     let mut invoice = get_invoice(env, invoice_id);
     invoice.amount_refunded += amount_to_refund;
-    invoice.status = InvoiceStatus::Refunded;
+    invoice_index::set_status(env, &mut invoice, InvoiceStatus::Refunded);
     env.storage()
         .persistent()
         .set(&DataKey::Invoice(invoice_id), &invoice);
@@ -505,181 +949,551 @@ pub fn refund_invoice(env: &Env, merchant_address: &Address, invoice_id: u64) {
     events::publish_invoice_refunded_event(
         env,
         invoice_id,
-        payer,
-        invoice.amount,
+        merchant_address.clone(),
+        refund_limit(&invoice),
         env.ledger().timestamp(),
     );
 }
 
-pub fn get_invoices(env: &Env, filter: InvoiceFilter) -> Vec<Invoice> {
-    let invoice_count: u64 = env
-        .storage()
-        .persistent()
-        .get(&DataKey::InvoiceCount)
-        .unwrap_or(0);
-    let mut invoices: Vec<Invoice> = Vec::new(env);
-    for i in 1..=invoice_count {
-        if let Some(invoice) = env
+/// Narrows the invoice IDs `get_invoices` has to load using the merchant or
+/// status index. Returns `None` when no indexed field is filtered on.
+fn indexed_invoice_ids(env: &Env, filter: &InvoiceFilter) -> Option<Vec<u64>> {
+    if let Some(merchant) = &filter.merchant {
+        let ids = match env
             .storage()
             .persistent()
-            .get::<_, Invoice>(&DataKey::Invoice(i))
+            .get::<_, u64>(&DataKey::MerchantId(merchant.clone()))
         {
-            let mut matches = true;
-            if let Some(status) = filter.status {
-                if invoice.status as u32 != status {
-                    matches = false;
-                }
+            Some(merchant_id) => {
+                invoice_index::get_index_ids(env, &InvoiceIndexKey::Merchant(merchant_id))
             }
-            if let Some(merchant) = &filter.merchant {
-                if let Some(merchant_id) = env
-                    .storage()
-                    .persistent()
-                    .get::<_, u64>(&DataKey::MerchantId(merchant.clone()))
-                {
-                    if invoice.merchant_id != merchant_id {
-                        matches = false;
-                    }
-                } else {
-                    matches = false;
-                }
+            None => Vec::new(env),
+        };
+        return Some(ids);
+    }
+    if let Some(status) = filter.status {
+        let ids = match invoice_status_from_u32(status) {
+            Some(status) => invoice_index::get_index_ids(env, &InvoiceIndexKey::Status(status)),
+            None => Vec::new(env),
+        };
+        return Some(ids);
+    }
+    None
+}
+
+fn invoice_status_from_u32(status: u32) -> Option<InvoiceStatus> {
+    match status {
+        0 => Some(InvoiceStatus::Pending),
+        1 => Some(InvoiceStatus::Paid),
+        2 => Some(InvoiceStatus::Cancelled),
+        3 => Some(InvoiceStatus::Refunded),
+        4 => Some(InvoiceStatus::PartiallyRefunded),
+        5 => Some(InvoiceStatus::PartiallyPaid),
+        6 => Some(InvoiceStatus::Draft),
+        7 => Some(InvoiceStatus::Expired),
+        _ => None,
+    }
+}
+
+pub fn get_invoices(env: &Env, filter: InvoiceFilter) -> Vec<Invoice> {
+    let mut invoices: Vec<Invoice> = Vec::new(env);
+    match indexed_invoice_ids(env, &filter) {
+        Some(ids) => {
+            for i in ids.iter() {
+                push_if_matches(env, &filter, i, &mut invoices);
             }
-            if let Some(min_amount) = filter.min_amount {
-                if invoice.amount < min_amount as i128 {
-                    matches = false;
-                }
+        }
+        None => {
+            let invoice_count: u64 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::InvoiceCount)
+                .unwrap_or(0);
+            for i in 1..=invoice_count {
+                push_if_matches(env, &filter, i, &mut invoices);
             }
-            if let Some(max_amount) = filter.max_amount {
-                if invoice.amount > max_amount as i128 {
-                    matches = false;
-                }
+        }
+    }
+    invoices
+}
+
+fn push_if_matches(
+    env: &Env,
+    filter: &InvoiceFilter,
+    invoice_id: u64,
+    invoices: &mut Vec<Invoice>,
+) {
+    if let Some(invoice) = env
+        .storage()
+        .persistent()
+        .get::<_, Invoice>(&DataKey::Invoice(invoice_id))
+    {
+        let mut matches = true;
+        if let Some(status) = filter.status {
+            if invoice.status as u32 != status {
+                matches = false;
             }
-            if let Some(start_date) = filter.start_date {
-                if invoice.date_created < start_date {
+        }
+        if let Some(merchant) = &filter.merchant {
+            if let Some(merchant_id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&DataKey::MerchantId(merchant.clone()))
+            {
+                if invoice.merchant_id != merchant_id {
                     matches = false;
                 }
+            } else {
+                matches = false;
             }
-            if let Some(end_date) = filter.end_date {
-                if invoice.date_created > end_date {
-                    matches = false;
-                }
+        }
+        if let Some(min_amount) = filter.min_amount {
+            if invoice.amount < min_amount as i128 {
+                matches = false;
             }
-            if matches {
-                invoices.push_back(invoice);
+        }
+        if let Some(max_amount) = filter.max_amount {
+            if invoice.amount > max_amount as i128 {
+                matches = false;
+            }
+        }
+        if let Some(start_date) = filter.start_date {
+            if invoice.date_created < start_date {
+                matches = false;
             }
         }
+        if let Some(end_date) = filter.end_date {
+            if invoice.date_created > end_date {
+                matches = false;
+            }
+        }
+        if matches {
+            invoices.push_back(invoice);
+        }
     }
-    invoices
 }
 //no new changes to add
 
-pub fn refund_invoice_partial(env: &Env, merchant_address: &Address, invoice_id: u64, amount: i128) {
+pub fn refund_invoice_partial(
+    env: &Env,
+    merchant_address: &Address,
+    invoice_id: u64,
+    amount: i128,
+) {
     merchant_address.require_auth();
-    let mut invoice = get_invoice(env, invoice_id);
+    let invoice = get_invoice(env, invoice_id);
 
     let merchant_id = merchant::get_merchant_id(env, merchant_address);
     if invoice.merchant_id != merchant_id {
         panic_with_error!(env, ContractError::NotAuthorized);
     }
 
-    if invoice.status != InvoiceStatus::Paid && invoice.status != InvoiceStatus::PartiallyRefunded {
-        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
-    }
+    check_refund_window(env, &invoice);
+    issue_refund(env, invoice_id, amount, None);
+}
 
+/// Panics once the merchant's refund window for the settlement token has
+/// passed since the invoice was paid, or straight away if refunds are off.
+pub fn check_refund_window(env: &Env, invoice: &Invoice) {
     if let Some(date_paid) = invoice.date_paid {
+        let window =
+            refund_policy::get_refund_window(env, invoice.merchant_id, &settlement_token(invoice));
         let elapsed = env.ledger().timestamp() - date_paid;
-        if elapsed > MAX_REFUND_DURATION {
+        if window == 0 || elapsed > window {
             panic_with_error!(env, ContractError::RefundPeriodExpired);
         }
     }
+}
+
+/// Token payments on an unfinished invoice that actually reached the merchant
+/// account and have not been returned: credit, tax passed through and
+/// platform fees are left out. This is what cancelling or expiring the
+/// invoice gives back.
+pub fn merchant_net_paid(invoice: &Invoice) -> i128 {
+    invoice.amount_paid
+        - invoice.credit_applied
+        - invoice.tax_paid
+        - invoice.fee_amount
+        - invoice.amount_refunded
+}
+
+/// Amount of a paid invoice that has not been refunded yet, in the token it
+/// was settled in. As with `merchant_net_paid`, platform fees and tax already
+/// paid out stay where they went; only what reached the merchant account can
+/// be refunded.
+pub fn refundable_amount(invoice: &Invoice) -> i128 {
+    refund_limit(invoice) - invoice.amount_refunded
+}
+
+/// Most a paid invoice can ever be refunded: its settlement less fees and tax.
+fn refund_limit(invoice: &Invoice) -> i128 {
+    settlement_amount(invoice) - invoice.fee_amount - invoice.tax_paid
+}
+
+/// Returns `amount` of a paid invoice to its payer from the merchant account.
+/// Callers are responsible for authorization and the refund window.
+pub fn issue_refund(env: &Env, invoice_id: u64, amount: i128, payer: Option<&Address>) {
+    let mut invoice = get_invoice(env, invoice_id);
+
+    if invoice.status != InvoiceStatus::Paid && invoice.status != InvoiceStatus::PartiallyRefunded {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+
+    if amount <= 0 {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+
+    let total_refund = invoice.amount_refunded + amount;
+    if total_refund > refund_limit(&invoice) {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+
+    invoice.amount_refunded = total_refund;
+
+    let new_status = if total_refund == refund_limit(&invoice) {
+        InvoiceStatus::Refunded
+    } else {
+        InvoiceStatus::PartiallyRefunded
+    };
+    invoice_index::set_status(env, &mut invoice, new_status);
+
+    // save invoice to storage
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+
+    let merchant_account_addr = merchant::get_merchant_account(env, invoice.merchant_id);
+    let refund_token = settlement_token(&invoice);
+    // check if merchant account balance for the token is sufficient
+    let token_client = TokenClient::new(env, &refund_token);
+    let merchant_balance = token_client.balance(&merchant_account_addr);
+    if merchant_balance < amount {
+        panic_with_error!(env, ContractError::InsufficientBalance);
+    }
+    // initiate refund
+    split_bill::refund_payers(env, &invoice, amount, payer);
+
+    let merchant_address = merchant_id_to_address(env, invoice.merchant_id);
+    if total_refund == refund_limit(&invoice) {
+        events::publish_invoice_refunded_event(
+            env,
+            invoice_id,
+            merchant_address,
+            total_refund,
+            env.ledger().timestamp(),
+        );
+    } else {
+        events::publish_invoice_partially_refunded_event(
+            env,
+            invoice_id,
+            merchant_address,
+            amount,
+            total_refund,
+            env.ledger().timestamp(),
+        );
+    }
+}
+
+pub fn pay_invoices_batch(env: &Env, payer: &Address, invoice_ids: &Vec<u64>) {
+    payer.require_auth();
+    for invoice_id in invoice_ids.iter() {
+        pay_remaining(env, payer, invoice_id, None, 0, None);
+    }
+}
+
+/// Pays whatever is left on the invoice. `max_amount` bounds the charge for
+/// invoices whose amount is only resolved at payment time. A credit note
+/// covers as much of the remainder as it can; only the rest is transferred.
+/// `expected_revision` rejects the payment if the invoice was amended since
+/// the payer looked at it.
+pub fn pay_invoice(
+    env: &Env,
+    payer: &Address,
+    invoice_id: u64,
+    max_amount: Option<i128>,
+    credit_note_id: Option<u64>,
+    expected_revision: Option<u32>,
+) -> i128 {
+    payer.require_auth();
+    check_revision(env, invoice_id, expected_revision);
+    pay_remaining(env, payer, invoice_id, max_amount, 0, credit_note_id)
+}
+
+/// Rejects the payment if the invoice is no longer at the revision the payer
+/// agreed to.
+fn check_revision(env: &Env, invoice_id: u64, expected_revision: Option<u32>) {
+    if let Some(expected_revision) = expected_revision {
+        if get_invoice(env, invoice_id).revision != expected_revision {
+            panic_with_error!(env, InvoiceError::RevisionMismatch);
+        }
+    }
+}
+
+/// Pays whatever is left on the invoice plus a `tip` for the merchant. The
+/// tip goes to the merchant's tip account, is kept out of `amount_paid` and
+/// is not refundable.
+pub fn pay_invoice_with_tip(
+    env: &Env,
+    payer: &Address,
+    invoice_id: u64,
+    max_amount: Option<i128>,
+    tip: i128,
+    expected_revision: Option<u32>,
+) -> i128 {
+    payer.require_auth();
+    if tip < 0 {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+    check_revision(env, invoice_id, expected_revision);
+    pay_remaining(env, payer, invoice_id, max_amount, tip, None)
+}
+
+pub fn pay_invoice_partial(
+    env: &Env,
+    payer: &Address,
+    invoice_id: u64,
+    amount: i128,
+    credit_note_id: Option<u64>,
+    expected_revision: Option<u32>,
+) -> i128 {
+    payer.require_auth();
+    check_revision(env, invoice_id, expected_revision);
+    apply_payment(env, payer, invoice_id, amount, 0, credit_note_id)
+}
+
+// The payer's authorization is required once by the entry point; requiring it
+// again per invoice would fail inside a batch.
+fn pay_remaining(
+    env: &Env,
+    payer: &Address,
+    invoice_id: u64,
+    max_amount: Option<i128>,
+    tip: i128,
+    credit_note_id: Option<u64>,
+) -> i128 {
+    let invoice = get_invoice(env, invoice_id);
+    if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::PartiallyPaid {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+    let remaining_amount = amount_due(env, &invoice, Some(payer)) - invoice.amount_paid;
+    if remaining_amount <= 0 {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+    if max_amount.is_some_and(|max_amount| remaining_amount > max_amount) {
+        panic_with_error!(env, InvoiceError::SlippageExceeded);
+    }
+    apply_payment(
+        env,
+        payer,
+        invoice_id,
+        remaining_amount,
+        tip,
+        credit_note_id,
+    )
+}
+
+fn apply_payment(
+    env: &Env,
+    payer: &Address,
+    invoice_id: u64,
+    amount: i128,
+    tip: i128,
+    credit_note_id: Option<u64>,
+) -> i128 {
+    if amount <= 0 {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+
+    let mut invoice = get_invoice(env, invoice_id);
+    refresh_fiat_invoice_quote(env, &mut invoice, payer);
+    check_invoice_payable(env, &invoice, payer);
+
+    // Whatever the terms add now is reported with this payment.
+    let (discount_before, penalty_before) = if invoice.amount_paid == 0 {
+        (0, 0)
+    } else {
+        (invoice.discount_amount, invoice.penalty_amount)
+    };
+    apply_payment_terms(env, &mut invoice, true);
+    if amount < invoice.amount {
+        apply_payment_terms(env, &mut invoice, false);
+    }
 
-    if amount <= 0 {
+    if invoice.amount_paid + amount > invoice.amount {
         panic_with_error!(env, ContractError::InvalidAmount);
     }
+    installments::record_installment_payment(env, &invoice, invoice.amount_paid + amount);
 
-    let total_refund = invoice.amount_refunded + amount;
-    if total_refund > invoice.amount {
-        panic_with_error!(env, ContractError::InvalidAmount);
+    if !admin::is_accepted_token(env, &invoice.token) {
+        panic_with_error!(env, ContractError::TokenNotAccepted);
     }
 
-    invoice.amount_refunded = total_refund;
+    let credit = match credit_note_id {
+        Some(credit_note_id) => {
+            credit_note::redeem_credit_note(env, credit_note_id, payer, &invoice, amount)
+        }
+        None => 0,
+    };
+    // Only the part paid in tokens can pass tax through to the tax account.
+    let token_amount = amount - credit;
+    let tax_amount = tax_portion(&invoice, amount) * token_amount / amount;
+    let discount = invoice.discount_amount - discount_before;
+    let penalty = invoice.penalty_amount - penalty_before;
+    let token = invoice.token.clone();
+    let fee_amount = settle_payment(
+        env,
+        payer,
+        &invoice,
+        &token,
+        token_amount,
+        tax_amount,
+        discount,
+        penalty,
+        tip,
+    );
 
-    let new_status = if total_refund == invoice.amount {
-        InvoiceStatus::Refunded
+    invoice.amount_paid += amount;
+    invoice.credit_applied += credit;
+    invoice.fee_amount += fee_amount;
+    invoice.tax_paid += tax_amount;
+    invoice.tip_amount += tip;
+    record_payer(env, &mut invoice, payer, token_amount);
+
+    if invoice.amount_paid == invoice.amount {
+        invoice_index::set_status(env, &mut invoice, InvoiceStatus::Paid);
+        invoice.date_paid = Some(env.ledger().timestamp());
     } else {
-        InvoiceStatus::PartiallyRefunded
-    };
-    invoice.status = new_status;
+        invoice_index::set_status(env, &mut invoice, InvoiceStatus::PartiallyPaid);
+    }
 
-    // save invoice to storage
     env.storage()
         .persistent()
         .set(&DataKey::Invoice(invoice_id), &invoice);
 
-    let payer = invoice
-        .payer
-        .clone()
-        .unwrap_or_else(|| panic_with_error!(env, ContractError::PayerNotAvailable));
-
-    let merchant_account_addr = merchant::get_merchant_account(env, invoice.merchant_id);
-    // check if merchant account balance for the token is sufficient
-    let token_client = TokenClient::new(env, &invoice.token);
-    let merchant_balance = token_client.balance(&merchant_account_addr);
-    if merchant_balance < amount {
-        panic_with_error!(env, ContractError::InsufficientBalance);
-    }
-    // initiate refund
-    let refund_client = MerchantAccountRefundClient::new(env, &merchant_account_addr);
-    refund_client.refund(&invoice.token, &amount, &payer);
-
-    if total_refund == invoice.amount {
-        events::publish_invoice_refunded_event(
-            env,
-            invoice_id,
-            merchant_address.clone(),
-            invoice.amount,
-            env.ledger().timestamp(),
-        );
-    } else {
-        events::publish_invoice_partially_refunded_event(
-            env,
-            invoice_id,
-            merchant_address.clone(),
-            amount,
-            total_refund,
-            env.ledger().timestamp(),
-        );
-    }
+    fee_amount
 }
 
-pub fn pay_invoices_batch(env: &Env, payer: &Address, invoice_ids: &Vec<u64>) {
+/// Settles a fiat-priced or multi-token invoice in full using `pay_token`,
+/// converted at current oracle prices or at a quote the payer locked for
+/// that token. Paying in the invoice's own token behaves like `pay_invoice`.
+/// `max_amount` bounds the charge in `pay_token`.
+pub fn pay_invoice_with_token(
+    env: &Env,
+    payer: &Address,
+    invoice_id: u64,
+    pay_token: &Address,
+    max_amount: Option<i128>,
+    expected_revision: Option<u32>,
+) -> i128 {
     payer.require_auth();
-    for invoice_id in invoice_ids.iter() {
-        pay_invoice(env, payer, invoice_id);
+    check_revision(env, invoice_id, expected_revision);
+
+    let mut invoice = get_invoice(env, invoice_id);
+    if *pay_token == invoice.token {
+        return pay_remaining(env, payer, invoice_id, max_amount, 0, None);
     }
-}
 
-pub fn pay_invoice(env: &Env, payer: &Address, invoice_id: u64) -> i128 {
-    let invoice = get_invoice(env, invoice_id);
-    if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::PartiallyPaid {
+    check_invoice_payable(env, &invoice, payer);
+    // Only whole-invoice settlements are converted, so the amounts recorded
+    // on an invoice are never split across two tokens.
+    if invoice.amount_paid > 0 {
         panic_with_error!(env, ContractError::InvalidInvoiceStatus);
     }
-    let remaining_amount = resolve_invoice_amount(env, invoice_id) - invoice.amount_paid;
-    if remaining_amount <= 0 {
-        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    check_pay_token(env, &invoice, pay_token);
+
+    let (pay_amount, tax_amount) = match invoice.pricing_mode {
+        InvoicePricingMode::FixedFiat => {
+            let locked_amount = locked_fiat_quote(env, invoice_id, payer, pay_token);
+            let mut quote = invoice.clone();
+            quote.token = pay_token.clone();
+            quote.subtotal =
+                locked_amount.unwrap_or_else(|| resolve_fiat_invoice_amount(env, &quote));
+            recompute_invoice_totals(env, &mut quote);
+            apply_payment_terms(env, &mut quote, true);
+            invoice.discount_amount = quote.discount_amount;
+            invoice.penalty_amount = quote.penalty_amount;
+            if locked_amount.is_some() {
+                env.storage()
+                    .temporary()
+                    .remove(&InvoiceDataKey::FiatQuote(invoice_id, payer.clone()));
+            } else {
+                events::publish_fiat_invoice_priced_event(
+                    env,
+                    invoice_id,
+                    pay_token.clone(),
+                    quote.subtotal,
+                    None,
+                    env.ledger().timestamp(),
+                );
+            }
+            (quote.amount, quote.tax_amount)
+        }
+        InvoicePricingMode::FixedCrypto => {
+            let quote_currency = invoice.quote_currency.clone().unwrap_or_else(|| {
+                panic_with_error!(env, InvoiceError::TokenConversionUnavailable)
+            });
+            apply_payment_terms(env, &mut invoice, true);
+            let pay_amount = convert_token_amount(
+                env,
+                invoice.amount,
+                &invoice.token,
+                pay_token,
+                &quote_currency,
+            );
+            (pay_amount, pay_amount * invoice.tax_amount / invoice.amount)
+        }
+    };
+    if max_amount.is_some_and(|max_amount| pay_amount > max_amount) {
+        panic_with_error!(env, InvoiceError::SlippageExceeded);
     }
-    pay_invoice_partial(env, payer, invoice_id, remaining_amount)
-}
-
-pub fn pay_invoice_partial(env: &Env, payer: &Address, invoice_id: u64, amount: i128) -> i128 {
-    payer.require_auth();
 
-    if amount <= 0 {
-        panic_with_error!(env, ContractError::InvalidAmount);
+    installments::record_installment_payment(env, &invoice, invoice.amount);
+    // Like the other settled amounts, the adjustment is recorded in the token
+    // actually paid. Fiat quotes are priced in that token already.
+    if invoice.pricing_mode == InvoicePricingMode::FixedCrypto {
+        invoice.discount_amount = pay_amount * invoice.discount_amount / invoice.amount;
+        invoice.penalty_amount = pay_amount * invoice.penalty_amount / invoice.amount;
     }
+    let fee_amount = settle_payment(
+        env,
+        payer,
+        &invoice,
+        pay_token,
+        pay_amount,
+        tax_amount,
+        invoice.discount_amount,
+        invoice.penalty_amount,
+        0,
+    );
 
-    let mut invoice = get_invoice(env, invoice_id);
-    refresh_fiat_invoice_quote(env, &mut invoice);
+    invoice.paid_token = Some(pay_token.clone());
+    invoice.amount_paid = pay_amount;
+    invoice.fee_amount = fee_amount;
+    invoice.tax_paid = tax_amount;
+    record_payer(env, &mut invoice, payer, pay_amount);
+    invoice_index::set_status(env, &mut invoice, InvoiceStatus::Paid);
+    invoice.date_paid = Some(env.ledger().timestamp());
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+
+    fee_amount
+}
+
+/// Checks that both the platform and the invoice's merchant accept
+/// `pay_token`.
+fn check_pay_token(env: &Env, invoice: &Invoice, pay_token: &Address) {
+    let merchant_address = merchant_id_to_address(env, invoice.merchant_id);
+    if !admin::is_accepted_token(env, pay_token) {
+        panic_with_error!(env, ContractError::TokenNotAccepted);
+    }
+    if !merchant::is_token_accepted_for_merchant(env, &merchant_address, pay_token) {
+        panic_with_error!(env, ContractError::TokenNotAcceptedByMerchant);
+    }
+}
 
+fn check_invoice_payable(env: &Env, invoice: &Invoice, payer: &Address) {
     if let Some(expires_at) = invoice.expires_at {
         if env.ledger().timestamp() >= expires_at {
             panic_with_error!(env, ContractError::InvoiceExpired);
@@ -690,83 +1504,131 @@ pub fn pay_invoice_partial(env: &Env, payer: &Address, invoice_id: u64, amount:
         panic_with_error!(env, ContractError::InvalidInvoiceStatus);
     }
 
-    if invoice.amount_paid + amount > invoice.amount {
-        panic_with_error!(env, ContractError::InvalidAmount);
+    // Set at creation for invoices restricted to one payer, otherwise by the
+    // first payment; nobody else may pay after that.
+    if invoice.split_bill {
+        return;
+    }
+    if let Some(expected_payer) = &invoice.payer {
+        if *expected_payer != *payer {
+            panic_with_error!(env, ContractError::NotAuthorized);
+        }
     }
+}
 
-    if !admin::is_accepted_token(env, &invoice.token) {
-        panic_with_error!(env, ContractError::TokenNotAccepted);
+/// Records who paid. Split bills track each payer's contribution instead of
+/// pinning the invoice to the first one.
+fn record_payer(env: &Env, invoice: &mut Invoice, payer: &Address, amount: i128) {
+    if invoice.split_bill {
+        split_bill::record_contribution(env, invoice, payer, amount);
+    } else if invoice.payer.is_none() {
+        invoice.payer = Some(payer.clone());
+        invoice_index::index_payer(env, payer, invoice.id);
     }
+}
 
+/// Moves one payment of `amount` in `token` from the payer to the merchant,
+/// platform and tax accounts, then records analytics, events and history.
+/// Returns the platform fee. Tax is passed through in full; the fee is
+/// charged on the revenue portion only. `discount` and `penalty` are only
+/// reported in the paid event; they are already reflected in `amount`. A
+/// `tip` is paid on top of `amount` and routed to the merchant's tip account;
+/// any fee on it is included in the returned fee.
+#[allow(clippy::too_many_arguments)]
+fn settle_payment(
+    env: &Env,
+    payer: &Address,
+    invoice: &Invoice,
+    token: &Address,
+    amount: i128,
+    tax_amount: i128,
+    discount: i128,
+    penalty: i128,
+    tip: i128,
+) -> i128 {
     let merchant_address: Address = merchant_id_to_address(env, invoice.merchant_id);
-    let fee_amount = admin::calculate_fee(env, &merchant_address, &invoice.token, amount);
+    let fee_amount = admin::charge_fee(env, &merchant_address, token, amount - tax_amount);
     let merchant_account_id = merchant::get_merchant_account(env, invoice.merchant_id);
-    let platform_account = admin::get_platform_account(env);
-    let merchant_amount = amount - fee_amount;
-
-    let token_client = token::TokenClient::new(env, &invoice.token);
+    let merchant_amount = amount - tax_amount - fee_amount;
 
-    token_client.transfer(payer, &merchant_account_id, &merchant_amount);
-    if fee_amount > 0 {
-        token_client.transfer(payer, &platform_account, &fee_amount);
+    let mut payouts = Vec::from_array(env, [(merchant_account_id.clone(), merchant_amount)]);
+    let (platform_fee, referral_share) =
+        referral::split_platform_fee(env, &merchant_address, token, fee_amount);
+    if let Some(referral_share) = referral_share {
+        payouts.push_back(referral_share);
     }
-    admin::record_merchant_payment(env, &merchant_address, &invoice.token, amount, fee_amount);
-
-    invoice.amount_paid += amount;
-    if let Some(existing_payer) = &invoice.payer {
-        if *existing_payer != *payer {
-            panic_with_error!(env, ContractError::NotAuthorized);
-        }
-    } else {
-        invoice.payer = Some(payer.clone());
+    if tax_amount > 0 {
+        let tax_account = tax::tax_destination(env, invoice.merchant_id);
+        payouts.push_back((tax_account, tax_amount));
     }
-
-    if invoice.amount_paid == invoice.amount {
-        invoice.status = InvoiceStatus::Paid;
-        invoice.date_paid = Some(env.ledger().timestamp());
+    let (tip_fee, tip_platform_fee) = if tip > 0 {
+        tips::settle_tip(
+            env,
+            &merchant_address,
+            invoice.merchant_id,
+            token,
+            tip,
+            &mut payouts,
+        )
     } else {
-        invoice.status = InvoiceStatus::PartiallyPaid;
-    }
-
-    env.storage()
-        .persistent()
-        .set(&DataKey::Invoice(invoice_id), &invoice);
+        (0, 0)
+    };
+    let platform_account = treasury::route_payment(
+        env,
+        token,
+        payer,
+        false,
+        platform_fee + tip_platform_fee,
+        &payouts,
+    );
+    admin::record_merchant_payment(
+        env,
+        &merchant_address,
+        token,
+        amount + tip,
+        fee_amount + tip_fee,
+        tax_amount,
+    );
 
     events::publish_invoice_paid_event(
         env,
-        invoice_id,
+        invoice.id,
         invoice.merchant_id,
         merchant_account_id.clone(),
         payer.clone(),
         amount,
-        fee_amount,
+        fee_amount + tip_fee,
+        tax_amount,
+        discount,
+        penalty,
+        tip,
         merchant_amount,
-        invoice.token.clone(),
+        token.clone(),
         env.ledger().timestamp(),
     );
     events::publish_payment_split_routed_event(
         env,
-        invoice_id,
+        invoice.id,
         merchant_account_id,
         platform_account,
         merchant_amount,
-        fee_amount,
-        invoice.token.clone(),
+        platform_fee,
+        token.clone(),
         env.ledger().timestamp(),
     );
 
     let transaction = Transaction {
         transaction_type: TransactionType::InvoicePayment,
-        ref_id: invoice_id,
+        ref_id: invoice.id,
         amount,
-        token: invoice.token.clone(),
+        token: token.clone(),
         description: invoice.description.clone(),
         date: env.ledger().timestamp(),
         merchant_id: invoice.merchant_id,
     };
     history::record_transaction(env, payer, transaction);
 
-    fee_amount
+    fee_amount + tip_fee
 }
 
 pub fn void_invoice(env: &Env, merchant_address: &Address, invoice_id: u64) {
@@ -788,7 +1650,7 @@ pub fn void_invoice(env: &Env, merchant_address: &Address, invoice_id: u64) {
         panic_with_error!(env, ContractError::InvalidInvoiceStatus);
     }
 
-    invoice.status = InvoiceStatus::Cancelled;
+    invoice_index::set_status(env, &mut invoice, InvoiceStatus::Cancelled);
 
     env.storage()
         .persistent()
@@ -802,6 +1664,61 @@ pub fn void_invoice(env: &Env, merchant_address: &Address, invoice_id: u64) {
     );
 }
 
+/// Cancels a partially paid invoice and returns what the merchant holds to
+/// the payer: the tokens that reached the merchant account and the credit,
+/// back onto the notes it was drawn from. Platform fees and tax already paid
+/// out cannot be clawed back, so they are not refunded; the event reports
+/// them next to the refund.
+pub fn cancel_invoice(env: &Env, merchant_address: &Address, invoice_id: u64) {
+    merchant_address.require_auth();
+
+    let mut invoice = get_invoice(env, invoice_id);
+
+    let merchant_id: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::MerchantId(merchant_address.clone()))
+        .unwrap_or_else(|| panic_with_error!(env, ContractError::NotAuthorized));
+
+    if invoice.merchant_id != merchant_id {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+
+    if invoice.status != InvoiceStatus::PartiallyPaid {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+
+    // Credit goes back on the notes; tax and fees were paid out elsewhere.
+    let refund_amount = merchant_net_paid(&invoice);
+    let merchant_account_addr = merchant::get_merchant_account(env, invoice.merchant_id);
+    let token_client = TokenClient::new(env, &invoice.token);
+    if token_client.balance(&merchant_account_addr) < refund_amount {
+        panic_with_error!(env, ContractError::InsufficientBalance);
+    }
+
+    invoice.amount_refunded += refund_amount;
+    invoice_index::set_status(env, &mut invoice, InvoiceStatus::Cancelled);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+
+    if refund_amount > 0 {
+        split_bill::refund_payers(env, &invoice, refund_amount, None);
+    }
+    credit_note::restore_invoice_credit(env, &invoice);
+
+    events::publish_partial_invoice_cancelled_event(
+        env,
+        invoice_id,
+        merchant_address.clone(),
+        invoice.payer.clone(),
+        refund_amount,
+        invoice.fee_amount,
+        invoice.tax_paid,
+        env.ledger().timestamp(),
+    );
+}
+
 pub fn amend_invoice(
     env: &Env,
     merchant_address: &Address,
@@ -828,32 +1745,178 @@ pub fn amend_invoice(
     }
 
     let old_amount = invoice.amount;
+    let old_description = invoice.description.clone();
 
     if let Some(amount) = new_amount {
         if amount <= 0 {
             panic_with_error!(env, ContractError::InvalidAmount);
         }
-        invoice.amount = amount;
+        // Itemized totals are derived from their lines; use amend_invoice_items.
+        if !invoice.line_items.is_empty() {
+            panic_with_error!(env, InvoiceError::InvalidLineItems);
+        }
+        installments::assert_no_installment_plan(env, invoice_id);
+        invoice.subtotal = amount;
+        recompute_invoice_totals(env, &mut invoice);
     }
 
     if let Some(description) = new_description {
         invoice.description = description;
     }
 
+    record_revision(
+        env,
+        &mut invoice,
+        merchant_address,
+        old_amount,
+        old_description,
+    );
     env.storage()
         .persistent()
         .set(&DataKey::Invoice(invoice_id), &invoice);
+}
 
-    events::publish_invoice_amended_event(
+pub fn amend_invoice_items(
+    env: &Env,
+    merchant_address: &Address,
+    invoice_id: u64,
+    line_items: &Vec<LineItem>,
+) {
+    merchant_address.require_auth();
+
+    let mut invoice = get_invoice(env, invoice_id);
+
+    let merchant_id: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::MerchantId(merchant_address.clone()))
+        .unwrap_or_else(|| panic_with_error!(env, ContractError::NotAuthorized));
+
+    if invoice.merchant_id != merchant_id {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+
+    if invoice.status != InvoiceStatus::Pending {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+
+    if invoice.line_items.is_empty() {
+        panic_with_error!(env, InvoiceError::InvalidLineItems);
+    }
+    installments::assert_no_installment_plan(env, invoice_id);
+
+    let old_amount = invoice.amount;
+    let old_description = invoice.description.clone();
+    invoice.line_items = line_items.clone();
+    recompute_invoice_totals(env, &mut invoice);
+
+    let fee_amount = admin::calculate_fee(env, merchant_address, &invoice.token, invoice.amount);
+    if invoice.amount <= fee_amount {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+
+    record_revision(
         env,
-        invoice_id,
-        merchant_address.clone(),
+        &mut invoice,
+        merchant_address,
         old_amount,
-        invoice.amount,
-        env.ledger().timestamp(),
+        old_description,
+    );
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+}
+
+/// Applies one of the merchant's registered tax rates to a pending or draft
+/// invoice. The rate is copied onto the invoice, so later changes to the
+/// merchant's rate table do not affect it.
+pub fn apply_invoice_tax(env: &Env, merchant_address: &Address, invoice_id: u64, code: &String) {
+    merchant_address.require_auth();
+
+    let mut invoice = get_invoice(env, invoice_id);
+
+    let merchant_id = merchant::get_merchant_id(env, merchant_address);
+    if invoice.merchant_id != merchant_id {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+
+    if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Draft {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+
+    installments::assert_no_installment_plan(env, invoice_id);
+
+    let old_amount = invoice.amount;
+    let old_description = invoice.description.clone();
+    invoice.tax_rate_bps = tax::get_merchant_tax_rate(env, merchant_id, code);
+    invoice.tax_code = Some(code.clone());
+    recompute_invoice_totals(env, &mut invoice);
+
+    record_revision(
+        env,
+        &mut invoice,
+        merchant_address,
+        old_amount,
+        old_description,
+    );
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+}
+
+/// Bumps the invoice's revision and stores what the amendment changed. The
+/// caller saves the invoice.
+pub fn record_revision(
+    env: &Env,
+    invoice: &mut Invoice,
+    amended_by: &Address,
+    old_amount: i128,
+    old_description: String,
+) {
+    invoice.revision += 1;
+    let revision = InvoiceRevision {
+        revision: invoice.revision,
+        old_amount,
+        new_amount: invoice.amount,
+        old_description,
+        new_description: invoice.description.clone(),
+        amended_by: amended_by.clone(),
+        timestamp: env.ledger().timestamp(),
+    };
+    env.storage().persistent().set(
+        &InvoiceDataKey::InvoiceRevision(invoice.id, invoice.revision),
+        &revision,
+    );
+
+    events::publish_invoice_amended_event(
+        env,
+        invoice.id,
+        amended_by.clone(),
+        revision.revision,
+        revision.old_amount,
+        revision.new_amount,
+        revision.old_description,
+        revision.new_description,
+        revision.timestamp,
     );
 }
 
+/// Every amendment of the invoice, oldest first.
+pub fn get_invoice_revisions(env: &Env, invoice_id: u64) -> Vec<InvoiceRevision> {
+    let invoice = get_invoice(env, invoice_id);
+    let mut revisions = Vec::new(env);
+    for revision in 1..=invoice.revision {
+        if let Some(entry) = env
+            .storage()
+            .persistent()
+            .get(&InvoiceDataKey::InvoiceRevision(invoice_id, revision))
+        {
+            revisions.push_back(entry);
+        }
+    }
+    revisions
+}
+
 fn merchant_id_to_address(env: &Env, merchant_id: u64) -> Address {
     let merchant_data: crate::types::Merchant = env
         .storage()
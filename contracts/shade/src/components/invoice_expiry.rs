@@ -0,0 +1,130 @@
+use crate::components::{credit_note, invoice, invoice_index, merchant, split_bill};
+use crate::errors::ContractError;
+use crate::events;
+use crate::types::{
+    DataKey, ExpiryRefundPolicy, Invoice, InvoiceDataKey, InvoiceIndexKey, InvoiceStatus,
+};
+use soroban_sdk::token::TokenClient;
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+pub fn set_merchant_expiry_policy(env: &Env, merchant: &Address, policy: ExpiryRefundPolicy) {
+    merchant.require_auth();
+
+    if !merchant::is_merchant(env, merchant) {
+        panic_with_error!(env, ContractError::MerchantNotFound);
+    }
+    let merchant_id = merchant::get_merchant_id(env, merchant);
+
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::MerchantExpiryPolicy(merchant_id), &policy);
+}
+
+pub fn get_merchant_expiry_policy(env: &Env, merchant_id: u64) -> ExpiryRefundPolicy {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::MerchantExpiryPolicy(merchant_id))
+        .unwrap_or(ExpiryRefundPolicy::RefundPayer)
+}
+
+/// Expires every listed invoice that is past its `expires_at` and still open.
+/// Anyone may call this; IDs that are not eligible are skipped. Returns the
+/// number of invoices expired.
+pub fn expire_invoices(env: &Env, invoice_ids: &Vec<u64>) -> u32 {
+    let mut expired = 0;
+    for invoice_id in invoice_ids.iter() {
+        if expire_invoice(env, invoice_id) {
+            expired += 1;
+        }
+    }
+    expired
+}
+
+/// Walks the open (Pending and PartiallyPaid) invoices from position `cursor`
+/// in the open index, looking at no more than `limit` of them, and expires
+/// the overdue ones. Returns the cursor for the next sweep, or `None` once
+/// the end of the index was reached.
+pub fn sweep_expired_invoices(env: &Env, cursor: Option<u64>, limit: u32) -> Option<u64> {
+    if limit == 0 || limit > invoice_index::MAX_PAGE_SIZE {
+        panic_with_error!(env, ContractError::InvalidPageLimit);
+    }
+
+    let len = invoice_index::index_len(env, &InvoiceIndexKey::Open);
+    let mut position = cursor.unwrap_or(0).min(len as u64) as u32;
+    for _ in 0..limit {
+        if position >= len {
+            break;
+        }
+        // Slots of invoices that already left the open index are empty.
+        if let Some(invoice_id) = invoice_index::index_entry(env, &InvoiceIndexKey::Open, position)
+        {
+            expire_invoice(env, invoice_id);
+        }
+        position += 1;
+    }
+
+    if position < len {
+        Some(position as u64)
+    } else {
+        None
+    }
+}
+
+fn expire_invoice(env: &Env, invoice_id: u64) -> bool {
+    let mut invoice: Invoice = match env
+        .storage()
+        .persistent()
+        .get(&DataKey::Invoice(invoice_id))
+    {
+        Some(invoice) => invoice,
+        None => return false,
+    };
+
+    if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::PartiallyPaid {
+        return false;
+    }
+    match invoice.expires_at {
+        Some(expires_at) if env.ledger().timestamp() >= expires_at => {}
+        _ => return false,
+    }
+
+    let mut refunded = 0;
+    if invoice.amount_paid > 0
+        && get_merchant_expiry_policy(env, invoice.merchant_id) == ExpiryRefundPolicy::RefundPayer
+    {
+        if invoice.payer.is_none() && !invoice.split_bill {
+            return false;
+        }
+        let merchant_account = merchant::get_merchant_account(env, invoice.merchant_id);
+        // Leave the invoice open rather than fail the whole sweep; it is
+        // picked up again once the merchant account can cover the refund.
+        // Credit is returned to its notes; tax and fees were paid out
+        // elsewhere, so only what the merchant account received comes back.
+        let net_paid = invoice::merchant_net_paid(&invoice);
+        let token_client = TokenClient::new(env, &invoice.token);
+        if token_client.balance(&merchant_account) < net_paid {
+            return false;
+        }
+        if net_paid > 0 {
+            split_bill::refund_payers(env, &invoice, net_paid, None);
+        }
+        credit_note::restore_invoice_credit(env, &invoice);
+        refunded = net_paid;
+        invoice.amount_refunded += refunded;
+    }
+
+    invoice_index::set_status(env, &mut invoice, InvoiceStatus::Expired);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+
+    events::publish_invoice_expired_event(
+        env,
+        invoice_id,
+        invoice.merchant_id,
+        invoice.amount_paid,
+        refunded,
+        env.ledger().timestamp(),
+    );
+    true
+}
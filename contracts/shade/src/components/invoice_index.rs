@@ -0,0 +1,239 @@
+use crate::components::{core, merchant, reentrancy};
+use crate::errors::ContractError;
+use crate::types::{
+    DataKey, Invoice, InvoiceIndex, InvoiceIndexDataKey, InvoiceIndexKey, InvoicePage,
+    InvoiceStatus,
+};
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+// Entries are only ever appended, so every index stays in the order invoices
+// were added to it. Removing an invoice leaves an empty slot behind instead
+// of moving other entries, which keeps positions (and so page cursors)
+// stable. The length counts slots, including the empty ones.
+
+pub fn index_len(env: &Env, index: &InvoiceIndexKey) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&InvoiceIndexDataKey::Length(index.clone()))
+        .unwrap_or(0)
+}
+
+pub fn index_entry(env: &Env, index: &InvoiceIndexKey, position: u32) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&InvoiceIndexDataKey::Entry(index.clone(), position))
+}
+
+fn insert_id(env: &Env, index: InvoiceIndexKey, invoice_id: u64) {
+    let position_key = InvoiceIndexDataKey::Position(index.clone(), invoice_id);
+    if env.storage().persistent().has(&position_key) {
+        return;
+    }
+    let len = index_len(env, &index);
+    env.storage()
+        .persistent()
+        .set(&InvoiceIndexDataKey::Entry(index.clone(), len), &invoice_id);
+    env.storage().persistent().set(&position_key, &len);
+    env.storage()
+        .persistent()
+        .set(&InvoiceIndexDataKey::Length(index), &(len + 1));
+}
+
+fn remove_id(env: &Env, index: InvoiceIndexKey, invoice_id: u64) {
+    let position_key = InvoiceIndexDataKey::Position(index.clone(), invoice_id);
+    let Some(position) = env.storage().persistent().get::<_, u32>(&position_key) else {
+        return;
+    };
+    env.storage()
+        .persistent()
+        .remove(&InvoiceIndexDataKey::Entry(index, position));
+    env.storage().persistent().remove(&position_key);
+}
+
+fn is_open(status: InvoiceStatus) -> bool {
+    matches!(
+        status,
+        InvoiceStatus::Pending | InvoiceStatus::PartiallyPaid
+    )
+}
+
+/// Adds a freshly created invoice to the merchant and status indexes, or a
+/// backfilled one to the indexes for its current status.
+pub fn index_new_invoice(env: &Env, invoice: &Invoice) {
+    insert_id(
+        env,
+        InvoiceIndexKey::Merchant(invoice.merchant_id),
+        invoice.id,
+    );
+    insert_id(env, InvoiceIndexKey::Status(invoice.status), invoice.id);
+    if is_open(invoice.status) {
+        insert_id(env, InvoiceIndexKey::Open, invoice.id);
+    }
+    if let Some(payer) = &invoice.payer {
+        insert_id(env, InvoiceIndexKey::Payer(payer.clone()), invoice.id);
+    }
+}
+
+pub fn index_payer(env: &Env, payer: &Address, invoice_id: u64) {
+    insert_id(env, InvoiceIndexKey::Payer(payer.clone()), invoice_id);
+}
+
+/// Moves an invoice between status indexes and updates its status in place.
+/// The caller is still responsible for persisting the invoice.
+pub fn set_status(env: &Env, invoice: &mut Invoice, status: InvoiceStatus) {
+    if invoice.status == status {
+        return;
+    }
+    remove_id(env, InvoiceIndexKey::Status(invoice.status), invoice.id);
+    insert_id(env, InvoiceIndexKey::Status(status), invoice.id);
+    match (is_open(invoice.status), is_open(status)) {
+        (true, false) => remove_id(env, InvoiceIndexKey::Open, invoice.id),
+        (false, true) => insert_id(env, InvoiceIndexKey::Open, invoice.id),
+        _ => {}
+    }
+    invoice.status = status;
+}
+
+/// Indexes invoices created before the indexes existed, walking invoice IDs
+/// after `cursor` and looking at no more than `limit` of them. Invoices that
+/// are already indexed are left alone, so batches can safely be re-run; the
+/// backfilled ones are appended after any invoice indexed since the upgrade.
+/// Returns the cursor for the next batch, or `None` once every invoice was
+/// seen.
+pub fn backfill_invoice_indexes(
+    env: &Env,
+    admin: &Address,
+    cursor: Option<u64>,
+    limit: u32,
+) -> Option<u64> {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        panic_with_error!(env, ContractError::InvalidPageLimit);
+    }
+
+    let invoice_count: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::InvoiceCount)
+        .unwrap_or(0);
+    let start = cursor.unwrap_or(0);
+    let end = invoice_count.min(start.saturating_add(limit as u64));
+    for invoice_id in start.saturating_add(1)..=end {
+        if let Some(invoice) = env
+            .storage()
+            .persistent()
+            .get::<_, Invoice>(&DataKey::Invoice(invoice_id))
+        {
+            index_new_invoice(env, &invoice);
+        }
+    }
+
+    reentrancy::exit(env);
+    if end < invoice_count {
+        Some(end)
+    } else {
+        None
+    }
+}
+
+/// Every ID in `index`. Only for callers that load the whole index anyway;
+/// pages should go through `get_invoices_page`.
+pub fn get_index_ids(env: &Env, index: &InvoiceIndexKey) -> Vec<u64> {
+    let mut ids = Vec::new(env);
+    for position in 0..index_len(env, index) {
+        if let Some(invoice_id) = index_entry(env, index, position) {
+            ids.push_back(invoice_id);
+        }
+    }
+    ids
+}
+
+pub fn get_invoices_page(
+    env: &Env,
+    index: InvoiceIndex,
+    cursor: Option<u64>,
+    limit: u32,
+) -> InvoicePage {
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        panic_with_error!(env, ContractError::InvalidPageLimit);
+    }
+    let start = cursor.unwrap_or(0);
+
+    let mut invoices: Vec<Invoice> = Vec::new(env);
+    let (end, len) = match index {
+        InvoiceIndex::All => {
+            let invoice_count: u64 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::InvoiceCount)
+                .unwrap_or(0);
+            let end = invoice_count.min(start.saturating_add(limit as u64));
+            for id in start.saturating_add(1)..=end {
+                push_invoice(env, id, &mut invoices);
+            }
+            (end, invoice_count)
+        }
+        InvoiceIndex::Merchant(merchant_address) => {
+            let merchant_id = merchant::get_merchant_id(env, &merchant_address);
+            collect_page(
+                env,
+                &InvoiceIndexKey::Merchant(merchant_id),
+                start,
+                limit,
+                &mut invoices,
+            )
+        }
+        InvoiceIndex::Payer(payer) => collect_page(
+            env,
+            &InvoiceIndexKey::Payer(payer),
+            start,
+            limit,
+            &mut invoices,
+        ),
+        InvoiceIndex::Status(status) => collect_page(
+            env,
+            &InvoiceIndexKey::Status(status),
+            start,
+            limit,
+            &mut invoices,
+        ),
+    };
+
+    InvoicePage {
+        invoices,
+        next_cursor: if end < len { Some(end) } else { None },
+    }
+}
+
+fn push_invoice(env: &Env, invoice_id: u64, invoices: &mut Vec<Invoice>) {
+    if let Some(invoice) = env
+        .storage()
+        .persistent()
+        .get::<_, Invoice>(&DataKey::Invoice(invoice_id))
+    {
+        invoices.push_back(invoice);
+    }
+}
+
+// Loads the invoices in up to `limit` slots of `index` from position
+// `start`, skipping empty ones. Returns the position after the page and the
+// index length.
+fn collect_page(
+    env: &Env,
+    index: &InvoiceIndexKey,
+    start: u64,
+    limit: u32,
+    invoices: &mut Vec<Invoice>,
+) -> (u64, u64) {
+    let len = index_len(env, index) as u64;
+    let end = len.min(start.saturating_add(limit as u64));
+    for position in start..end {
+        if let Some(invoice_id) = index_entry(env, index, position as u32) {
+            push_invoice(env, invoice_id, invoices);
+        }
+    }
+    (end, len)
+}
@@ -0,0 +1,183 @@
+use crate::components::{admin, invoice, invoice_index, merchant};
+use crate::errors::{ContractError, InvoiceError};
+use crate::events;
+use crate::types::{InvoiceDataKey, InvoiceTemplate, InvoiceTemplateStatus};
+use soroban_sdk::{panic_with_error, Address, Env, String};
+
+/// Sets up a recurring invoice for `payer`. The first invoice can be issued
+/// straight away and one more becomes due every `interval` seconds after.
+pub fn create_invoice_template(
+    env: &Env,
+    merchant_address: &Address,
+    payer: &Address,
+    description: &String,
+    amount: i128,
+    token: &Address,
+    interval: u64,
+) -> u64 {
+    merchant_address.require_auth();
+    invoice::validate_invoice_creation(env, merchant_address, description, amount, token, None);
+    if interval == 0 {
+        panic_with_error!(env, ContractError::InvalidInterval);
+    }
+
+    let template_id: u64 = env
+        .storage()
+        .persistent()
+        .get(&InvoiceDataKey::InvoiceTemplateCount)
+        .unwrap_or(0)
+        + 1;
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::InvoiceTemplateCount, &template_id);
+
+    let now = env.ledger().timestamp();
+    let template = InvoiceTemplate {
+        id: template_id,
+        merchant_id: merchant::get_merchant_id(env, merchant_address),
+        merchant: merchant_address.clone(),
+        payer: payer.clone(),
+        description: description.clone(),
+        amount,
+        token: token.clone(),
+        interval,
+        next_issue_at: now,
+        issued_count: 0,
+        status: InvoiceTemplateStatus::Active,
+    };
+    save_template(env, &template);
+
+    events::publish_invoice_template_created_event(
+        env,
+        template_id,
+        merchant_address.clone(),
+        payer.clone(),
+        amount,
+        token.clone(),
+        interval,
+        now,
+    );
+    template_id
+}
+
+pub fn get_invoice_template(env: &Env, template_id: u64) -> InvoiceTemplate {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::InvoiceTemplate(template_id))
+        .unwrap_or_else(|| panic_with_error!(env, InvoiceError::InvoiceTemplateNotFound))
+}
+
+/// Stops issuing invoices until the template is resumed.
+pub fn pause_invoice_template(env: &Env, merchant_address: &Address, template_id: u64) {
+    let mut template = merchant_template(env, merchant_address, template_id);
+    if template.status != InvoiceTemplateStatus::Active {
+        panic_with_error!(env, InvoiceError::InvalidTemplateStatus);
+    }
+    set_template_status(env, &mut template, InvoiceTemplateStatus::Paused);
+}
+
+/// Restarts a paused template. Periods skipped while paused are not billed;
+/// the next invoice is due now at the earliest.
+pub fn resume_invoice_template(env: &Env, merchant_address: &Address, template_id: u64) {
+    let mut template = merchant_template(env, merchant_address, template_id);
+    if template.status != InvoiceTemplateStatus::Paused {
+        panic_with_error!(env, InvoiceError::InvalidTemplateStatus);
+    }
+    template.next_issue_at = template.next_issue_at.max(env.ledger().timestamp());
+    set_template_status(env, &mut template, InvoiceTemplateStatus::Active);
+}
+
+/// Permanently stops the template. Invoices already issued are unaffected.
+pub fn end_invoice_template(env: &Env, merchant_address: &Address, template_id: u64) {
+    let mut template = merchant_template(env, merchant_address, template_id);
+    if template.status == InvoiceTemplateStatus::Ended {
+        panic_with_error!(env, InvoiceError::InvalidTemplateStatus);
+    }
+    set_template_status(env, &mut template, InvoiceTemplateStatus::Ended);
+}
+
+/// Walks the templates after `cursor`, looking at no more than `limit` of
+/// them, and issues the next invoice from each active one that is due.
+/// Returns the cursor for the next call, or `None` once every template was
+/// visited.
+pub fn issue_due_invoices(env: &Env, cursor: Option<u64>, limit: u32) -> Option<u64> {
+    if limit == 0 || limit > invoice_index::MAX_PAGE_SIZE {
+        panic_with_error!(env, ContractError::InvalidPageLimit);
+    }
+
+    let template_count: u64 = env
+        .storage()
+        .persistent()
+        .get(&InvoiceDataKey::InvoiceTemplateCount)
+        .unwrap_or(0);
+    let start = cursor.unwrap_or(0) + 1;
+    let end = template_count.min(cursor.unwrap_or(0) + limit as u64);
+
+    let now = env.ledger().timestamp();
+    for template_id in start..=end {
+        let mut template = get_invoice_template(env, template_id);
+        if template.status != InvoiceTemplateStatus::Active || now < template.next_issue_at {
+            continue;
+        }
+        if !can_issue(env, &template) {
+            continue;
+        }
+
+        let invoice_id = invoice::create_template_invoice(env, &template);
+        template.next_issue_at += template.interval;
+        template.issued_count += 1;
+        save_template(env, &template);
+
+        events::publish_template_invoice_issued_event(
+            env,
+            template_id,
+            invoice_id,
+            template.payer.clone(),
+            now,
+        );
+    }
+
+    if end < template_count {
+        Some(end)
+    } else {
+        None
+    }
+}
+
+/// Repeats the checks `create_invoice` makes, without panicking. A token
+/// delisted or a merchant deactivated since the template was set up would
+/// make the invoice unpayable, so the template stays due until it is sorted
+/// out.
+fn can_issue(env: &Env, template: &InvoiceTemplate) -> bool {
+    admin::is_accepted_token(env, &template.token)
+        && merchant::is_token_accepted_for_merchant(env, &template.merchant, &template.token)
+        && merchant::is_merchant_active(env, template.merchant_id)
+        && template.amount
+            > admin::calculate_fee(env, &template.merchant, &template.token, template.amount)
+}
+
+fn merchant_template(env: &Env, merchant_address: &Address, template_id: u64) -> InvoiceTemplate {
+    merchant_address.require_auth();
+    let template = get_invoice_template(env, template_id);
+    if template.merchant != *merchant_address {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+    template
+}
+
+fn set_template_status(env: &Env, template: &mut InvoiceTemplate, status: InvoiceTemplateStatus) {
+    template.status = status;
+    save_template(env, template);
+    events::publish_invoice_template_updated_event(
+        env,
+        template.id,
+        status,
+        env.ledger().timestamp(),
+    );
+}
+
+fn save_template(env: &Env, template: &InvoiceTemplate) {
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::InvoiceTemplate(template.id), template);
+}
@@ -1,6 +1,7 @@
 use crate::components::access_control;
 use crate::components::admin as admin_component;
 use crate::components::core as core_component;
+use crate::components::referral;
 use crate::errors::ContractError;
 use crate::events;
 use crate::types::{DataKey, Merchant, MerchantFilter, Role};
@@ -11,7 +12,7 @@ pub trait MerchantAccountContract {
     fn restrict_account(env: Env, status: bool);
 }
 
-pub fn register_merchant(env: &Env, merchant: &Address) {
+pub fn register_merchant(env: &Env, merchant: &Address, referrer: Option<Address>) {
     merchant.require_auth();
 
     if env
@@ -49,11 +50,15 @@ pub fn register_merchant(env: &Env, merchant: &Address) {
     env.storage()
         .persistent()
         .set(&DataKey::MerchantCount, &new_id);
+    if let Some(referrer) = &referrer {
+        referral::record_referral(env, merchant, referrer);
+    }
 
     events::publish_merchant_registered_event(
         env,
         merchant.clone(),
         new_id,
+        referrer,
         env.ledger().timestamp(),
     );
 }
@@ -2,13 +2,27 @@ pub mod access_control;
 pub mod account_factory;
 pub mod admin;
 pub mod core;
+pub mod credit_note;
+pub mod event;
+pub mod fee_override;
+pub mod history;
+pub mod installments;
 pub mod invoice;
+pub mod invoice_expiry;
+pub mod invoice_index;
+pub mod invoice_template;
 pub mod merchant;
 pub mod pausable;
 pub mod payment;
+pub mod payment_terms;
 pub mod reentrancy;
+pub mod referral;
+pub mod refund_policy;
+pub mod refund_request;
 pub mod signature_util;
+pub mod split_bill;
 pub mod subscription;
-pub mod history;
+pub mod tax;
+pub mod tips;
+pub mod treasury;
 pub mod upgrade;
-pub mod event;
@@ -0,0 +1,107 @@
+use crate::components::{installments, invoice, merchant};
+use crate::errors::{ContractError, InvoiceError};
+use crate::events;
+use crate::types::{DataKey, Invoice, InvoiceStatus, PaymentTerms, PaymentTermsData};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+const MAX_BPS: u32 = 10_000;
+
+/// Sets or clears the discount and penalty terms of a pending or draft
+/// invoice. Terms cannot be combined with an installment plan, whose amounts
+/// are fixed up front.
+pub fn set_invoice_terms(
+    env: &Env,
+    merchant_address: &Address,
+    invoice_id: u64,
+    terms: Option<PaymentTerms>,
+) {
+    merchant_address.require_auth();
+
+    let mut invoice = invoice::get_invoice(env, invoice_id);
+    let merchant_id = merchant::get_merchant_id(env, merchant_address);
+    if invoice.merchant_id != merchant_id {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+
+    if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Draft {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+
+    if let Some(terms) = &terms {
+        validate_terms(env, terms);
+        installments::assert_no_installment_plan(env, invoice_id);
+    }
+
+    invoice.payment_terms = match terms {
+        Some(terms) => PaymentTermsData::Some(terms),
+        None => PaymentTermsData::None,
+    };
+    let (amount, description) = (invoice.amount, invoice.description.clone());
+    invoice::record_revision(env, &mut invoice, merchant_address, amount, description);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+
+    publish_terms_set(env, &invoice);
+}
+
+/// Rejects terms with a full discount, a penalty above the subtotal or a
+/// discount deadline after the due date.
+pub fn validate_terms(env: &Env, terms: &PaymentTerms) {
+    // A full discount would leave nothing but tax to pay.
+    if terms.discount_bps >= MAX_BPS
+        || terms.penalty_bps > MAX_BPS
+        || terms.discount_deadline > terms.due_date
+    {
+        panic_with_error!(env, InvoiceError::InvalidPaymentTerms);
+    }
+}
+
+/// Publishes the invoice's current terms; cleared terms are reported as zeros.
+pub fn publish_terms_set(env: &Env, invoice: &Invoice) {
+    let terms = match &invoice.payment_terms {
+        PaymentTermsData::Some(terms) => terms.clone(),
+        PaymentTermsData::None => PaymentTerms {
+            discount_bps: 0,
+            discount_deadline: 0,
+            penalty_bps: 0,
+            due_date: 0,
+        },
+    };
+    events::publish_invoice_terms_set_event(
+        env,
+        invoice.id,
+        invoice.merchant_id,
+        terms.discount_bps,
+        terms.discount_deadline,
+        terms.penalty_bps,
+        terms.due_date,
+        env.ledger().timestamp(),
+    );
+}
+
+/// Returns the `(discount, penalty)` the invoice's terms give for a payment
+/// made now. Both are charged on the subtotal, so tax is unaffected.
+pub fn resolve_adjustment(env: &Env, invoice: &Invoice) -> (i128, i128) {
+    let terms = match &invoice.payment_terms {
+        PaymentTermsData::Some(terms) => terms,
+        PaymentTermsData::None => return (0, 0),
+    };
+
+    let now = env.ledger().timestamp();
+    let bps_of_subtotal = |bps: u32| {
+        invoice
+            .subtotal
+            .checked_mul(bps as i128)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::InvalidAmount))
+            / MAX_BPS as i128
+    };
+
+    if now <= terms.discount_deadline {
+        (bps_of_subtotal(terms.discount_bps), 0)
+    } else if now > terms.due_date {
+        (0, bps_of_subtotal(terms.penalty_bps))
+    } else {
+        (0, 0)
+    }
+}
@@ -0,0 +1,104 @@
+use crate::components::{core, reentrancy};
+use crate::errors::FeeError;
+use crate::events;
+use crate::types::{FeeDataKey, MerchantReferral, ReferralConfig};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+/// Sets the referrer share of platform fees and how long after registration
+/// a referred merchant keeps earning it for their referrer. Applies to
+/// existing referrals as well as new ones.
+pub fn set_referral_config(env: &Env, admin: &Address, config: &ReferralConfig) {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
+    if config.share_bps < 0 || config.share_bps > 10_000 {
+        panic_with_error!(env, FeeError::InvalidReferralConfig);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&FeeDataKey::ReferralConfig, config);
+    events::publish_referral_config_set_event(
+        env,
+        admin.clone(),
+        config.share_bps,
+        config.duration,
+        env.ledger().timestamp(),
+    );
+    reentrancy::exit(env);
+}
+
+pub fn get_referral_config(env: &Env) -> ReferralConfig {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::ReferralConfig)
+        .unwrap_or(ReferralConfig {
+            share_bps: 0,
+            duration: 0,
+        })
+}
+
+pub fn record_referral(env: &Env, merchant: &Address, referrer: &Address) {
+    if referrer == merchant {
+        panic_with_error!(env, FeeError::InvalidReferrer);
+    }
+    env.storage().persistent().set(
+        &FeeDataKey::MerchantReferral(merchant.clone()),
+        &MerchantReferral {
+            referrer: referrer.clone(),
+            referred_at: env.ledger().timestamp(),
+        },
+    );
+}
+
+pub fn get_merchant_referral(env: &Env, merchant: &Address) -> Option<MerchantReferral> {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::MerchantReferral(merchant.clone()))
+}
+
+pub fn get_referral_earnings(env: &Env, referrer: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::ReferralEarnings(
+            referrer.clone(),
+            token.clone(),
+        ))
+        .unwrap_or(0)
+}
+
+/// Splits a platform fee the merchant is paying into the part kept by the
+/// platform and, while the merchant's referral is running, the referrer's
+/// share. The share is credited to the referrer's earnings; the caller
+/// moves the tokens.
+pub fn split_platform_fee(
+    env: &Env,
+    merchant: &Address,
+    token: &Address,
+    fee: i128,
+) -> (i128, Option<(Address, i128)>) {
+    let Some(referral) = get_merchant_referral(env, merchant) else {
+        return (fee, None);
+    };
+    let config = get_referral_config(env);
+    let now = env.ledger().timestamp();
+    if now >= referral.referred_at.saturating_add(config.duration) {
+        return (fee, None);
+    }
+    let share = fee * config.share_bps / 10_000;
+    if share <= 0 {
+        return (fee, None);
+    }
+
+    let earnings_key = FeeDataKey::ReferralEarnings(referral.referrer.clone(), token.clone());
+    let earnings = get_referral_earnings(env, &referral.referrer, token) + share;
+    env.storage().persistent().set(&earnings_key, &earnings);
+    events::publish_referral_fee_paid_event(
+        env,
+        referral.referrer.clone(),
+        merchant.clone(),
+        token.clone(),
+        share,
+        now,
+    );
+    (fee - share, Some((referral.referrer, share)))
+}
@@ -0,0 +1,128 @@
+use crate::components::{core, merchant, reentrancy};
+use crate::errors::{ContractError, InvoiceError};
+use crate::events;
+use crate::types::{InvoiceDataKey, RefundWindowBounds};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+pub const DEFAULT_REFUND_WINDOW: u64 = 604_800; // 7 days
+pub const DEFAULT_MAX_REFUND_WINDOW: u64 = 7_776_000; // 90 days
+
+/// Limits every merchant refund window is clamped to. A `min` of zero lets
+/// merchants opt out of refunds entirely.
+pub fn set_refund_window_bounds(env: &Env, admin: &Address, bounds: &RefundWindowBounds) {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
+
+    if bounds.min > bounds.max {
+        panic_with_error!(env, InvoiceError::InvalidRefundWindow);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::RefundWindowBounds, bounds);
+
+    events::publish_refund_window_bounds_set_event(
+        env,
+        admin.clone(),
+        bounds.min,
+        bounds.max,
+        env.ledger().timestamp(),
+    );
+    reentrancy::exit(env);
+}
+
+pub fn get_refund_window_bounds(env: &Env) -> RefundWindowBounds {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::RefundWindowBounds)
+        .unwrap_or(RefundWindowBounds {
+            min: 0,
+            max: DEFAULT_MAX_REFUND_WINDOW,
+        })
+}
+
+/// Sets how long after payment the merchant's invoices can be refunded, either
+/// for all tokens or, with `token`, for invoices settled in that token only.
+/// A window of zero disables refunds.
+pub fn set_merchant_refund_window(
+    env: &Env,
+    merchant_address: &Address,
+    token: &Option<Address>,
+    window: u64,
+) {
+    reentrancy::enter(env);
+    merchant_address.require_auth();
+    let merchant_id = registered_merchant_id(env, merchant_address);
+
+    let bounds = get_refund_window_bounds(env);
+    if window < bounds.min || window > bounds.max {
+        panic_with_error!(env, InvoiceError::InvalidRefundWindow);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&window_key(merchant_id, token), &window);
+
+    events::publish_merchant_refund_window_set_event(
+        env,
+        merchant_address.clone(),
+        token.clone(),
+        window,
+        env.ledger().timestamp(),
+    );
+    reentrancy::exit(env);
+}
+
+/// Drops a merchant's window, falling back to the merchant-wide window for a
+/// token rule and to the platform default otherwise.
+pub fn remove_merchant_refund_window(
+    env: &Env,
+    merchant_address: &Address,
+    token: &Option<Address>,
+) {
+    reentrancy::enter(env);
+    merchant_address.require_auth();
+    let merchant_id = registered_merchant_id(env, merchant_address);
+
+    env.storage()
+        .persistent()
+        .remove(&window_key(merchant_id, token));
+
+    events::publish_refund_window_removed_event(
+        env,
+        merchant_address.clone(),
+        token.clone(),
+        env.ledger().timestamp(),
+    );
+    reentrancy::exit(env);
+}
+
+/// Refund window that applies to the merchant's invoices settled in `token`,
+/// clamped to the current admin bounds.
+pub fn get_refund_window(env: &Env, merchant_id: u64, token: &Address) -> u64 {
+    let storage = env.storage().persistent();
+    let window: u64 = storage
+        .get(&InvoiceDataKey::MerchantTokenRefundWindow(
+            merchant_id,
+            token.clone(),
+        ))
+        .or_else(|| storage.get(&InvoiceDataKey::MerchantRefundWindow(merchant_id)))
+        .unwrap_or(DEFAULT_REFUND_WINDOW);
+
+    let bounds = get_refund_window_bounds(env);
+    window.clamp(bounds.min, bounds.max)
+}
+
+fn window_key(merchant_id: u64, token: &Option<Address>) -> InvoiceDataKey {
+    match token {
+        Some(token) => InvoiceDataKey::MerchantTokenRefundWindow(merchant_id, token.clone()),
+        None => InvoiceDataKey::MerchantRefundWindow(merchant_id),
+    }
+}
+
+fn registered_merchant_id(env: &Env, merchant_address: &Address) -> u64 {
+    if !merchant::is_merchant(env, merchant_address) {
+        panic_with_error!(env, ContractError::MerchantNotFound);
+    }
+    merchant::get_merchant_id(env, merchant_address)
+}
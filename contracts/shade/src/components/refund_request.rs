@@ -0,0 +1,161 @@
+use crate::components::{access_control, invoice, merchant, split_bill};
+use crate::errors::{ContractError, InvoiceError};
+use crate::events;
+use crate::types::{InvoiceDataKey, InvoiceStatus, RefundRequest, RefundRequestStatus, Role};
+use soroban_sdk::{panic_with_error, Address, BytesN, Env};
+
+pub const REFUND_RESPONSE_WINDOW: u64 = 259_200; // 3 days
+
+/// Opens a refund request on a paid invoice. Only the invoice's payer may ask,
+/// or on a split bill any contributor up to what they still have in. Requests
+/// must come within the usual refund window and only one can be open at a time.
+pub fn request_refund(
+    env: &Env,
+    payer: &Address,
+    invoice_id: u64,
+    amount: i128,
+    reason_hash: &BytesN<32>,
+) {
+    payer.require_auth();
+
+    let invoice = invoice::get_invoice(env, invoice_id);
+    let mut refundable = invoice::refundable_amount(&invoice);
+    if invoice.split_bill {
+        let contribution = split_bill::unrefunded_contribution(env, invoice_id, payer)
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::NotAuthorized));
+        refundable = refundable.min(contribution);
+    } else if invoice.payer.as_ref() != Some(payer) {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+    if invoice.status != InvoiceStatus::Paid && invoice.status != InvoiceStatus::PartiallyRefunded {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+    invoice::check_refund_window(env, &invoice);
+
+    if amount <= 0 || amount > refundable {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+
+    let key = InvoiceDataKey::RefundRequest(invoice_id);
+    if let Some(existing) = env.storage().persistent().get::<_, RefundRequest>(&key) {
+        if existing.status == RefundRequestStatus::Requested
+            || existing.status == RefundRequestStatus::Rejected
+        {
+            panic_with_error!(env, InvoiceError::RefundRequestOpen);
+        }
+    }
+
+    let now = env.ledger().timestamp();
+    let request = RefundRequest {
+        invoice_id,
+        payer: payer.clone(),
+        amount,
+        reason_hash: reason_hash.clone(),
+        status: RefundRequestStatus::Requested,
+        requested_at: now,
+        respond_by: now + REFUND_RESPONSE_WINDOW,
+        resolved_at: None,
+    };
+    env.storage().persistent().set(&key, &request);
+
+    events::publish_refund_requested_event(
+        env,
+        invoice_id,
+        payer.clone(),
+        amount,
+        reason_hash.clone(),
+        request.respond_by,
+        now,
+    );
+}
+
+/// Merchant's answer to an open request. Accepting refunds the requested
+/// amount straight away; rejecting leaves the request open to arbitration.
+pub fn respond_to_refund_request(
+    env: &Env,
+    merchant_address: &Address,
+    invoice_id: u64,
+    accept: bool,
+) {
+    merchant_address.require_auth();
+
+    let invoice = invoice::get_invoice(env, invoice_id);
+    let merchant_id = merchant::get_merchant_id(env, merchant_address);
+    if invoice.merchant_id != merchant_id {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+
+    let mut request = get_refund_request(env, invoice_id);
+    if request.status != RefundRequestStatus::Requested {
+        panic_with_error!(env, InvoiceError::InvalidRefundRequestStatus);
+    }
+
+    let now = env.ledger().timestamp();
+    if accept {
+        invoice::issue_refund(env, invoice_id, request.amount, Some(&request.payer));
+        request.status = RefundRequestStatus::Accepted;
+        request.resolved_at = Some(now);
+        events::publish_refund_request_accepted_event(
+            env,
+            invoice_id,
+            merchant_address.clone(),
+            request.amount,
+            now,
+        );
+    } else {
+        request.status = RefundRequestStatus::Rejected;
+        events::publish_refund_request_rejected_event(
+            env,
+            invoice_id,
+            merchant_address.clone(),
+            now,
+        );
+    }
+
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::RefundRequest(invoice_id), &request);
+}
+
+/// Rules on a request the merchant rejected or left unanswered past
+/// `respond_by`. Open to the admin and to holders of `Role::Manager`.
+pub fn arbitrate_refund_request(env: &Env, arbiter: &Address, invoice_id: u64, approve: bool) {
+    arbiter.require_auth();
+    if !access_control::has_role(env, arbiter, Role::Manager) {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+
+    let mut request = get_refund_request(env, invoice_id);
+    let now = env.ledger().timestamp();
+    let unanswered = request.status == RefundRequestStatus::Requested && now > request.respond_by;
+    if request.status != RefundRequestStatus::Rejected && !unanswered {
+        panic_with_error!(env, InvoiceError::InvalidRefundRequestStatus);
+    }
+
+    if approve {
+        invoice::issue_refund(env, invoice_id, request.amount, Some(&request.payer));
+        request.status = RefundRequestStatus::Approved;
+    } else {
+        request.status = RefundRequestStatus::Denied;
+    }
+    request.resolved_at = Some(now);
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::RefundRequest(invoice_id), &request);
+
+    events::publish_refund_request_arbitrated_event(
+        env,
+        invoice_id,
+        arbiter.clone(),
+        approve,
+        request.amount,
+        now,
+    );
+}
+
+pub fn get_refund_request(env: &Env, invoice_id: u64) -> RefundRequest {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::RefundRequest(invoice_id))
+        .unwrap_or_else(|| panic_with_error!(env, InvoiceError::RefundRequestNotFound))
+}
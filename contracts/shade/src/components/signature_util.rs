@@ -1,74 +1,76 @@
-use crate::errors::ContractError;
-use crate::events::publish_nonce_invalidated_event;
-use crate::types::DataKey;
+use crate::errors::{ContractError, InvoiceError};
+use crate::events::{publish_nonce_invalidated_event, publish_nonces_purged_event};
+use crate::types::{DataKey, SignedInvoicePayload};
 use soroban_sdk::xdr::ToXdr;
-use soroban_sdk::{panic_with_error, Address, Bytes, BytesN, Env, String};
+use soroban_sdk::{panic_with_error, Address, Bytes, BytesN, Env, TryFromVal, Val, Vec};
+
+/// Payload layout accepted by `create_invoice_signed`.
+pub const SIGNED_INVOICE_VERSION: u32 = 1;
+
+/// Domain tag that keeps invoice signatures from being valid for any other
+/// message a merchant key might sign.
+const SIGNED_INVOICE_DOMAIN: &[u8] = b"shade:signed-invoice";
+
+/// Furthest ahead a signature deadline may be. Used nonces are kept until the
+/// deadline passes, so this bounds how long they occupy storage.
+pub const MAX_SIGNATURE_LIFETIME: u64 = 30 * 86_400;
 
 /// Builds the message that the merchant must have signed.
 ///
-/// Format: "contract_address|merchant_address|nonce|amount|token_address|description_bytes"
-fn build_message(
-    env: &Env,
-    merchant: &Address,
-    description: &String,
-    amount: i128,
-    token: &Address,
-    nonce: &BytesN<32>,
-) -> Bytes {
-    let mut msg = Bytes::new(env);
+/// Format: domain tag, contract address XDR, network ID (the SHA-256 of the
+/// network passphrase), then the XDR of the whole payload.
+fn build_message(env: &Env, payload: &SignedInvoicePayload) -> Bytes {
+    let mut msg = Bytes::from_slice(env, SIGNED_INVOICE_DOMAIN);
     msg.append(&env.current_contract_address().to_xdr(env));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&merchant.clone().to_xdr(env));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(nonce.as_ref());
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&Bytes::from_slice(env, &amount.to_be_bytes()));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&token.clone().to_xdr(env));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&description.clone().to_xdr(env));
+    msg.append(env.ledger().network_id().as_ref());
+    msg.append(&payload.clone().to_xdr(env));
     msg
 }
 
-/// Verifies the merchant's ed25519 signature over the invoice parameters.
+/// Verifies the merchant's ed25519 signature over a signed invoice payload.
 ///
-/// Retrieves the merchant's public key from `DataKey::MerchantKey(merchant)`.
-/// Panics with `MerchantKeyNotFound` if the key is not registered.
+/// Panics with `UnsupportedPayloadVersion` for an unknown layout, with
+/// `SignatureExpired` once the deadline has passed, with
+/// `SignatureDeadlineTooFar` if it is more than `MAX_SIGNATURE_LIFETIME` away
+/// and with `MerchantKeyNotFound` if the merchant has no key registered.
 /// Panics with a crypto error if the signature is invalid.
-pub fn verify_invoice_signature(
-    env: &Env,
-    merchant: &Address,
-    description: &String,
-    amount: i128,
-    token: &Address,
-    nonce: &BytesN<32>,
-    signature: &BytesN<64>,
-) {
+pub fn verify_invoice_signature(env: &Env, payload: &SignedInvoicePayload, signature: &BytesN<64>) {
+    if payload.version != SIGNED_INVOICE_VERSION {
+        panic_with_error!(env, InvoiceError::UnsupportedPayloadVersion);
+    }
+    let now = env.ledger().timestamp();
+    if now > payload.deadline {
+        panic_with_error!(env, InvoiceError::SignatureExpired);
+    }
+    if payload.deadline - now > MAX_SIGNATURE_LIFETIME {
+        panic_with_error!(env, InvoiceError::SignatureDeadlineTooFar);
+    }
+
     let key: BytesN<32> = env
         .storage()
         .persistent()
-        .get(&DataKey::MerchantKey(merchant.clone()))
+        .get(&DataKey::MerchantKey(payload.merchant.clone()))
         .unwrap_or_else(|| panic_with_error!(env, ContractError::MerchantKeyNotFound));
 
-    invalidate_nonce(env, merchant, nonce);
+    invalidate_nonce(env, &payload.merchant, &payload.nonce, payload.deadline);
 
-    let message = build_message(env, merchant, description, amount, token, nonce);
+    let message = build_message(env, payload);
 
     env.crypto().ed25519_verify(&key, &message, signature);
 }
 
-/// Checks the nonce has not been used and marks it as used.
+/// Checks the nonce has not been used and marks it as used until `deadline`.
 ///
 /// Panics with `NonceAlreadyUsed` if the nonce was already consumed.
 /// Emits a `NonceInvalidated` event on success.
-pub fn invalidate_nonce(env: &Env, merchant: &Address, nonce: &BytesN<32>) {
+pub fn invalidate_nonce(env: &Env, merchant: &Address, nonce: &BytesN<32>, deadline: u64) {
     let nonce_key = DataKey::UsedNonce(merchant.clone(), nonce.clone());
 
     if env.storage().persistent().has(&nonce_key) {
         panic_with_error!(env, ContractError::NonceAlreadyUsed);
     }
 
-    env.storage().persistent().set(&nonce_key, &true);
+    env.storage().persistent().set(&nonce_key, &deadline);
 
     publish_nonce_invalidated_event(
         env,
@@ -77,3 +79,30 @@ pub fn invalidate_nonce(env: &Env, merchant: &Address, nonce: &BytesN<32>) {
         env.ledger().timestamp(),
     );
 }
+
+/// Drops used nonces whose signature deadline has passed. Such signatures are
+/// rejected on the deadline alone, so the record is no longer needed.
+/// Unknown and still-live nonces are skipped, as are nonces stored as `true`
+/// before deadlines were recorded: with no deadline to go on they must stay.
+/// Returns how many were removed.
+pub fn purge_used_nonces(env: &Env, merchant: &Address, nonces: &Vec<BytesN<32>>) -> u32 {
+    let now = env.ledger().timestamp();
+    let mut purged = 0;
+    for nonce in nonces.iter() {
+        let nonce_key = DataKey::UsedNonce(merchant.clone(), nonce);
+        let deadline = env
+            .storage()
+            .persistent()
+            .get::<_, Val>(&nonce_key)
+            .and_then(|value| u64::try_from_val(env, &value).ok());
+        if matches!(deadline, Some(deadline) if deadline < now) {
+            env.storage().persistent().remove(&nonce_key);
+            purged += 1;
+        }
+    }
+
+    if purged > 0 {
+        publish_nonces_purged_event(env, merchant.clone(), purged, now);
+    }
+    purged
+}
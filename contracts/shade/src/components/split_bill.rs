@@ -0,0 +1,213 @@
+use crate::components::invoice::MerchantAccountRefundClient;
+use crate::components::{admin, invoice, invoice_index, merchant};
+use crate::errors::{ContractError, InvoiceError};
+use crate::events;
+use crate::types::{DataKey, Invoice, InvoiceDataKey, InvoiceStatus, PayerContribution};
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+pub const MAX_SPLIT_PAYERS: u32 = 20;
+
+/// Lets any number of payers (up to `MAX_SPLIT_PAYERS`) share an invoice.
+/// Only for unpaid invoices that are not restricted to a single payer.
+pub fn set_invoice_split_bill(
+    env: &Env,
+    merchant_address: &Address,
+    invoice_id: u64,
+    enabled: bool,
+) {
+    merchant_address.require_auth();
+
+    let mut invoice = invoice::get_invoice(env, invoice_id);
+    if invoice.merchant_id != merchant::get_merchant_id(env, merchant_address) {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+    if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Draft {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+    if invoice.payer.is_some() {
+        panic_with_error!(env, ContractError::InvalidInvoiceStatus);
+    }
+
+    invoice.split_bill = enabled;
+    let (amount, description) = (invoice.amount, invoice.description.clone());
+    invoice::record_revision(env, &mut invoice, merchant_address, amount, description);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Invoice(invoice_id), &invoice);
+
+    events::publish_invoice_split_bill_set_event(
+        env,
+        invoice_id,
+        enabled,
+        env.ledger().timestamp(),
+    );
+}
+
+/// What each payer has put into a split-bill invoice and had refunded, in
+/// order of their first payment.
+pub fn get_invoice_contributions(env: &Env, invoice_id: u64) -> Vec<PayerContribution> {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::InvoiceContributions(invoice_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Adds a payment to the payer's running contribution.
+pub fn record_contribution(env: &Env, invoice: &Invoice, payer: &Address, amount: i128) {
+    let mut contributions = get_invoice_contributions(env, invoice.id);
+    match contributions.iter().position(|c| c.payer == *payer) {
+        Some(index) => {
+            let mut contribution = contributions.get_unchecked(index as u32);
+            contribution.amount_paid += amount;
+            contributions.set(index as u32, contribution);
+        }
+        None => {
+            if contributions.len() >= MAX_SPLIT_PAYERS {
+                panic_with_error!(env, InvoiceError::SplitBillPayerLimit);
+            }
+            contributions.push_back(PayerContribution {
+                payer: payer.clone(),
+                amount_paid: amount,
+                amount_refunded: 0,
+            });
+            invoice_index::index_payer(env, payer, invoice.id);
+        }
+    }
+    save_contributions(env, invoice.id, &contributions);
+}
+
+/// What `payer` has put into a split-bill invoice and not had refunded yet,
+/// or `None` if they never contributed.
+pub fn unrefunded_contribution(env: &Env, invoice_id: u64, payer: &Address) -> Option<i128> {
+    get_invoice_contributions(env, invoice_id)
+        .iter()
+        .find(|c| c.payer == *payer)
+        .map(|c| c.amount_paid - c.amount_refunded)
+}
+
+/// Refunds one payer of a split-bill invoice out of their own contribution.
+pub fn refund_invoice_payer(
+    env: &Env,
+    merchant_address: &Address,
+    invoice_id: u64,
+    payer: &Address,
+    amount: i128,
+) {
+    merchant_address.require_auth();
+    let invoice = invoice::get_invoice(env, invoice_id);
+    if invoice.merchant_id != merchant::get_merchant_id(env, merchant_address) {
+        panic_with_error!(env, ContractError::NotAuthorized);
+    }
+    if !invoice.split_bill {
+        panic_with_error!(env, InvoiceError::ContributionNotFound);
+    }
+
+    invoice::check_refund_window(env, &invoice);
+    invoice::issue_refund(env, invoice_id, amount, Some(payer));
+}
+
+/// Sends `amount` from the merchant account back to whoever paid the invoice.
+/// Single-payer invoices refund `invoice.payer`. Split bills refund `payer`
+/// from their contribution, or every payer pro-rata to what they still have
+/// in when `payer` is `None`. The caller checks the merchant account balance.
+pub fn refund_payers(env: &Env, invoice: &Invoice, amount: i128, payer: Option<&Address>) {
+    let merchant_account = merchant::get_merchant_account(env, invoice.merchant_id);
+    let refund_client = MerchantAccountRefundClient::new(env, &merchant_account);
+    let token = invoice::settlement_token(invoice);
+    let merchant_address = merchant::get_merchant(env, invoice.merchant_id).address;
+    admin::record_merchant_refund(env, &merchant_address, &token, amount, invoice.date_paid);
+
+    if !invoice.split_bill {
+        let invoice_payer = invoice
+            .payer
+            .clone()
+            .unwrap_or_else(|| panic_with_error!(env, ContractError::PayerNotAvailable));
+        if payer.is_some_and(|payer| *payer != invoice_payer) {
+            panic_with_error!(env, InvoiceError::ContributionNotFound);
+        }
+        refund_client.refund(&token, &amount, &invoice_payer);
+        return;
+    }
+
+    let mut contributions = get_invoice_contributions(env, invoice.id);
+    let shares = match payer {
+        Some(payer) => single_share(env, &contributions, payer, amount),
+        None => pro_rata_shares(env, &contributions, amount),
+    };
+
+    for (index, share) in shares.iter().enumerate() {
+        if share == 0 {
+            continue;
+        }
+        let mut contribution = contributions.get_unchecked(index as u32);
+        contribution.amount_refunded += share;
+        refund_client.refund(&token, &share, &contribution.payer);
+        contributions.set(index as u32, contribution);
+    }
+    save_contributions(env, invoice.id, &contributions);
+}
+
+fn single_share(
+    env: &Env,
+    contributions: &Vec<PayerContribution>,
+    payer: &Address,
+    amount: i128,
+) -> Vec<i128> {
+    let mut shares = Vec::new(env);
+    let mut found = false;
+    for contribution in contributions.iter() {
+        if contribution.payer == *payer {
+            if amount > contribution.amount_paid - contribution.amount_refunded {
+                panic_with_error!(env, ContractError::InvalidAmount);
+            }
+            shares.push_back(amount);
+            found = true;
+        } else {
+            shares.push_back(0);
+        }
+    }
+    if !found {
+        panic_with_error!(env, InvoiceError::ContributionNotFound);
+    }
+    shares
+}
+
+/// Splits `amount` in proportion to each payer's unrefunded contribution.
+/// Rounding leftovers go one unit at a time to payers with room left, so the
+/// shares always add up to `amount`.
+fn pro_rata_shares(env: &Env, contributions: &Vec<PayerContribution>, amount: i128) -> Vec<i128> {
+    let total: i128 = contributions
+        .iter()
+        .map(|c| c.amount_paid - c.amount_refunded)
+        .sum();
+    if amount > total {
+        panic_with_error!(env, ContractError::InvalidAmount);
+    }
+
+    let mut shares = Vec::new(env);
+    let mut remaining = amount;
+    for contribution in contributions.iter() {
+        let share = amount * (contribution.amount_paid - contribution.amount_refunded) / total;
+        shares.push_back(share);
+        remaining -= share;
+    }
+
+    let mut index = 0;
+    while remaining > 0 {
+        let contribution = contributions.get_unchecked(index);
+        let share = shares.get_unchecked(index);
+        if share < contribution.amount_paid - contribution.amount_refunded {
+            shares.set(index, share + 1);
+            remaining -= 1;
+        }
+        index = (index + 1) % contributions.len();
+    }
+    shares
+}
+
+fn save_contributions(env: &Env, invoice_id: u64, contributions: &Vec<PayerContribution>) {
+    env.storage().persistent().set(
+        &InvoiceDataKey::InvoiceContributions(invoice_id),
+        contributions,
+    );
+}
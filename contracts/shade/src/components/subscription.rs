@@ -1,8 +1,10 @@
-use crate::components::{admin, history, merchant};
+use crate::components::{admin, history, merchant, referral, treasury};
 use crate::errors::ContractError;
 use crate::events;
-use crate::types::{DataKey, Subscription, SubscriptionPlan, SubscriptionStatus, Transaction, TransactionType};
-use soroban_sdk::{panic_with_error, token, Address, Env, String};
+use crate::types::{
+    DataKey, Subscription, SubscriptionPlan, SubscriptionStatus, Transaction, TransactionType,
+};
+use soroban_sdk::{panic_with_error, Address, Env, String, Vec};
 
 // TODO: create a functionality for bulk subscription plan charging
 // TODO: create a functionality for charging all the subscription in a plan
@@ -142,19 +144,25 @@ pub fn charge_subscription(env: &Env, subscription_id: u64) {
         panic_with_error!(env, ContractError::ChargeTooEarly);
     }
 
-    let fee = admin::calculate_fee(env, &plan.merchant, &plan.token, plan.amount);
+    let fee = admin::charge_fee(env, &plan.merchant, &plan.token, plan.amount);
     let merchant_amount = plan.amount - fee;
 
-    let token_client = token::TokenClient::new(env, &plan.token);
     let merchant_account = merchant::get_merchant_account(env, plan.merchant_id);
-    let platform_account = admin::get_platform_account(env);
-    let spender = env.current_contract_address();
-
-    token_client.transfer_from(&spender, &sub.customer, &merchant_account, &merchant_amount);
-    if fee > 0 {
-        token_client.transfer_from(&spender, &sub.customer, &platform_account, &fee);
+    let mut payouts = Vec::from_array(env, [(merchant_account, merchant_amount)]);
+    let (platform_fee, referral_share) =
+        referral::split_platform_fee(env, &plan.merchant, &plan.token, fee);
+    if let Some(referral_share) = referral_share {
+        payouts.push_back(referral_share);
     }
-    admin::record_merchant_payment(env, &plan.merchant, &plan.token, plan.amount, fee);
+    treasury::route_payment(
+        env,
+        &plan.token,
+        &sub.customer,
+        true,
+        platform_fee,
+        &payouts,
+    );
+    admin::record_merchant_payment(env, &plan.merchant, &plan.token, plan.amount, fee, 0);
 
     sub.last_charged = now;
     env.storage()
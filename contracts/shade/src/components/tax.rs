@@ -0,0 +1,68 @@
+use crate::components::merchant;
+use crate::errors::{ContractError, InvoiceError};
+use crate::types::InvoiceDataKey;
+use soroban_sdk::{panic_with_error, Address, Env, String};
+
+pub const MAX_TAX_CODE_LENGTH: u32 = 16;
+pub const MAX_TAX_RATE_BPS: u32 = 10_000;
+
+fn registered_merchant_id(env: &Env, merchant: &Address) -> u64 {
+    if !merchant::is_merchant(env, merchant) {
+        panic_with_error!(env, ContractError::MerchantNotFound);
+    }
+    merchant::get_merchant_id(env, merchant)
+}
+
+pub fn set_merchant_tax_rate(env: &Env, merchant: &Address, code: &String, rate_bps: u32) {
+    merchant.require_auth();
+    let merchant_id = registered_merchant_id(env, merchant);
+
+    if code.is_empty() || code.len() > MAX_TAX_CODE_LENGTH || rate_bps > MAX_TAX_RATE_BPS {
+        panic_with_error!(env, InvoiceError::InvalidTaxRate);
+    }
+
+    env.storage().persistent().set(
+        &InvoiceDataKey::MerchantTaxRate(merchant_id, code.clone()),
+        &rate_bps,
+    );
+}
+
+pub fn remove_merchant_tax_rate(env: &Env, merchant: &Address, code: &String) {
+    merchant.require_auth();
+    let merchant_id = registered_merchant_id(env, merchant);
+
+    let key = InvoiceDataKey::MerchantTaxRate(merchant_id, code.clone());
+    if !env.storage().persistent().has(&key) {
+        panic_with_error!(env, InvoiceError::TaxRateNotFound);
+    }
+    env.storage().persistent().remove(&key);
+}
+
+pub fn get_merchant_tax_rate(env: &Env, merchant_id: u64, code: &String) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::MerchantTaxRate(merchant_id, code.clone()))
+        .unwrap_or_else(|| panic_with_error!(env, InvoiceError::TaxRateNotFound))
+}
+
+pub fn set_merchant_tax_account(env: &Env, merchant: &Address, account: &Address) {
+    merchant.require_auth();
+    let merchant_id = registered_merchant_id(env, merchant);
+
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::MerchantTaxAccount(merchant_id), account);
+}
+
+pub fn get_merchant_tax_account(env: &Env, merchant_id: u64) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::MerchantTaxAccount(merchant_id))
+}
+
+/// Where collected tax is sent: the merchant's tax account, or the merchant
+/// account when no tax account has been designated.
+pub fn tax_destination(env: &Env, merchant_id: u64) -> Address {
+    get_merchant_tax_account(env, merchant_id)
+        .unwrap_or_else(|| merchant::get_merchant_account(env, merchant_id))
+}
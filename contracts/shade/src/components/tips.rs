@@ -0,0 +1,58 @@
+use crate::components::{admin, merchant, referral};
+use crate::errors::ContractError;
+use crate::types::{InvoiceDataKey, TipConfig};
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+/// Where the merchant's tips go and whether the platform fee applies to them.
+pub fn set_merchant_tip_config(env: &Env, merchant_address: &Address, config: &TipConfig) {
+    merchant_address.require_auth();
+    if !merchant::is_merchant(env, merchant_address) {
+        panic_with_error!(env, ContractError::MerchantNotFound);
+    }
+    let merchant_id = merchant::get_merchant_id(env, merchant_address);
+    env.storage()
+        .persistent()
+        .set(&InvoiceDataKey::MerchantTipConfig(merchant_id), config);
+}
+
+/// Defaults to tipping into the merchant account with the usual fee.
+pub fn get_merchant_tip_config(env: &Env, merchant_id: u64) -> TipConfig {
+    env.storage()
+        .persistent()
+        .get(&InvoiceDataKey::MerchantTipConfig(merchant_id))
+        .unwrap_or(TipConfig {
+            tip_account: None,
+            fee_exempt: false,
+        })
+}
+
+/// Adds the payouts for a tip to `payouts`: the merchant's tip account gets
+/// the tip less the platform fee unless tips are fee-exempt, and a running
+/// referral gets its share of that fee. Returns the fee taken and the part of
+/// it left for the platform, which the caller routes with the payment.
+pub fn settle_tip(
+    env: &Env,
+    merchant_address: &Address,
+    merchant_id: u64,
+    token: &Address,
+    tip: i128,
+    payouts: &mut Vec<(Address, i128)>,
+) -> (i128, i128) {
+    let config = get_merchant_tip_config(env, merchant_id);
+    let fee = if config.fee_exempt {
+        0
+    } else {
+        admin::charge_fee(env, merchant_address, token, tip)
+    };
+    let tip_account = config
+        .tip_account
+        .unwrap_or_else(|| merchant::get_merchant_account(env, merchant_id));
+
+    payouts.push_back((tip_account, tip - fee));
+    let (platform_fee, referral_share) =
+        referral::split_platform_fee(env, merchant_address, token, fee);
+    if let Some(referral_share) = referral_share {
+        payouts.push_back(referral_share);
+    }
+    (fee, platform_fee)
+}
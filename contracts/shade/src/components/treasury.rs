@@ -0,0 +1,174 @@
+use crate::components::{admin, core, reentrancy};
+use crate::errors::FeeError;
+use crate::events;
+use crate::types::{FeeDataKey, TreasurySplit};
+use soroban_sdk::{panic_with_error, token, Address, Env, Vec};
+
+pub const MAX_TREASURY_SPLITS: u32 = 10;
+
+/// When enabled, platform fees are paid into this contract and held on a
+/// per-token ledger until `sweep_fees` instead of going straight to the
+/// platform account.
+pub fn set_fee_accrual(env: &Env, admin: &Address, enabled: bool) {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
+    env.storage()
+        .persistent()
+        .set(&FeeDataKey::FeeAccrualEnabled, &enabled);
+    events::publish_fee_accrual_set_event(env, admin.clone(), enabled, env.ledger().timestamp());
+    reentrancy::exit(env);
+}
+
+pub fn is_fee_accrual_enabled(env: &Env) -> bool {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::FeeAccrualEnabled)
+        .unwrap_or(false)
+}
+
+pub fn get_accrued_fees(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::AccruedFees(token.clone()))
+        .unwrap_or(0)
+}
+
+/// Pays `platform_fee` and each of `payouts` out of the payer's funds and
+/// returns where the platform fee went. With accrual off everyone is paid
+/// straight from the payer. With accrual on the payer makes a single transfer
+/// of the gross amount into this contract, which forwards the payouts and
+/// adds the fee to the token's ledger. `via_allowance` pulls the funds with
+/// `transfer_from` against the contract's allowance instead.
+pub fn route_payment(
+    env: &Env,
+    token: &Address,
+    payer: &Address,
+    via_allowance: bool,
+    platform_fee: i128,
+    payouts: &Vec<(Address, i128)>,
+) -> Address {
+    let token_client = token::TokenClient::new(env, token);
+    let contract = env.current_contract_address();
+    let pull = |to: &Address, amount: i128| {
+        if via_allowance {
+            token_client.transfer_from(&contract, payer, to, &amount);
+        } else {
+            token_client.transfer(payer, to, &amount);
+        }
+    };
+
+    if !is_fee_accrual_enabled(env) {
+        let platform_account = admin::get_platform_account(env);
+        for (account, amount) in payouts.iter() {
+            if amount > 0 {
+                pull(&account, amount);
+            }
+        }
+        if platform_fee > 0 {
+            pull(&platform_account, platform_fee);
+        }
+        return platform_account;
+    }
+
+    let gross = payouts
+        .iter()
+        .fold(platform_fee, |total, (_, amount)| total + amount);
+    if gross > 0 {
+        pull(&contract, gross);
+    }
+    for (account, amount) in payouts.iter() {
+        if amount > 0 {
+            token_client.transfer(&contract, &account, &amount);
+        }
+    }
+    if platform_fee > 0 {
+        env.storage().persistent().set(
+            &FeeDataKey::AccruedFees(token.clone()),
+            &(get_accrued_fees(env, token) + platform_fee),
+        );
+    }
+    contract
+}
+
+/// Treasury accounts that accrued fees are swept to. Shares are in basis
+/// points and must add up to 10,000.
+pub fn set_treasury_splits(env: &Env, admin: &Address, splits: &Vec<TreasurySplit>) {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
+    if splits.is_empty() || splits.len() > MAX_TREASURY_SPLITS {
+        panic_with_error!(env, FeeError::InvalidTreasurySplits);
+    }
+    let mut total = 0;
+    for split in splits.iter() {
+        if split.share_bps <= 0 {
+            panic_with_error!(env, FeeError::InvalidTreasurySplits);
+        }
+        total += split.share_bps;
+    }
+    if total != 10_000 {
+        panic_with_error!(env, FeeError::InvalidTreasurySplits);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&FeeDataKey::TreasurySplits, splits);
+    events::publish_treasury_splits_set_event(
+        env,
+        admin.clone(),
+        splits.clone(),
+        env.ledger().timestamp(),
+    );
+    reentrancy::exit(env);
+}
+
+pub fn get_treasury_splits(env: &Env) -> Vec<TreasurySplit> {
+    env.storage()
+        .persistent()
+        .get(&FeeDataKey::TreasurySplits)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Pays out everything accrued in `token` to the treasury accounts by their
+/// shares. Rounding dust goes to the last account. Returns the amount swept.
+pub fn sweep_fees(env: &Env, admin: &Address, token: &Address) -> i128 {
+    reentrancy::enter(env);
+    core::assert_admin(env, admin);
+
+    let splits = get_treasury_splits(env);
+    if splits.is_empty() {
+        panic_with_error!(env, FeeError::InvalidTreasurySplits);
+    }
+    let amount = get_accrued_fees(env, token);
+    if amount <= 0 {
+        panic_with_error!(env, FeeError::NoAccruedFees);
+    }
+    env.storage()
+        .persistent()
+        .remove(&FeeDataKey::AccruedFees(token.clone()));
+
+    let token_client = token::TokenClient::new(env, token);
+    let contract = env.current_contract_address();
+    let last = splits.len() - 1;
+    let mut paid = 0;
+    for (index, split) in splits.iter().enumerate() {
+        let share = if index as u32 == last {
+            amount - paid
+        } else {
+            amount * split.share_bps / 10_000
+        };
+        if share > 0 {
+            token_client.transfer(&contract, &split.account, &share);
+        }
+        paid += share;
+    }
+
+    events::publish_fees_swept_event(
+        env,
+        admin.clone(),
+        token.clone(),
+        amount,
+        env.ledger().timestamp(),
+    );
+    reentrancy::exit(env);
+    amount
+}
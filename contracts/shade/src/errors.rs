@@ -53,4 +53,57 @@ pub enum ContractError {
     NotTicketOwner = 52,
     TicketEventMismatch = 53,
     InvalidResalePrice = 54,
+    InvalidPageLimit = 55,
+}
+
+// A contract error enum can hold at most 50 cases in the contract spec, and
+// `ContractError` is full. Newer invoice errors live here, numbered from 100 so
+// their codes never collide with `ContractError`.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum InvoiceError {
+    InvalidLineItems = 100,
+    InvalidTaxRate = 101,
+    TaxRateNotFound = 102,
+    TokenConversionUnavailable = 103,
+    InvalidInstallmentSchedule = 104,
+    InstallmentUnderpaid = 105,
+    InstallmentPlanNotFound = 106,
+    InvalidPaymentTerms = 107,
+    InvalidQuoteWindow = 108,
+    SlippageExceeded = 109,
+    RefundRequestNotFound = 110,
+    RefundRequestOpen = 111,
+    InvalidRefundRequestStatus = 112,
+    InvalidRefundWindow = 113,
+    UnsupportedPayloadVersion = 114,
+    SignatureExpired = 115,
+    InvoiceTemplateNotFound = 116,
+    InvalidTemplateStatus = 117,
+    InvalidExternalRef = 118,
+    DuplicateExternalRef = 119,
+    SplitBillPayerLimit = 120,
+    ContributionNotFound = 121,
+    CreditNoteNotFound = 122,
+    CreditNoteExpired = 123,
+    InvalidCreditNoteStatus = 124,
+    CreditNoteMismatch = 125,
+    RevisionMismatch = 126,
+    SignatureDeadlineTooFar = 127,
+}
+
+// Fee configuration errors, numbered from 200.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum FeeError {
+    InvalidVolumeTiers = 200,
+    InvalidFeeSchedule = 201,
+    InvalidFeeOverride = 202,
+    InvalidVolumeWindow = 203,
+    InvalidReferralConfig = 204,
+    InvalidReferrer = 205,
+    InvalidTreasurySplits = 206,
+    NoAccruedFees = 207,
 }
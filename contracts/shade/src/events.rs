@@ -37,6 +37,7 @@ pub fn publish_token_removed_event(env: &Env, token: Address, timestamp: u64) {
 pub struct MerchantRegisteredEvent {
     pub merchant: Address,
     pub merchant_id: u64,
+    pub referrer: Option<Address>,
     pub timestamp: u64,
 }
 
@@ -44,11 +45,13 @@ pub fn publish_merchant_registered_event(
     env: &Env,
     merchant: Address,
     merchant_id: u64,
+    referrer: Option<Address>,
     timestamp: u64,
 ) {
     MerchantRegisteredEvent {
         merchant,
         merchant_id,
+        referrer,
         timestamp,
     }
     .publish(env);
@@ -304,7 +307,11 @@ pub fn publish_contract_unpaused_event(env: &Env, admin: Address, timestamp: u64
 pub struct FeeProposedEvent {
     pub admin: Address,
     pub token: Address,
+    /// The schedule's `fee_bps`.
     pub fee: i128,
+    pub flat_fee: i128,
+    pub min_fee: i128,
+    pub max_fee: i128,
     pub timestamp: u64,
 }
 
@@ -312,13 +319,16 @@ pub fn publish_fee_proposed_event(
     env: &Env,
     admin: Address,
     token: Address,
-    fee: i128,
+    schedule: &crate::types::FeeSchedule,
     timestamp: u64,
 ) {
     FeeProposedEvent {
         admin,
         token,
-        fee,
+        fee: schedule.fee_bps,
+        flat_fee: schedule.flat_fee,
+        min_fee: schedule.min_fee,
+        max_fee: schedule.max_fee,
         timestamp,
     }
     .publish(env);
@@ -328,15 +338,130 @@ pub fn publish_fee_proposed_event(
 pub struct FeeSetEvent {
     pub admin: Address,
     pub token: Address,
+    /// The schedule's `fee_bps`.
     pub fee: i128,
+    pub flat_fee: i128,
+    pub min_fee: i128,
+    pub max_fee: i128,
     pub timestamp: u64,
 }
 
-pub fn publish_fee_set_event(env: &Env, admin: Address, token: Address, fee: i128, timestamp: u64) {
+pub fn publish_fee_set_event(
+    env: &Env,
+    admin: Address,
+    token: Address,
+    schedule: &crate::types::FeeSchedule,
+    timestamp: u64,
+) {
     FeeSetEvent {
         admin,
         token,
-        fee,
+        fee: schedule.fee_bps,
+        flat_fee: schedule.flat_fee,
+        min_fee: schedule.min_fee,
+        max_fee: schedule.max_fee,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct FeeOverrideSetEvent {
+    pub merchant: Address,
+    pub token: Address,
+    pub set_by: Address,
+    pub effective_from: u64,
+    pub expires_at: Option<u64>,
+    pub timestamp: u64,
+}
+
+pub fn publish_fee_override_set_event(
+    env: &Env,
+    merchant: Address,
+    token: Address,
+    set_by: Address,
+    effective_from: u64,
+    expires_at: Option<u64>,
+    timestamp: u64,
+) {
+    FeeOverrideSetEvent {
+        merchant,
+        token,
+        set_by,
+        effective_from,
+        expires_at,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct FeeOverrideRemovedEvent {
+    pub merchant: Address,
+    pub token: Address,
+    pub removed_by: Address,
+    pub timestamp: u64,
+}
+
+pub fn publish_fee_override_removed_event(
+    env: &Env,
+    merchant: Address,
+    token: Address,
+    removed_by: Address,
+    timestamp: u64,
+) {
+    FeeOverrideRemovedEvent {
+        merchant,
+        token,
+        removed_by,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct VolumeTiersProposedEvent {
+    pub admin: Address,
+    pub token: Address,
+    pub tiers: Vec<crate::types::VolumeDiscount>,
+    pub timestamp: u64,
+}
+
+pub fn publish_volume_tiers_proposed_event(
+    env: &Env,
+    admin: Address,
+    token: Address,
+    tiers: Vec<crate::types::VolumeDiscount>,
+    timestamp: u64,
+) {
+    VolumeTiersProposedEvent {
+        admin,
+        token,
+        tiers,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct VolumeTiersSetEvent {
+    pub admin: Address,
+    pub token: Address,
+    pub tiers: Vec<crate::types::VolumeDiscount>,
+    pub timestamp: u64,
+}
+
+pub fn publish_volume_tiers_set_event(
+    env: &Env,
+    admin: Address,
+    token: Address,
+    tiers: Vec<crate::types::VolumeDiscount>,
+    timestamp: u64,
+) {
+    VolumeTiersSetEvent {
+        admin,
+        token,
+        tiers,
         timestamp,
     }
     .publish(env);
@@ -402,167 +527,673 @@ pub fn publish_contract_upgraded_event(env: &Env, new_wasm_hash: BytesN<32>, tim
 }
 
 #[contractevent]
-pub struct AccountRestrictedEvent {
-    pub merchant: Address,
-    pub status: bool,
-    pub caller: Address,
+pub struct AccountRestrictedEvent {
+    pub merchant: Address,
+    pub status: bool,
+    pub caller: Address,
+    pub timestamp: u64,
+}
+
+pub fn publish_account_restricted_event(
+    env: &Env,
+    merchant: Address,
+    status: bool,
+    caller: Address,
+    timestamp: u64,
+) {
+    AccountRestrictedEvent {
+        merchant,
+        status,
+        caller,
+        timestamp,
+    }
+    .publish(env);
+}
+
+/// A payment priced by a merchant's fee override. `standard_fee` is what the
+/// token's schedule and volume discounts alone would have charged.
+#[contractevent]
+pub struct FeeDiscountAppliedEvent {
+    pub merchant: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub standard_fee: i128,
+    pub fee: i128,
+    pub timestamp: u64,
+}
+
+pub fn publish_fee_discount_applied_event(
+    env: &Env,
+    merchant: Address,
+    token: Address,
+    amount: i128,
+    standard_fee: i128,
+    fee: i128,
+    timestamp: u64,
+) {
+    FeeDiscountAppliedEvent {
+        merchant,
+        token,
+        amount,
+        standard_fee,
+        fee,
+        timestamp,
+    }
+    .publish(env);
+}
+
+// Kept merchant_amount from your branch AND merchant_account from main — both are useful.
+#[contractevent]
+pub struct InvoicePaidEvent {
+    pub invoice_id: u64,
+    pub merchant_id: u64,
+    pub merchant_account: Address,
+    pub payer: Address,
+    pub amount: i128,
+    pub fee: i128,
+    /// Portion of `amount` routed to the merchant's tax account.
+    pub tax: i128,
+    /// Early-payment discount and late-payment penalty resolved from the
+    /// invoice terms by this payment, in `token`.
+    pub discount: i128,
+    pub penalty: i128,
+    /// Paid on top of `amount` into the merchant's tip account. Its fee, if
+    /// any, is included in `fee`.
+    pub tip: i128,
+    pub merchant_amount: i128,
+    pub token: Address,
+    pub timestamp: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn publish_invoice_paid_event(
+    env: &Env,
+    invoice_id: u64,
+    merchant_id: u64,
+    merchant_account: Address,
+    payer: Address,
+    amount: i128,
+    fee: i128,
+    tax: i128,
+    discount: i128,
+    penalty: i128,
+    tip: i128,
+    merchant_amount: i128,
+    token: Address,
+    timestamp: u64,
+) {
+    InvoicePaidEvent {
+        invoice_id,
+        merchant_id,
+        merchant_account,
+        payer,
+        amount,
+        fee,
+        tax,
+        discount,
+        penalty,
+        tip,
+        merchant_amount,
+        token,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct FiatInvoicePricedEvent {
+    pub invoice_id: u64,
+    pub token: Address,
+    pub resolved_amount: i128,
+    /// End of the lock window for quotes pinned with `lock_fiat_quote`;
+    /// `None` for spot prices taken at payment time.
+    pub valid_until: Option<u64>,
+    pub timestamp: u64,
+}
+
+pub fn publish_fiat_invoice_priced_event(
+    env: &Env,
+    invoice_id: u64,
+    token: Address,
+    resolved_amount: i128,
+    valid_until: Option<u64>,
+    timestamp: u64,
+) {
+    FiatInvoicePricedEvent {
+        invoice_id,
+        token,
+        resolved_amount,
+        valid_until,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct PaymentSplitRoutedEvent {
+    pub invoice_id: u64,
+    pub merchant_account: Address,
+    pub platform_account: Address,
+    pub merchant_amount: i128,
+    pub platform_amount: i128,
+    pub token: Address,
+    pub timestamp: u64,
+}
+
+pub fn publish_payment_split_routed_event(
+    env: &Env,
+    invoice_id: u64,
+    merchant_account: Address,
+    platform_account: Address,
+    merchant_amount: i128,
+    platform_amount: i128,
+    token: Address,
+    timestamp: u64,
+) {
+    PaymentSplitRoutedEvent {
+        invoice_id,
+        merchant_account,
+        platform_account,
+        merchant_amount,
+        platform_amount,
+        token,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct InvoiceCancelledEvent {
+    pub invoice_id: u64,
+    pub merchant: Address,
+    pub timestamp: u64,
+}
+
+pub fn publish_invoice_cancelled_event(
+    env: &Env,
+    invoice_id: u64,
+    merchant: Address,
+    timestamp: u64,
+) {
+    InvoiceCancelledEvent {
+        invoice_id,
+        merchant,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct PartialInvoiceCancelledEvent {
+    pub invoice_id: u64,
+    pub merchant: Address,
+    /// `None` for split bills, which are refunded to each payer pro-rata.
+    pub payer: Option<Address>,
+    /// What the merchant account received, which is all that is refunded.
+    pub amount_refunded: i128,
+    /// Platform fees taken from the payments and not refunded.
+    pub fee_retained: i128,
+    /// Tax passed through to the tax account and not refunded.
+    pub tax_retained: i128,
+    pub timestamp: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn publish_partial_invoice_cancelled_event(
+    env: &Env,
+    invoice_id: u64,
+    merchant: Address,
+    payer: Option<Address>,
+    amount_refunded: i128,
+    fee_retained: i128,
+    tax_retained: i128,
+    timestamp: u64,
+) {
+    PartialInvoiceCancelledEvent {
+        invoice_id,
+        merchant,
+        payer,
+        amount_refunded,
+        fee_retained,
+        tax_retained,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct InvoiceSplitBillSetEvent {
+    pub invoice_id: u64,
+    pub enabled: bool,
+    pub timestamp: u64,
+}
+
+pub fn publish_invoice_split_bill_set_event(
+    env: &Env,
+    invoice_id: u64,
+    enabled: bool,
+    timestamp: u64,
+) {
+    InvoiceSplitBillSetEvent {
+        invoice_id,
+        enabled,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct CreditNoteIssuedEvent {
+    pub credit_note_id: u64,
+    pub merchant: Address,
+    pub customer: Address,
+    pub invoice_id: Option<u64>,
+    pub amount: i128,
+    pub token: Address,
+    pub expires_at: Option<u64>,
+    pub timestamp: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn publish_credit_note_issued_event(
+    env: &Env,
+    credit_note_id: u64,
+    merchant: Address,
+    customer: Address,
+    invoice_id: Option<u64>,
+    amount: i128,
+    token: Address,
+    expires_at: Option<u64>,
+    timestamp: u64,
+) {
+    CreditNoteIssuedEvent {
+        credit_note_id,
+        merchant,
+        customer,
+        invoice_id,
+        amount,
+        token,
+        expires_at,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct CreditNoteRedeemedEvent {
+    pub credit_note_id: u64,
+    pub invoice_id: u64,
+    pub customer: Address,
+    pub amount: i128,
+    /// Credit left on the note after this redemption.
+    pub remaining: i128,
+    pub timestamp: u64,
+}
+
+pub fn publish_credit_note_redeemed_event(
+    env: &Env,
+    credit_note_id: u64,
+    invoice_id: u64,
+    customer: Address,
+    amount: i128,
+    remaining: i128,
+    timestamp: u64,
+) {
+    CreditNoteRedeemedEvent {
+        credit_note_id,
+        invoice_id,
+        customer,
+        amount,
+        remaining,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct CreditNoteVoidedEvent {
+    pub credit_note_id: u64,
+    pub merchant: Address,
+    /// Unredeemed credit cancelled with the note.
+    pub amount_voided: i128,
+    pub timestamp: u64,
+}
+
+pub fn publish_credit_note_voided_event(
+    env: &Env,
+    credit_note_id: u64,
+    merchant: Address,
+    amount_voided: i128,
+    timestamp: u64,
+) {
+    CreditNoteVoidedEvent {
+        credit_note_id,
+        merchant,
+        amount_voided,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct CreditNoteRestoredEvent {
+    pub credit_note_id: u64,
+    pub invoice_id: u64,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+pub fn publish_credit_note_restored_event(
+    env: &Env,
+    credit_note_id: u64,
+    invoice_id: u64,
+    amount: i128,
+    timestamp: u64,
+) {
+    CreditNoteRestoredEvent {
+        credit_note_id,
+        invoice_id,
+        amount,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct InvoiceExpiredEvent {
+    pub invoice_id: u64,
+    pub merchant_id: u64,
+    pub amount_paid: i128,
+    /// Partial payments returned to the payer under the merchant's policy.
+    pub amount_refunded: i128,
+    pub timestamp: u64,
+}
+
+pub fn publish_invoice_expired_event(
+    env: &Env,
+    invoice_id: u64,
+    merchant_id: u64,
+    amount_paid: i128,
+    amount_refunded: i128,
+    timestamp: u64,
+) {
+    InvoiceExpiredEvent {
+        invoice_id,
+        merchant_id,
+        amount_paid,
+        amount_refunded,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct InvoiceInstallmentsSetEvent {
+    pub invoice_id: u64,
+    pub merchant_id: u64,
+    pub installment_count: u32,
+    pub timestamp: u64,
+}
+
+pub fn publish_invoice_installments_set_event(
+    env: &Env,
+    invoice_id: u64,
+    merchant_id: u64,
+    installment_count: u32,
+    timestamp: u64,
+) {
+    InvoiceInstallmentsSetEvent {
+        invoice_id,
+        merchant_id,
+        installment_count,
+        timestamp,
+    }
+    .publish(env);
+}
+
+/// Terms are all zero when they were cleared.
+#[contractevent]
+pub struct InvoiceTermsSetEvent {
+    pub invoice_id: u64,
+    pub merchant_id: u64,
+    pub discount_bps: u32,
+    pub discount_deadline: u64,
+    pub penalty_bps: u32,
+    pub due_date: u64,
+    pub timestamp: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn publish_invoice_terms_set_event(
+    env: &Env,
+    invoice_id: u64,
+    merchant_id: u64,
+    discount_bps: u32,
+    discount_deadline: u64,
+    penalty_bps: u32,
+    due_date: u64,
+    timestamp: u64,
+) {
+    InvoiceTermsSetEvent {
+        invoice_id,
+        merchant_id,
+        discount_bps,
+        discount_deadline,
+        penalty_bps,
+        due_date,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct InstallmentOverdueEvent {
+    pub invoice_id: u64,
+    pub merchant_id: u64,
+    /// Zero-based position of the missed installment in the plan.
+    pub installment: u32,
+    pub due_date: u64,
+    /// Amount still owed towards the missed installment.
+    pub amount_due: i128,
+    pub timestamp: u64,
+}
+
+pub fn publish_installment_overdue_event(
+    env: &Env,
+    invoice_id: u64,
+    merchant_id: u64,
+    installment: u32,
+    due_date: u64,
+    amount_due: i128,
+    timestamp: u64,
+) {
+    InstallmentOverdueEvent {
+        invoice_id,
+        merchant_id,
+        installment,
+        due_date,
+        amount_due,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct InvoiceTemplateCreatedEvent {
+    pub template_id: u64,
+    pub merchant: Address,
+    pub payer: Address,
+    pub amount: i128,
+    pub token: Address,
+    pub interval: u64,
+    pub timestamp: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn publish_invoice_template_created_event(
+    env: &Env,
+    template_id: u64,
+    merchant: Address,
+    payer: Address,
+    amount: i128,
+    token: Address,
+    interval: u64,
+    timestamp: u64,
+) {
+    InvoiceTemplateCreatedEvent {
+        template_id,
+        merchant,
+        payer,
+        amount,
+        token,
+        interval,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct InvoiceTemplateUpdatedEvent {
+    pub template_id: u64,
+    pub status: crate::types::InvoiceTemplateStatus,
     pub timestamp: u64,
 }
 
-pub fn publish_account_restricted_event(
+pub fn publish_invoice_template_updated_event(
     env: &Env,
-    merchant: Address,
-    status: bool,
-    caller: Address,
+    template_id: u64,
+    status: crate::types::InvoiceTemplateStatus,
     timestamp: u64,
 ) {
-    AccountRestrictedEvent {
-        merchant,
+    InvoiceTemplateUpdatedEvent {
+        template_id,
         status,
-        caller,
         timestamp,
     }
     .publish(env);
 }
 
 #[contractevent]
-pub struct FeeDiscountAppliedEvent {
-    pub merchant: Address,
-    pub volume: i128,
-    pub discount_bps: i128,
+pub struct TemplateInvoiceIssuedEvent {
+    pub template_id: u64,
+    pub invoice_id: u64,
+    pub payer: Address,
     pub timestamp: u64,
 }
 
-pub fn publish_fee_discount_applied_event(
+pub fn publish_template_invoice_issued_event(
     env: &Env,
-    merchant: Address,
-    volume: i128,
-    discount_bps: i128,
+    template_id: u64,
+    invoice_id: u64,
+    payer: Address,
     timestamp: u64,
 ) {
-    FeeDiscountAppliedEvent {
-        merchant,
-        volume,
-        discount_bps,
+    TemplateInvoiceIssuedEvent {
+        template_id,
+        invoice_id,
+        payer,
         timestamp,
     }
     .publish(env);
 }
 
-// Kept merchant_amount from your branch AND merchant_account from main — both are useful.
 #[contractevent]
-pub struct InvoicePaidEvent {
+pub struct RefundRequestedEvent {
     pub invoice_id: u64,
-    pub merchant_id: u64,
-    pub merchant_account: Address,
     pub payer: Address,
     pub amount: i128,
-    pub fee: i128,
-    pub merchant_amount: i128,
-    pub token: Address,
+    pub reason_hash: BytesN<32>,
+    pub respond_by: u64,
     pub timestamp: u64,
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn publish_invoice_paid_event(
+pub fn publish_refund_requested_event(
     env: &Env,
     invoice_id: u64,
-    merchant_id: u64,
-    merchant_account: Address,
     payer: Address,
     amount: i128,
-    fee: i128,
-    merchant_amount: i128,
-    token: Address,
+    reason_hash: BytesN<32>,
+    respond_by: u64,
     timestamp: u64,
 ) {
-    InvoicePaidEvent {
+    RefundRequestedEvent {
         invoice_id,
-        merchant_id,
-        merchant_account,
         payer,
         amount,
-        fee,
-        merchant_amount,
-        token,
+        reason_hash,
+        respond_by,
         timestamp,
     }
     .publish(env);
 }
 
 #[contractevent]
-pub struct FiatInvoicePricedEvent {
+pub struct RefundRequestAcceptedEvent {
     pub invoice_id: u64,
-    pub token: Address,
-    pub resolved_amount: i128,
+    pub merchant: Address,
+    pub amount: i128,
     pub timestamp: u64,
 }
 
-pub fn publish_fiat_invoice_priced_event(
+pub fn publish_refund_request_accepted_event(
     env: &Env,
     invoice_id: u64,
-    token: Address,
-    resolved_amount: i128,
+    merchant: Address,
+    amount: i128,
     timestamp: u64,
 ) {
-    FiatInvoicePricedEvent {
+    RefundRequestAcceptedEvent {
         invoice_id,
-        token,
-        resolved_amount,
+        merchant,
+        amount,
         timestamp,
     }
     .publish(env);
 }
 
 #[contractevent]
-pub struct PaymentSplitRoutedEvent {
+pub struct RefundRequestRejectedEvent {
     pub invoice_id: u64,
-    pub merchant_account: Address,
-    pub platform_account: Address,
-    pub merchant_amount: i128,
-    pub platform_amount: i128,
-    pub token: Address,
+    pub merchant: Address,
     pub timestamp: u64,
 }
 
-pub fn publish_payment_split_routed_event(
+pub fn publish_refund_request_rejected_event(
     env: &Env,
     invoice_id: u64,
-    merchant_account: Address,
-    platform_account: Address,
-    merchant_amount: i128,
-    platform_amount: i128,
-    token: Address,
+    merchant: Address,
     timestamp: u64,
 ) {
-    PaymentSplitRoutedEvent {
+    RefundRequestRejectedEvent {
         invoice_id,
-        merchant_account,
-        platform_account,
-        merchant_amount,
-        platform_amount,
-        token,
+        merchant,
         timestamp,
     }
     .publish(env);
 }
 
 #[contractevent]
-pub struct InvoiceCancelledEvent {
+pub struct RefundRequestArbitratedEvent {
     pub invoice_id: u64,
-    pub merchant: Address,
+    pub arbiter: Address,
+    pub approved: bool,
+    pub amount: i128,
     pub timestamp: u64,
 }
 
-pub fn publish_invoice_cancelled_event(
+pub fn publish_refund_request_arbitrated_event(
     env: &Env,
     invoice_id: u64,
-    merchant: Address,
+    arbiter: Address,
+    approved: bool,
+    amount: i128,
     timestamp: u64,
 ) {
-    InvoiceCancelledEvent {
+    RefundRequestArbitratedEvent {
         invoice_id,
-        merchant,
+        arbiter,
+        approved,
+        amount,
         timestamp,
     }
     .publish(env);
@@ -572,24 +1203,34 @@ pub fn publish_invoice_cancelled_event(
 pub struct InvoiceAmendedEvent {
     pub invoice_id: u64,
     pub merchant: Address,
+    pub revision: u32,
     pub old_amount: i128,
     pub new_amount: i128,
+    pub old_description: String,
+    pub new_description: String,
     pub timestamp: u64,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn publish_invoice_amended_event(
     env: &Env,
     invoice_id: u64,
     merchant: Address,
+    revision: u32,
     old_amount: i128,
     new_amount: i128,
+    old_description: String,
+    new_description: String,
     timestamp: u64,
 ) {
     InvoiceAmendedEvent {
         invoice_id,
         merchant,
+        revision,
         old_amount,
         new_amount,
+        old_description,
+        new_description,
         timestamp,
     }
     .publish(env);
@@ -616,6 +1257,22 @@ pub fn publish_nonce_invalidated_event(
     .publish(env);
 }
 
+#[contractevent]
+pub struct NoncesPurgedEvent {
+    pub merchant: Address,
+    pub count: u32,
+    pub timestamp: u64,
+}
+
+pub fn publish_nonces_purged_event(env: &Env, merchant: Address, count: u32, timestamp: u64) {
+    NoncesPurgedEvent {
+        merchant,
+        count,
+        timestamp,
+    }
+    .publish(env);
+}
+
 #[contractevent]
 pub struct BridgePlaceholderEvent {
     pub caller: Address,
@@ -1004,3 +1661,216 @@ pub fn publish_ticket_resold_event(
     }
     .publish(env);
 }
+
+#[contractevent]
+pub struct ReferralConfigSetEvent {
+    pub admin: Address,
+    pub share_bps: i128,
+    pub duration: u64,
+    pub timestamp: u64,
+}
+
+pub fn publish_referral_config_set_event(
+    env: &Env,
+    admin: Address,
+    share_bps: i128,
+    duration: u64,
+    timestamp: u64,
+) {
+    ReferralConfigSetEvent {
+        admin,
+        share_bps,
+        duration,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct ReferralFeePaidEvent {
+    pub referrer: Address,
+    pub merchant: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+pub fn publish_referral_fee_paid_event(
+    env: &Env,
+    referrer: Address,
+    merchant: Address,
+    token: Address,
+    amount: i128,
+    timestamp: u64,
+) {
+    ReferralFeePaidEvent {
+        referrer,
+        merchant,
+        token,
+        amount,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct FeeAccrualSetEvent {
+    pub admin: Address,
+    pub enabled: bool,
+    pub timestamp: u64,
+}
+
+pub fn publish_fee_accrual_set_event(env: &Env, admin: Address, enabled: bool, timestamp: u64) {
+    FeeAccrualSetEvent {
+        admin,
+        enabled,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct FiatQuoteWindowSetEvent {
+    pub admin: Address,
+    pub window: u64,
+    pub timestamp: u64,
+}
+
+pub fn publish_fiat_quote_window_set_event(env: &Env, admin: Address, window: u64, timestamp: u64) {
+    FiatQuoteWindowSetEvent {
+        admin,
+        window,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct VolumeWindowSetEvent {
+    pub admin: Address,
+    pub days: u32,
+    pub timestamp: u64,
+}
+
+pub fn publish_volume_window_set_event(env: &Env, admin: Address, days: u32, timestamp: u64) {
+    VolumeWindowSetEvent {
+        admin,
+        days,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct TreasurySplitsSetEvent {
+    pub admin: Address,
+    pub splits: Vec<crate::types::TreasurySplit>,
+    pub timestamp: u64,
+}
+
+pub fn publish_treasury_splits_set_event(
+    env: &Env,
+    admin: Address,
+    splits: Vec<crate::types::TreasurySplit>,
+    timestamp: u64,
+) {
+    TreasurySplitsSetEvent {
+        admin,
+        splits,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct FeesSweptEvent {
+    pub admin: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+pub fn publish_fees_swept_event(
+    env: &Env,
+    admin: Address,
+    token: Address,
+    amount: i128,
+    timestamp: u64,
+) {
+    FeesSweptEvent {
+        admin,
+        token,
+        amount,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct RefundWindowBoundsSetEvent {
+    pub admin: Address,
+    pub min: u64,
+    pub max: u64,
+    pub timestamp: u64,
+}
+
+pub fn publish_refund_window_bounds_set_event(
+    env: &Env,
+    admin: Address,
+    min: u64,
+    max: u64,
+    timestamp: u64,
+) {
+    RefundWindowBoundsSetEvent {
+        admin,
+        min,
+        max,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct MerchantRefundWindowSetEvent {
+    pub merchant: Address,
+    pub token: Option<Address>,
+    pub window: u64,
+    pub timestamp: u64,
+}
+
+pub fn publish_merchant_refund_window_set_event(
+    env: &Env,
+    merchant: Address,
+    token: Option<Address>,
+    window: u64,
+    timestamp: u64,
+) {
+    MerchantRefundWindowSetEvent {
+        merchant,
+        token,
+        window,
+        timestamp,
+    }
+    .publish(env);
+}
+
+#[contractevent]
+pub struct RefundWindowRemovedEvent {
+    pub merchant: Address,
+    pub token: Option<Address>,
+    pub timestamp: u64,
+}
+
+pub fn publish_refund_window_removed_event(
+    env: &Env,
+    merchant: Address,
+    token: Option<Address>,
+    timestamp: u64,
+) {
+    RefundWindowRemovedEvent {
+        merchant,
+        token,
+        timestamp,
+    }
+    .publish(env);
+}
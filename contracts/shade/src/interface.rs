@@ -1,7 +1,12 @@
 use crate::types::{
-    CrossChainBridgePayload, Event, Invoice, InvoiceFilter, Merchant, MerchantAnalytics,
-    MerchantAnalyticsSummary, MerchantFilter, OracleConfig, PaymentPayload, PendingFee, Role,
-    Subscription, SubscriptionPlan, Ticket, TokenAnalytics, Transaction
+    CreditNote, CrossChainBridgePayload, Event, ExpiryRefundPolicy, FeeSchedule, Installment,
+    InstallmentPlan, Invoice, InvoiceFilter, InvoiceIndex, InvoiceOptions, InvoicePage,
+    InvoiceRevision, InvoiceTemplate, LineItem, Merchant, MerchantAnalytics,
+    MerchantAnalyticsSummary, MerchantFeeOverride, MerchantFilter, MerchantReferral, OracleConfig,
+    PayerContribution, PaymentPayload, PaymentTerms, PendingFee, PendingVolumeDiscounts,
+    ReferralConfig, RefundRequest, RefundWindowBounds, Role, SignedInvoicePayload, Subscription,
+    SubscriptionPlan, Ticket, TipConfig, TokenAnalytics, Transaction, TreasurySplit,
+    VolumeDiscount,
 };
 use soroban_sdk::{contracttrait, Address, BytesN, Env, String, Vec};
 
@@ -16,14 +21,73 @@ pub trait ShadeTrait {
     fn set_account_wasm_hash(env: Env, admin: Address, wasm_hash: soroban_sdk::BytesN<32>);
     fn set_fee(env: Env, admin: Address, token: Address, fee: i128);
     fn get_fee(env: Env, token: Address) -> i128;
+    fn get_fee_schedule(env: Env, token: Address) -> FeeSchedule;
     fn set_platform_account(env: Env, admin: Address, account: Address);
     fn get_platform_account(env: Env) -> Address;
+    /// Hold platform fees in this contract instead of paying the platform
+    /// account on every payment. Accrued fees are paid out by `sweep_fees`.
+    fn set_fee_accrual(env: Env, admin: Address, enabled: bool);
+    fn is_fee_accrual_enabled(env: Env) -> bool;
+    fn get_accrued_fees(env: Env, token: Address) -> i128;
+    /// Treasury accounts and their basis-point shares of each sweep; the
+    /// shares must add up to 10,000.
+    fn set_treasury_splits(env: Env, admin: Address, splits: Vec<TreasurySplit>);
+    fn get_treasury_splits(env: Env) -> Vec<TreasurySplit>;
+    /// Pays out all fees accrued in `token` and returns the amount swept.
+    fn sweep_fees(env: Env, admin: Address, token: Address) -> i128;
     fn set_token_oracle(env: Env, admin: Address, token: Address, oracle: OracleConfig);
     fn get_token_oracle(env: Env, token: Address) -> OracleConfig;
+    /// Seconds a quote taken with `lock_fiat_quote` stays valid (default 300).
+    fn set_fiat_quote_window(env: Env, admin: Address, window: u64);
+    fn get_fiat_quote_window(env: Env) -> u64;
+    /// Days of payment volume, net of refunds, that volume discounts are
+    /// based on (default 30, at most 365).
+    fn set_volume_window(env: Env, admin: Address, days: u32);
+    fn get_volume_window(env: Env) -> u32;
     fn propose_fee(env: Env, admin: Address, token: Address, fee: i128);
+    /// Queue a flat, bps, minimum and maximum fee schedule for `token`. Like a
+    /// bps-only `propose_fee`, it takes effect through `execute_fee`.
+    fn propose_fee_schedule(env: Env, admin: Address, token: Address, schedule: FeeSchedule);
     fn execute_fee(env: Env, admin: Address, token: Address);
     fn get_pending_fee(env: Env, token: Address) -> PendingFee;
-    fn register_merchant(env: Env, merchant: Address);
+    /// Queue a per-token volume discount table, sorted by `min_volume` in the
+    /// token's own units. It takes effect through `execute_volume_discounts`
+    /// after the same delay as fee changes.
+    fn propose_volume_discounts(
+        env: Env,
+        admin: Address,
+        token: Address,
+        tiers: Vec<VolumeDiscount>,
+    );
+    fn execute_volume_discounts(env: Env, admin: Address, token: Address);
+    /// Tokens without a table of their own get 10% off the fee from 10,000
+    /// volume, 25% from 50,000 and 50% from 200,000.
+    fn get_volume_discounts(env: Env, token: Address) -> Vec<VolumeDiscount>;
+    fn get_pending_volume_discounts(env: Env, token: Address) -> PendingVolumeDiscounts;
+    /// Negotiated pricing for one merchant in one token, set by the admin or a
+    /// manager. Unlike token-wide fees it is not time-locked; it applies from
+    /// its `effective_from` date.
+    fn set_merchant_fee_override(
+        env: Env,
+        caller: Address,
+        merchant: Address,
+        token: Address,
+        fee_override: MerchantFeeOverride,
+    );
+    fn remove_merchant_fee_override(env: Env, caller: Address, merchant: Address, token: Address);
+    fn get_merchant_fee_override(
+        env: Env,
+        merchant: Address,
+        token: Address,
+    ) -> Option<MerchantFeeOverride>;
+    /// Share of platform fees paid to the partner that referred a merchant,
+    /// for `duration` seconds after the merchant registers.
+    fn set_referral_config(env: Env, admin: Address, config: ReferralConfig);
+    fn get_referral_config(env: Env) -> ReferralConfig;
+    fn get_merchant_referral(env: Env, merchant: Address) -> Option<MerchantReferral>;
+    /// Total platform fees shared with `referrer` in `token`.
+    fn get_referral_earnings(env: Env, referrer: Address, token: Address) -> i128;
+    fn register_merchant(env: Env, merchant: Address, referrer: Option<Address>);
     fn get_merchant(env: Env, merchant_id: u64) -> Merchant;
     fn get_merchants(env: Env, filter: MerchantFilter) -> Vec<Merchant>;
     fn is_merchant(env: Env, merchant: Address) -> bool;
@@ -31,14 +95,43 @@ pub trait ShadeTrait {
     fn is_merchant_active(env: Env, merchant_id: u64) -> bool;
     fn verify_merchant(env: Env, admin: Address, merchant_id: u64, status: bool);
     fn is_merchant_verified(env: Env, merchant_id: u64) -> bool;
+    /// `options` carries the optional payer, reference, expiry and payment
+    /// terms; see `InvoiceOptions`. When a payer is set, only that address can
+    /// pay the invoice. Creating a second invoice with the same `external_ref`
+    /// fails.
     fn create_invoice(
         env: Env,
         merchant: Address,
         description: String,
         amount: i128,
         token: Address,
-        expires_at: Option<u64>,
+        options: InvoiceOptions,
+    ) -> u64;
+    /// Find a merchant's invoice by the `external_ref` it was created with.
+    fn get_invoice_by_reference(env: Env, merchant: Address, external_ref: String) -> Invoice;
+    /// Create an invoice whose total is computed from its line items
+    /// (subtotal plus per-line tax).
+    fn create_itemized_invoice(
+        env: Env,
+        merchant: Address,
+        description: String,
+        line_items: Vec<LineItem>,
+        token: Address,
+        options: InvoiceOptions,
     ) -> u64;
+    /// Create an invoice priced in `reference_token` that the payer may settle
+    /// in any token the merchant accepts, converted via each token's oracle
+    /// price in `quote_currency`.
+    fn create_multi_token_invoice(
+        env: Env,
+        merchant: Address,
+        description: String,
+        amount: i128,
+        reference_token: Address,
+        quote_currency: String,
+        options: InvoiceOptions,
+    ) -> u64;
+    #[allow(clippy::too_many_arguments)]
     fn create_fiat_invoice(
         env: Env,
         merchant: Address,
@@ -47,7 +140,7 @@ pub trait ShadeTrait {
         fiat_currency: String,
         fiat_decimals: u32,
         token: Address,
-        expires_at: Option<u64>,
+        options: InvoiceOptions,
     ) -> u64;
     fn create_invoice_draft(
         env: Env,
@@ -55,22 +148,24 @@ pub trait ShadeTrait {
         description: String,
         amount: i128,
         token: Address,
-        expires_at: Option<u64>,
+        options: InvoiceOptions,
     ) -> u64;
     fn finalize_invoice(env: Env, merchant: Address, invoice_id: u64);
-    #[allow(clippy::too_many_arguments)]
+    /// Create an invoice from a payload the merchant signed with their
+    /// registered key. Restricted to managers and the admin.
     fn create_invoice_signed(
         env: Env,
         caller: Address,
-        merchant: Address,
-        description: String,
-        amount: i128,
-        token: Address,
-        nonce: BytesN<32>,
+        payload: SignedInvoicePayload,
         signature: BytesN<64>,
     ) -> u64;
+    /// Forget used signing nonces whose deadline has passed.
+    fn purge_used_nonces(env: Env, merchant: Address, nonces: Vec<BytesN<32>>) -> u32;
     fn get_invoice(env: Env, invoice_id: u64) -> Invoice;
     fn resolve_invoice_amount(env: Env, invoice_id: u64) -> i128;
+    /// Refund what is left of a paid invoice from the merchant account. Fees
+    /// and tax already paid out are kept, so only the merchant's net is
+    /// returned.
     fn refund_invoice(env: Env, merchant: Address, invoice_id: u64);
     fn set_merchant_key(env: Env, merchant: Address, key: BytesN<32>);
     fn get_merchant_key(env: Env, merchant: Address) -> BytesN<32>;
@@ -78,7 +173,119 @@ pub trait ShadeTrait {
     fn revoke_role(env: Env, admin: Address, user: Address, role: Role);
     fn has_role(env: Env, user: Address, role: Role) -> bool;
     fn get_invoices(env: Env, filter: InvoiceFilter) -> Vec<Invoice>;
+    /// Page through one invoice index. Pass `None` as the cursor for the first
+    /// page and the returned `next_cursor` afterwards. `limit` is capped at 100.
+    fn get_invoices_page(
+        env: Env,
+        index: InvoiceIndex,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> InvoicePage;
+    /// Add invoices created before the indexes existed to them, `limit` invoice
+    /// IDs after `cursor` at a time. Returns the cursor to continue from, or
+    /// `None` when done. Admin only.
+    fn backfill_invoice_indexes(
+        env: Env,
+        admin: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Option<u64>;
+    /// Refund `amount` of a paid invoice, up to the merchant's net.
     fn refund_invoice_partial(env: Env, merchant: Address, invoice_id: u64, amount: i128);
+    /// Let several payers share an unpaid invoice. Full and partial refunds of
+    /// a split bill are returned to the payers pro-rata.
+    fn set_invoice_split_bill(env: Env, merchant: Address, invoice_id: u64, enabled: bool);
+    fn get_invoice_contributions(env: Env, invoice_id: u64) -> Vec<PayerContribution>;
+    /// Refund `amount` of a split bill to one payer, out of their contribution.
+    fn refund_invoice_payer(
+        env: Env,
+        merchant: Address,
+        invoice_id: u64,
+        payer: Address,
+        amount: i128,
+    );
+    /// Give `customer` store credit, standalone or against one of the
+    /// merchant's paid invoices, which it then can no longer be refunded for.
+    /// The customer redeems it through `pay_invoice`.
+    fn issue_credit_note(
+        env: Env,
+        merchant: Address,
+        customer: Address,
+        token: Address,
+        amount: i128,
+        invoice_id: Option<u64>,
+        expires_at: Option<u64>,
+    ) -> u64;
+    fn get_credit_note(env: Env, credit_note_id: u64) -> CreditNote;
+    fn void_credit_note(env: Env, merchant: Address, credit_note_id: u64);
+    /// Ask the merchant to refund `amount` of a paid invoice. Only the payer,
+    /// or a contributor to a split bill, may request within the refund window.
+    fn request_refund(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        amount: i128,
+        reason_hash: BytesN<32>,
+    );
+    fn respond_to_refund_request(env: Env, merchant: Address, invoice_id: u64, accept: bool);
+    /// Rule on a refund request the merchant rejected or did not answer in
+    /// time. Requires the admin or `Role::Manager`.
+    fn arbitrate_refund_request(env: Env, arbiter: Address, invoice_id: u64, approve: bool);
+    fn get_refund_request(env: Env, invoice_id: u64) -> RefundRequest;
+    fn set_refund_window_bounds(env: Env, admin: Address, bounds: RefundWindowBounds);
+    fn get_refund_window_bounds(env: Env) -> RefundWindowBounds;
+    /// Set the merchant's refund window in seconds, for all tokens or only for
+    /// invoices settled in `token`. Zero disables refunds.
+    fn set_merchant_refund_window(env: Env, merchant: Address, token: Option<Address>, window: u64);
+    fn remove_merchant_refund_window(env: Env, merchant: Address, token: Option<Address>);
+    /// Refund window in seconds that applies to the merchant's invoices
+    /// settled in `token`.
+    fn get_refund_window(env: Env, merchant_id: u64, token: Address) -> u64;
+    /// Whether partial payments are refunded to the payer when the merchant's
+    /// invoices expire. Defaults to `RefundPayer`.
+    fn set_merchant_expiry_policy(env: Env, merchant: Address, policy: ExpiryRefundPolicy);
+    fn get_merchant_expiry_policy(env: Env, merchant_id: u64) -> ExpiryRefundPolicy;
+    /// Route tips to `tip_account` (the merchant account when unset) and
+    /// choose whether the platform fee applies to them.
+    fn set_merchant_tip_config(env: Env, merchant: Address, config: TipConfig);
+    fn get_merchant_tip_config(env: Env, merchant_id: u64) -> TipConfig;
+    /// Move the listed overdue Pending/PartiallyPaid invoices to `Expired`.
+    /// Permissionless; ineligible IDs are skipped. Returns how many expired.
+    fn expire_invoices(env: Env, invoice_ids: Vec<u64>) -> u32;
+    /// Expire overdue invoices among the next `limit` entries of the open
+    /// invoice index after `cursor`. Returns the cursor to continue from, or
+    /// `None` when done.
+    fn sweep_expired_invoices(env: Env, cursor: Option<u64>, limit: u32) -> Option<u64>;
+    /// Bill `payer` the same invoice every `interval` seconds, starting now.
+    fn create_invoice_template(
+        env: Env,
+        merchant: Address,
+        payer: Address,
+        description: String,
+        amount: i128,
+        token: Address,
+        interval: u64,
+    ) -> u64;
+    fn get_invoice_template(env: Env, template_id: u64) -> InvoiceTemplate;
+    fn pause_invoice_template(env: Env, merchant: Address, template_id: u64);
+    fn resume_invoice_template(env: Env, merchant: Address, template_id: u64);
+    fn end_invoice_template(env: Env, merchant: Address, template_id: u64);
+    /// Issue the next invoice from each due template among the next `limit`
+    /// templates after `cursor`. Returns the cursor to continue from, or
+    /// `None` when done.
+    fn issue_due_invoices(env: Env, cursor: Option<u64>, limit: u32) -> Option<u64>;
+    /// Attach an installment schedule to an unpaid invoice. The amounts must
+    /// sum to the invoice total; an empty schedule removes the plan.
+    fn set_invoice_installments(
+        env: Env,
+        merchant: Address,
+        invoice_id: u64,
+        installments: Vec<Installment>,
+    );
+    fn get_invoice_installments(env: Env, invoice_id: u64) -> InstallmentPlan;
+    /// Permissionless; flags the invoice overdue when its next unpaid
+    /// installment is past due and returns the overdue state.
+    fn check_installments_overdue(env: Env, invoice_id: u64) -> bool;
     fn pause(env: Env, admin: Address);
     fn unpause(env: Env, admin: Address);
     fn is_paused(env: Env) -> bool;
@@ -91,15 +298,76 @@ pub trait ShadeTrait {
     );
     fn calculate_fee(env: Env, merchant: Address, token: Address, amount: i128) -> i128;
     fn get_merchant_volume(env: Env, merchant: Address, token: Address) -> i128;
+    /// Volume inside the current volume window, which is what discounts use.
+    fn get_merchant_window_volume(env: Env, merchant: Address, token: Address) -> i128;
     fn get_merchant_analytics(env: Env, merchant: Address, token: Address) -> MerchantAnalytics;
     fn get_merchant_analytics_summary(env: Env, merchant: Address) -> MerchantAnalyticsSummary;
     fn set_merchant_account(env: Env, merchant: Address, account: Address);
     fn get_merchant_account(env: Env, merchant_id: u64) -> Address;
-    fn pay_invoice(env: Env, payer: Address, invoice_id: u64);
+    /// Register or update a tax rate (in basis points) under a jurisdiction code.
+    fn set_merchant_tax_rate(env: Env, merchant: Address, code: String, rate_bps: u32);
+    fn remove_merchant_tax_rate(env: Env, merchant: Address, code: String);
+    fn get_merchant_tax_rate(env: Env, merchant_id: u64, code: String) -> u32;
+    /// Account that receives the tax portion of invoice payments. Tax goes to
+    /// the merchant account while none is set.
+    fn set_merchant_tax_account(env: Env, merchant: Address, account: Address);
+    fn get_merchant_tax_account(env: Env, merchant_id: u64) -> Option<Address>;
+    /// `max_amount` rejects the payment if a fiat or term-priced invoice
+    /// resolves to more than the payer expected. `credit_note_id` applies the
+    /// payer's store credit first, reducing the tokens transferred.
+    /// `expected_revision` pins the invoice revision the payer agreed to.
+    fn pay_invoice(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        max_amount: Option<i128>,
+        credit_note_id: Option<u64>,
+        expected_revision: Option<u32>,
+    );
     fn pay_invoices_batch(env: Env, payer: Address, invoice_ids: Vec<u64>);
-    fn pay_invoice_partial(env: Env, payer: Address, invoice_id: u64, amount: i128);
+    /// Pay `amount` towards the invoice, part of which may come from a credit
+    /// note. `expected_revision` works as in `pay_invoice`.
+    fn pay_invoice_partial(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        amount: i128,
+        credit_note_id: Option<u64>,
+        expected_revision: Option<u32>,
+    );
+    /// Pay the rest of the invoice and add `tip` for the merchant. Tips are
+    /// routed to the merchant's tip account and are not refundable.
+    /// `expected_revision` works as in `pay_invoice`.
+    fn pay_invoice_with_tip(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        max_amount: Option<i128>,
+        tip: i128,
+        expected_revision: Option<u32>,
+    );
+    /// Settle a fiat-priced or multi-token invoice in full with `token`.
+    /// Fees and analytics are recorded in the token paid. A quote locked for
+    /// `token` is honoured. `max_amount` and `expected_revision` work as in
+    /// `pay_invoice`.
+    fn pay_invoice_with_token(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        token: Address,
+        max_amount: Option<i128>,
+        expected_revision: Option<u32>,
+    );
+    /// Pin the current token amount of a fiat invoice for `payer` for the
+    /// quote window, in `token` or else the invoice token. Returns the amount
+    /// due at the locked price.
+    fn lock_fiat_quote(env: Env, payer: Address, invoice_id: u64, token: Option<Address>) -> i128;
     fn validate_payment_payload(env: Env, payload: PaymentPayload);
     fn void_invoice(env: Env, merchant: Address, invoice_id: u64);
+    /// Cancel a partially paid invoice, refunding the payer what reached the
+    /// merchant account. Fees and tax already paid out are not refunded.
+    fn cancel_invoice(env: Env, merchant: Address, invoice_id: u64);
+    /// Amendments are kept as numbered revisions; see `get_invoice_revisions`.
     fn amend_invoice(
         env: Env,
         merchant: Address,
@@ -107,6 +375,19 @@ pub trait ShadeTrait {
         new_amount: Option<i128>,
         new_description: Option<String>,
     );
+    /// Replace the lines of an itemized invoice; subtotal, tax and total are
+    /// recomputed from the new lines.
+    fn amend_invoice_items(env: Env, merchant: Address, invoice_id: u64, line_items: Vec<LineItem>);
+    /// Apply one of the merchant's registered tax rates to a pending or draft
+    /// invoice, recomputing its tax and total.
+    fn apply_invoice_tax(env: Env, merchant: Address, invoice_id: u64, tax_code: String);
+    /// Every amendment, tax change and line item change of the invoice, oldest
+    /// first.
+    fn get_invoice_revisions(env: Env, invoice_id: u64) -> Vec<InvoiceRevision>;
+    /// Set or clear early-payment discount and late-payment penalty terms on a
+    /// pending or draft invoice. The discount is decided by the first payment;
+    /// a penalty applies to whatever is outstanding once the due date passes.
+    fn set_invoice_terms(env: Env, merchant: Address, invoice_id: u64, terms: Option<PaymentTerms>);
 
     fn set_merchant_webhook(env: Env, merchant: Address, webhook: String);
     fn get_merchant_webhook(env: Env, merchant_id: u64) -> String;
@@ -165,11 +446,7 @@ pub trait ShadeTrait {
     fn get_user_transactions(env: Env, user: Address) -> Vec<Transaction>;
 
     // ── Cross-chain bridge placeholder ───────────────────────────────────────
-    fn emit_bridge_placeholder(
-        env: Env,
-        caller: Address,
-        payload: CrossChainBridgePayload,
-    );
+    fn emit_bridge_placeholder(env: Env, caller: Address, payload: CrossChainBridgePayload);
 
     // --- Event ticketing system ---
     #[allow(clippy::too_many_arguments)]
@@ -184,13 +461,7 @@ pub trait ShadeTrait {
         royalty_bps: u32,
     ) -> u64;
     fn purchase_ticket(env: Env, event_id: u64, buyer: Address) -> u64;
-    fn resell_ticket(
-        env: Env,
-        seller: Address,
-        buyer: Address,
-        ticket_id: u64,
-        resale_price: i128,
-    );
+    fn resell_ticket(env: Env, seller: Address, buyer: Address, ticket_id: u64, resale_price: i128);
     fn get_event(env: Env, event_id: u64) -> Event;
     fn get_ticket(env: Env, ticket_id: u64) -> Ticket;
     fn get_event_tickets(env: Env, event_id: u64) -> Vec<u64>;
@@ -1,16 +1,28 @@
 use crate::components::{
     access_control as access_control_component, admin as admin_component, core as core_component,
-    invoice as invoice_component, merchant as merchant_component, pausable as pausable_component,
-    subscription as subscription_component, upgrade as upgrade_component,
-    history as history_component,
+    credit_note as credit_note_component, fee_override as fee_override_component,
+    history as history_component, installments as installments_component,
+    invoice as invoice_component, invoice_expiry as invoice_expiry_component,
+    invoice_index as invoice_index_component, invoice_template as invoice_template_component,
+    merchant as merchant_component, pausable as pausable_component,
+    payment_terms as payment_terms_component, referral as referral_component,
+    refund_policy as refund_policy_component, refund_request as refund_request_component,
+    signature_util as signature_util_component, split_bill as split_bill_component,
+    subscription as subscription_component, tax as tax_component, tips as tips_component,
+    treasury as treasury_component, upgrade as upgrade_component,
 };
 use crate::errors::ContractError;
 use crate::events;
 use crate::interface::ShadeTrait;
 use crate::types::{
-    ContractInfo, CrossChainBridgePayload, DataKey, Event, Invoice, InvoiceFilter, Merchant,
-    MerchantAnalytics, MerchantAnalyticsSummary, MerchantFilter, OracleConfig, PaymentPayload,
-    PendingFee, Role, Subscription, SubscriptionPlan, Ticket, TokenAnalytics, Transaction,
+    ContractInfo, CreditNote, CrossChainBridgePayload, DataKey, Event, ExpiryRefundPolicy,
+    FeeSchedule, Installment, InstallmentPlan, Invoice, InvoiceFilter, InvoiceIndex,
+    InvoiceOptions, InvoicePage, InvoiceRevision, InvoiceTemplate, LineItem, Merchant,
+    MerchantAnalytics, MerchantAnalyticsSummary, MerchantFeeOverride, MerchantFilter,
+    MerchantReferral, OracleConfig, PayerContribution, PaymentPayload, PaymentTerms, PendingFee,
+    PendingVolumeDiscounts, ReferralConfig, RefundRequest, RefundWindowBounds, Role,
+    SignedInvoicePayload, Subscription, SubscriptionPlan, Ticket, TipConfig, TokenAnalytics,
+    Transaction, TreasurySplit, VolumeDiscount,
 };
 use soroban_sdk::{contract, contractimpl, panic_with_error, Address, BytesN, Env, String, Vec};
 
@@ -73,6 +85,10 @@ impl ShadeTrait for Shade {
         admin_component::get_fee(&env, &token)
     }
 
+    fn get_fee_schedule(env: Env, token: Address) -> FeeSchedule {
+        admin_component::get_fee_schedule(&env, &token)
+    }
+
     fn set_platform_account(env: Env, admin: Address, account: Address) {
         pausable_component::assert_not_paused(&env);
         admin_component::set_platform_account(&env, &admin, &account);
@@ -82,6 +98,33 @@ impl ShadeTrait for Shade {
         admin_component::get_platform_account(&env)
     }
 
+    fn set_fee_accrual(env: Env, admin: Address, enabled: bool) {
+        pausable_component::assert_not_paused(&env);
+        treasury_component::set_fee_accrual(&env, &admin, enabled);
+    }
+
+    fn is_fee_accrual_enabled(env: Env) -> bool {
+        treasury_component::is_fee_accrual_enabled(&env)
+    }
+
+    fn get_accrued_fees(env: Env, token: Address) -> i128 {
+        treasury_component::get_accrued_fees(&env, &token)
+    }
+
+    fn set_treasury_splits(env: Env, admin: Address, splits: Vec<TreasurySplit>) {
+        pausable_component::assert_not_paused(&env);
+        treasury_component::set_treasury_splits(&env, &admin, &splits);
+    }
+
+    fn get_treasury_splits(env: Env) -> Vec<TreasurySplit> {
+        treasury_component::get_treasury_splits(&env)
+    }
+
+    fn sweep_fees(env: Env, admin: Address, token: Address) -> i128 {
+        pausable_component::assert_not_paused(&env);
+        treasury_component::sweep_fees(&env, &admin, &token)
+    }
+
     fn set_token_oracle(env: Env, admin: Address, token: Address, oracle: OracleConfig) {
         pausable_component::assert_not_paused(&env);
         admin_component::set_token_oracle(&env, &admin, &token, &oracle);
@@ -91,11 +134,34 @@ impl ShadeTrait for Shade {
         admin_component::get_token_oracle(&env, &token)
     }
 
+    fn set_fiat_quote_window(env: Env, admin: Address, window: u64) {
+        pausable_component::assert_not_paused(&env);
+        admin_component::set_fiat_quote_window(&env, &admin, window);
+    }
+
+    fn get_fiat_quote_window(env: Env) -> u64 {
+        admin_component::get_fiat_quote_window(&env)
+    }
+
+    fn set_volume_window(env: Env, admin: Address, days: u32) {
+        pausable_component::assert_not_paused(&env);
+        admin_component::set_volume_window(&env, &admin, days);
+    }
+
+    fn get_volume_window(env: Env) -> u32 {
+        admin_component::get_volume_window(&env)
+    }
+
     fn propose_fee(env: Env, admin: Address, token: Address, fee: i128) {
         pausable_component::assert_not_paused(&env);
         admin_component::propose_fee(&env, &admin, &token, fee);
     }
 
+    fn propose_fee_schedule(env: Env, admin: Address, token: Address, schedule: FeeSchedule) {
+        pausable_component::assert_not_paused(&env);
+        admin_component::propose_fee_schedule(&env, &admin, &token, &schedule);
+    }
+
     fn execute_fee(env: Env, admin: Address, token: Address) {
         pausable_component::assert_not_paused(&env);
         admin_component::execute_fee(&env, &admin, &token);
@@ -105,9 +171,79 @@ impl ShadeTrait for Shade {
         admin_component::get_pending_fee(&env, &token)
     }
 
-    fn register_merchant(env: Env, merchant: Address) {
+    fn propose_volume_discounts(
+        env: Env,
+        admin: Address,
+        token: Address,
+        tiers: Vec<VolumeDiscount>,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        admin_component::propose_volume_discounts(&env, &admin, &token, &tiers);
+    }
+
+    fn execute_volume_discounts(env: Env, admin: Address, token: Address) {
         pausable_component::assert_not_paused(&env);
-        merchant_component::register_merchant(&env, &merchant);
+        admin_component::execute_volume_discounts(&env, &admin, &token);
+    }
+
+    fn get_volume_discounts(env: Env, token: Address) -> Vec<VolumeDiscount> {
+        admin_component::get_volume_discounts(&env, &token)
+    }
+
+    fn get_pending_volume_discounts(env: Env, token: Address) -> PendingVolumeDiscounts {
+        admin_component::get_pending_volume_discounts(&env, &token)
+    }
+
+    fn set_merchant_fee_override(
+        env: Env,
+        caller: Address,
+        merchant: Address,
+        token: Address,
+        fee_override: MerchantFeeOverride,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        fee_override_component::set_merchant_fee_override(
+            &env,
+            &caller,
+            &merchant,
+            &token,
+            &fee_override,
+        );
+    }
+
+    fn remove_merchant_fee_override(env: Env, caller: Address, merchant: Address, token: Address) {
+        pausable_component::assert_not_paused(&env);
+        fee_override_component::remove_merchant_fee_override(&env, &caller, &merchant, &token);
+    }
+
+    fn get_merchant_fee_override(
+        env: Env,
+        merchant: Address,
+        token: Address,
+    ) -> Option<MerchantFeeOverride> {
+        fee_override_component::get_merchant_fee_override(&env, &merchant, &token)
+    }
+
+    fn set_referral_config(env: Env, admin: Address, config: ReferralConfig) {
+        pausable_component::assert_not_paused(&env);
+        referral_component::set_referral_config(&env, &admin, &config);
+    }
+
+    fn get_referral_config(env: Env) -> ReferralConfig {
+        referral_component::get_referral_config(&env)
+    }
+
+    fn get_merchant_referral(env: Env, merchant: Address) -> Option<MerchantReferral> {
+        referral_component::get_merchant_referral(&env, &merchant)
+    }
+
+    fn get_referral_earnings(env: Env, referrer: Address, token: Address) -> i128 {
+        referral_component::get_referral_earnings(&env, &referrer, &token)
+    }
+
+    fn register_merchant(env: Env, merchant: Address, referrer: Option<Address>) {
+        pausable_component::assert_not_paused(&env);
+        merchant_component::register_merchant(&env, &merchant, referrer);
     }
 
     fn get_merchant(env: Env, merchant_id: u64) -> Merchant {
@@ -144,10 +280,54 @@ impl ShadeTrait for Shade {
         description: String,
         amount: i128,
         token: Address,
-        expires_at: Option<u64>,
+        options: InvoiceOptions,
+    ) -> u64 {
+        pausable_component::assert_not_paused(&env);
+        invoice_component::create_invoice(&env, &merchant, &description, amount, &token, &options)
+    }
+
+    fn get_invoice_by_reference(env: Env, merchant: Address, external_ref: String) -> Invoice {
+        invoice_component::get_invoice_by_reference(&env, &merchant, &external_ref)
+    }
+
+    fn create_itemized_invoice(
+        env: Env,
+        merchant: Address,
+        description: String,
+        line_items: Vec<LineItem>,
+        token: Address,
+        options: InvoiceOptions,
     ) -> u64 {
         pausable_component::assert_not_paused(&env);
-        invoice_component::create_invoice(&env, &merchant, &description, amount, &token, expires_at)
+        invoice_component::create_itemized_invoice(
+            &env,
+            &merchant,
+            &description,
+            &line_items,
+            &token,
+            &options,
+        )
+    }
+
+    fn create_multi_token_invoice(
+        env: Env,
+        merchant: Address,
+        description: String,
+        amount: i128,
+        reference_token: Address,
+        quote_currency: String,
+        options: InvoiceOptions,
+    ) -> u64 {
+        pausable_component::assert_not_paused(&env);
+        invoice_component::create_multi_token_invoice(
+            &env,
+            &merchant,
+            &description,
+            amount,
+            &reference_token,
+            &quote_currency,
+            &options,
+        )
     }
 
     fn create_fiat_invoice(
@@ -158,7 +338,7 @@ impl ShadeTrait for Shade {
         fiat_currency: String,
         fiat_decimals: u32,
         token: Address,
-        expires_at: Option<u64>,
+        options: InvoiceOptions,
     ) -> u64 {
         pausable_component::assert_not_paused(&env);
         invoice_component::create_fiat_invoice(
@@ -169,7 +349,7 @@ impl ShadeTrait for Shade {
             &fiat_currency,
             fiat_decimals,
             &token,
-            expires_at,
+            &options,
         )
     }
 
@@ -179,7 +359,7 @@ impl ShadeTrait for Shade {
         description: String,
         amount: i128,
         token: Address,
-        expires_at: Option<u64>,
+        options: InvoiceOptions,
     ) -> u64 {
         pausable_component::assert_not_paused(&env);
         invoice_component::create_invoice_draft(
@@ -188,7 +368,7 @@ impl ShadeTrait for Shade {
             &description,
             amount,
             &token,
-            expires_at,
+            &options,
         )
     }
 
@@ -197,28 +377,19 @@ impl ShadeTrait for Shade {
         invoice_component::finalize_invoice(&env, &merchant, invoice_id);
     }
 
-    #[allow(clippy::too_many_arguments)]
     fn create_invoice_signed(
         env: Env,
         caller: Address,
-        merchant: Address,
-        description: String,
-        amount: i128,
-        token: Address,
-        nonce: BytesN<32>,
+        payload: SignedInvoicePayload,
         signature: BytesN<64>,
     ) -> u64 {
         pausable_component::assert_not_paused(&env);
-        invoice_component::create_invoice_signed(
-            &env,
-            &caller,
-            &merchant,
-            &description,
-            amount,
-            &token,
-            &nonce,
-            &signature,
-        )
+        invoice_component::create_invoice_signed(&env, &caller, &payload, &signature)
+    }
+
+    fn purge_used_nonces(env: Env, merchant: Address, nonces: Vec<BytesN<32>>) -> u32 {
+        pausable_component::assert_not_paused(&env);
+        signature_util_component::purge_used_nonces(&env, &merchant, &nonces)
     }
 
     fn get_invoice(env: Env, invoice_id: u64) -> Invoice {
@@ -258,11 +429,224 @@ impl ShadeTrait for Shade {
         invoice_component::get_invoices(&env, filter)
     }
 
+    fn get_invoices_page(
+        env: Env,
+        index: InvoiceIndex,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> InvoicePage {
+        invoice_index_component::get_invoices_page(&env, index, cursor, limit)
+    }
+
+    fn backfill_invoice_indexes(
+        env: Env,
+        admin: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Option<u64> {
+        pausable_component::assert_not_paused(&env);
+        invoice_index_component::backfill_invoice_indexes(&env, &admin, cursor, limit)
+    }
+
     fn refund_invoice_partial(env: Env, merchant: Address, invoice_id: u64, amount: i128) {
         pausable_component::assert_not_paused(&env);
         invoice_component::refund_invoice_partial(&env, &merchant, invoice_id, amount);
     }
 
+    fn set_invoice_split_bill(env: Env, merchant: Address, invoice_id: u64, enabled: bool) {
+        pausable_component::assert_not_paused(&env);
+        split_bill_component::set_invoice_split_bill(&env, &merchant, invoice_id, enabled);
+    }
+
+    fn get_invoice_contributions(env: Env, invoice_id: u64) -> Vec<PayerContribution> {
+        split_bill_component::get_invoice_contributions(&env, invoice_id)
+    }
+
+    fn refund_invoice_payer(
+        env: Env,
+        merchant: Address,
+        invoice_id: u64,
+        payer: Address,
+        amount: i128,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        split_bill_component::refund_invoice_payer(&env, &merchant, invoice_id, &payer, amount);
+    }
+
+    fn issue_credit_note(
+        env: Env,
+        merchant: Address,
+        customer: Address,
+        token: Address,
+        amount: i128,
+        invoice_id: Option<u64>,
+        expires_at: Option<u64>,
+    ) -> u64 {
+        pausable_component::assert_not_paused(&env);
+        credit_note_component::issue_credit_note(
+            &env, &merchant, &customer, &token, amount, invoice_id, expires_at,
+        )
+    }
+
+    fn get_credit_note(env: Env, credit_note_id: u64) -> CreditNote {
+        credit_note_component::get_credit_note(&env, credit_note_id)
+    }
+
+    fn void_credit_note(env: Env, merchant: Address, credit_note_id: u64) {
+        pausable_component::assert_not_paused(&env);
+        credit_note_component::void_credit_note(&env, &merchant, credit_note_id);
+    }
+
+    fn request_refund(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        amount: i128,
+        reason_hash: BytesN<32>,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        refund_request_component::request_refund(&env, &payer, invoice_id, amount, &reason_hash);
+    }
+
+    fn respond_to_refund_request(env: Env, merchant: Address, invoice_id: u64, accept: bool) {
+        pausable_component::assert_not_paused(&env);
+        refund_request_component::respond_to_refund_request(&env, &merchant, invoice_id, accept);
+    }
+
+    fn arbitrate_refund_request(env: Env, arbiter: Address, invoice_id: u64, approve: bool) {
+        pausable_component::assert_not_paused(&env);
+        refund_request_component::arbitrate_refund_request(&env, &arbiter, invoice_id, approve);
+    }
+
+    fn get_refund_request(env: Env, invoice_id: u64) -> RefundRequest {
+        refund_request_component::get_refund_request(&env, invoice_id)
+    }
+
+    fn set_refund_window_bounds(env: Env, admin: Address, bounds: RefundWindowBounds) {
+        pausable_component::assert_not_paused(&env);
+        refund_policy_component::set_refund_window_bounds(&env, &admin, &bounds);
+    }
+
+    fn get_refund_window_bounds(env: Env) -> RefundWindowBounds {
+        refund_policy_component::get_refund_window_bounds(&env)
+    }
+
+    fn set_merchant_refund_window(
+        env: Env,
+        merchant: Address,
+        token: Option<Address>,
+        window: u64,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        refund_policy_component::set_merchant_refund_window(&env, &merchant, &token, window);
+    }
+
+    fn remove_merchant_refund_window(env: Env, merchant: Address, token: Option<Address>) {
+        pausable_component::assert_not_paused(&env);
+        refund_policy_component::remove_merchant_refund_window(&env, &merchant, &token);
+    }
+
+    fn get_refund_window(env: Env, merchant_id: u64, token: Address) -> u64 {
+        refund_policy_component::get_refund_window(&env, merchant_id, &token)
+    }
+
+    fn set_merchant_expiry_policy(env: Env, merchant: Address, policy: ExpiryRefundPolicy) {
+        pausable_component::assert_not_paused(&env);
+        invoice_expiry_component::set_merchant_expiry_policy(&env, &merchant, policy);
+    }
+
+    fn get_merchant_expiry_policy(env: Env, merchant_id: u64) -> ExpiryRefundPolicy {
+        invoice_expiry_component::get_merchant_expiry_policy(&env, merchant_id)
+    }
+
+    fn set_merchant_tip_config(env: Env, merchant: Address, config: TipConfig) {
+        pausable_component::assert_not_paused(&env);
+        tips_component::set_merchant_tip_config(&env, &merchant, &config);
+    }
+
+    fn get_merchant_tip_config(env: Env, merchant_id: u64) -> TipConfig {
+        tips_component::get_merchant_tip_config(&env, merchant_id)
+    }
+
+    fn expire_invoices(env: Env, invoice_ids: Vec<u64>) -> u32 {
+        pausable_component::assert_not_paused(&env);
+        invoice_expiry_component::expire_invoices(&env, &invoice_ids)
+    }
+
+    fn sweep_expired_invoices(env: Env, cursor: Option<u64>, limit: u32) -> Option<u64> {
+        pausable_component::assert_not_paused(&env);
+        invoice_expiry_component::sweep_expired_invoices(&env, cursor, limit)
+    }
+
+    fn create_invoice_template(
+        env: Env,
+        merchant: Address,
+        payer: Address,
+        description: String,
+        amount: i128,
+        token: Address,
+        interval: u64,
+    ) -> u64 {
+        pausable_component::assert_not_paused(&env);
+        invoice_template_component::create_invoice_template(
+            &env,
+            &merchant,
+            &payer,
+            &description,
+            amount,
+            &token,
+            interval,
+        )
+    }
+
+    fn get_invoice_template(env: Env, template_id: u64) -> InvoiceTemplate {
+        invoice_template_component::get_invoice_template(&env, template_id)
+    }
+
+    fn pause_invoice_template(env: Env, merchant: Address, template_id: u64) {
+        pausable_component::assert_not_paused(&env);
+        invoice_template_component::pause_invoice_template(&env, &merchant, template_id);
+    }
+
+    fn resume_invoice_template(env: Env, merchant: Address, template_id: u64) {
+        pausable_component::assert_not_paused(&env);
+        invoice_template_component::resume_invoice_template(&env, &merchant, template_id);
+    }
+
+    fn end_invoice_template(env: Env, merchant: Address, template_id: u64) {
+        pausable_component::assert_not_paused(&env);
+        invoice_template_component::end_invoice_template(&env, &merchant, template_id);
+    }
+
+    fn issue_due_invoices(env: Env, cursor: Option<u64>, limit: u32) -> Option<u64> {
+        pausable_component::assert_not_paused(&env);
+        invoice_template_component::issue_due_invoices(&env, cursor, limit)
+    }
+
+    fn set_invoice_installments(
+        env: Env,
+        merchant: Address,
+        invoice_id: u64,
+        installments: Vec<Installment>,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        installments_component::set_invoice_installments(
+            &env,
+            &merchant,
+            invoice_id,
+            &installments,
+        );
+    }
+
+    fn get_invoice_installments(env: Env, invoice_id: u64) -> InstallmentPlan {
+        installments_component::get_invoice_installments(&env, invoice_id)
+    }
+
+    fn check_installments_overdue(env: Env, invoice_id: u64) -> bool {
+        pausable_component::assert_not_paused(&env);
+        installments_component::check_installments_overdue(&env, invoice_id)
+    }
+
     fn pause(env: Env, admin: Address) {
         pausable_component::pause(&env, &admin);
     }
@@ -296,6 +680,10 @@ impl ShadeTrait for Shade {
         admin_component::get_merchant_volume(&env, &merchant, &token)
     }
 
+    fn get_merchant_window_volume(env: Env, merchant: Address, token: Address) -> i128 {
+        admin_component::get_window_volume(&env, &merchant, &token)
+    }
+
     fn get_merchant_analytics(env: Env, merchant: Address, token: Address) -> MerchantAnalytics {
         admin_component::get_merchant_analytics(&env, &merchant, &token)
     }
@@ -312,9 +700,65 @@ impl ShadeTrait for Shade {
         merchant_component::get_merchant_account(&env, merchant_id)
     }
 
-    fn pay_invoice(env: Env, payer: Address, invoice_id: u64) {
+    fn set_merchant_tax_rate(env: Env, merchant: Address, code: String, rate_bps: u32) {
+        pausable_component::assert_not_paused(&env);
+        tax_component::set_merchant_tax_rate(&env, &merchant, &code, rate_bps);
+    }
+
+    fn remove_merchant_tax_rate(env: Env, merchant: Address, code: String) {
+        pausable_component::assert_not_paused(&env);
+        tax_component::remove_merchant_tax_rate(&env, &merchant, &code);
+    }
+
+    fn get_merchant_tax_rate(env: Env, merchant_id: u64, code: String) -> u32 {
+        tax_component::get_merchant_tax_rate(&env, merchant_id, &code)
+    }
+
+    fn set_merchant_tax_account(env: Env, merchant: Address, account: Address) {
+        pausable_component::assert_not_paused(&env);
+        tax_component::set_merchant_tax_account(&env, &merchant, &account);
+    }
+
+    fn get_merchant_tax_account(env: Env, merchant_id: u64) -> Option<Address> {
+        tax_component::get_merchant_tax_account(&env, merchant_id)
+    }
+
+    fn pay_invoice(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        max_amount: Option<i128>,
+        credit_note_id: Option<u64>,
+        expected_revision: Option<u32>,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        invoice_component::pay_invoice(
+            &env,
+            &payer,
+            invoice_id,
+            max_amount,
+            credit_note_id,
+            expected_revision,
+        );
+    }
+
+    fn pay_invoice_with_tip(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        max_amount: Option<i128>,
+        tip: i128,
+        expected_revision: Option<u32>,
+    ) {
         pausable_component::assert_not_paused(&env);
-        invoice_component::pay_invoice(&env, &payer, invoice_id);
+        invoice_component::pay_invoice_with_tip(
+            &env,
+            &payer,
+            invoice_id,
+            max_amount,
+            tip,
+            expected_revision,
+        );
     }
 
     fn pay_invoices_batch(env: Env, payer: Address, invoice_ids: Vec<u64>) {
@@ -322,9 +766,47 @@ impl ShadeTrait for Shade {
         invoice_component::pay_invoices_batch(&env, &payer, &invoice_ids);
     }
 
-    fn pay_invoice_partial(env: Env, payer: Address, invoice_id: u64, amount: i128) {
+    fn pay_invoice_partial(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        amount: i128,
+        credit_note_id: Option<u64>,
+        expected_revision: Option<u32>,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        invoice_component::pay_invoice_partial(
+            &env,
+            &payer,
+            invoice_id,
+            amount,
+            credit_note_id,
+            expected_revision,
+        );
+    }
+
+    fn pay_invoice_with_token(
+        env: Env,
+        payer: Address,
+        invoice_id: u64,
+        token: Address,
+        max_amount: Option<i128>,
+        expected_revision: Option<u32>,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        invoice_component::pay_invoice_with_token(
+            &env,
+            &payer,
+            invoice_id,
+            &token,
+            max_amount,
+            expected_revision,
+        );
+    }
+
+    fn lock_fiat_quote(env: Env, payer: Address, invoice_id: u64, token: Option<Address>) -> i128 {
         pausable_component::assert_not_paused(&env);
-        invoice_component::pay_invoice_partial(&env, &payer, invoice_id, amount);
+        invoice_component::lock_fiat_quote(&env, &payer, invoice_id, token)
     }
 
     fn validate_payment_payload(env: Env, payload: crate::types::PaymentPayload) {
@@ -336,6 +818,11 @@ impl ShadeTrait for Shade {
         invoice_component::void_invoice(&env, &merchant, invoice_id);
     }
 
+    fn cancel_invoice(env: Env, merchant: Address, invoice_id: u64) {
+        pausable_component::assert_not_paused(&env);
+        invoice_component::cancel_invoice(&env, &merchant, invoice_id);
+    }
+
     fn amend_invoice(
         env: Env,
         merchant: Address,
@@ -347,6 +834,35 @@ impl ShadeTrait for Shade {
         invoice_component::amend_invoice(&env, &merchant, invoice_id, new_amount, new_description);
     }
 
+    fn amend_invoice_items(
+        env: Env,
+        merchant: Address,
+        invoice_id: u64,
+        line_items: Vec<LineItem>,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        invoice_component::amend_invoice_items(&env, &merchant, invoice_id, &line_items);
+    }
+
+    fn apply_invoice_tax(env: Env, merchant: Address, invoice_id: u64, tax_code: String) {
+        pausable_component::assert_not_paused(&env);
+        invoice_component::apply_invoice_tax(&env, &merchant, invoice_id, &tax_code);
+    }
+
+    fn get_invoice_revisions(env: Env, invoice_id: u64) -> Vec<InvoiceRevision> {
+        invoice_component::get_invoice_revisions(&env, invoice_id)
+    }
+
+    fn set_invoice_terms(
+        env: Env,
+        merchant: Address,
+        invoice_id: u64,
+        terms: Option<PaymentTerms>,
+    ) {
+        pausable_component::assert_not_paused(&env);
+        payment_terms_component::set_invoice_terms(&env, &merchant, invoice_id, terms);
+    }
+
     fn propose_admin_transfer(env: Env, admin: Address, new_admin: Address) {
         admin_component::propose_admin_transfer(&env, &admin, &new_admin);
     }
@@ -434,20 +950,11 @@ impl ShadeTrait for Shade {
     fn get_user_transactions(env: Env, user: Address) -> Vec<Transaction> {
         history_component::get_user_transactions(&env, user)
     }
-    
-    fn emit_bridge_placeholder(
-        env: Env,
-        caller: Address,
-        payload: CrossChainBridgePayload,
-    ) {
+
+    fn emit_bridge_placeholder(env: Env, caller: Address, payload: CrossChainBridgePayload) {
         pausable_component::assert_not_paused(&env);
         caller.require_auth();
-        events::publish_bridge_placeholder_event(
-            &env,
-            caller,
-            payload,
-            env.ledger().timestamp(),
-        );
+        events::publish_bridge_placeholder_event(&env, caller, payload, env.ledger().timestamp());
     }
 
     // --- Event ticketing system ---
@@ -0,0 +1,102 @@
+#![cfg(test)]
+
+use crate::shade::{Shade, ShadeClient};
+use crate::types::InvoiceOptions;
+use account::account::{MerchantAccount, MerchantAccountClient};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, Env, String};
+
+/// An initialized Shade contract with one accepted token and a registered
+/// merchant. The merchant account is a real `MerchantAccount` so refunds and
+/// cancellations can move funds back out of it.
+pub struct ShadeFixture {
+    pub env: Env,
+    pub client: ShadeClient<'static>,
+    pub contract_id: Address,
+    pub admin: Address,
+    pub platform: Address,
+    pub token: Address,
+    pub merchant: Address,
+    pub merchant_account: Address,
+}
+
+impl ShadeFixture {
+    pub fn setup() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(Shade, ());
+        let client = ShadeClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+        let platform = Address::generate(&env);
+        client.set_platform_account(&admin, &platform);
+
+        let token = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        client.add_accepted_token(&admin, &token);
+
+        let merchant = Address::generate(&env);
+        client.register_merchant(&merchant, &None);
+        let merchant_account = env.register(MerchantAccount, ());
+        MerchantAccountClient::new(&env, &merchant_account).initialize(
+            &merchant,
+            &contract_id,
+            &1_u64,
+        );
+        client.set_merchant_account(&merchant, &merchant_account);
+
+        ShadeFixture {
+            env,
+            client,
+            contract_id,
+            admin,
+            platform,
+            token,
+            merchant,
+            merchant_account,
+        }
+    }
+
+    /// Charges `fee_bps` on the fixture token.
+    pub fn with_fee(self, fee_bps: i128) -> Self {
+        self.client.set_fee(&self.admin, &self.token, &fee_bps);
+        self
+    }
+
+    /// Registers another Stellar asset and adds it to the accepted tokens.
+    pub fn add_token(&self) -> Address {
+        let token = self
+            .env
+            .register_stellar_asset_contract_v2(Address::generate(&self.env))
+            .address();
+        self.client.add_accepted_token(&self.admin, &token);
+        token
+    }
+
+    /// A plain invoice from the fixture merchant in the fixture token.
+    pub fn create_invoice(&self, amount: i128) -> u64 {
+        self.client.create_invoice(
+            &self.merchant,
+            &String::from_str(&self.env, "Order"),
+            &amount,
+            &self.token,
+            &InvoiceOptions::default(),
+        )
+    }
+
+    /// A fresh address holding `amount` of the fixture token.
+    pub fn funded_payer(&self, amount: i128) -> Address {
+        let payer = Address::generate(&self.env);
+        self.mint(&self.token, &payer, amount);
+        payer
+    }
+
+    pub fn mint(&self, token: &Address, to: &Address, amount: i128) {
+        token::StellarAssetClient::new(&self.env, token).mint(to, &amount);
+    }
+
+    pub fn balance(&self, owner: &Address) -> i128 {
+        token::TokenClient::new(&self.env, &self.token).balance(owner)
+    }
+}
@@ -1,3 +1,4 @@
+pub mod common;
 pub mod test;
 pub mod test_accepted_tokens;
 pub mod test_access_control;
@@ -7,35 +8,56 @@ pub mod test_admin_transfer;
 pub mod test_fee_discount;
 // pub mod test_batch_token_whitelist;
 pub mod test_calculate_fee;
+pub mod test_credit_note;
 pub mod test_date_range_filter;
 pub mod test_draft_invoice;
+pub mod test_event_tickets;
+pub mod test_fee_accrual;
 pub mod test_fee_discounts;
+pub mod test_fee_override;
+pub mod test_fee_schedule;
 pub mod test_fees;
+pub mod test_fiat_pricing;
+pub mod test_fiat_quote_lock;
+pub mod test_installments;
 pub mod test_invoice;
 pub mod test_invoice_amendment;
+pub mod test_invoice_cancel;
 pub mod test_invoice_expiry;
 pub mod test_invoice_filter;
+pub mod test_invoice_pagination;
 pub mod test_invoice_partial_refund;
+pub mod test_invoice_payer;
+pub mod test_invoice_reference;
 pub mod test_invoice_signed;
+pub mod test_invoice_template;
 pub mod test_invoice_void;
+pub mod test_line_items;
 pub mod test_merchant;
 pub mod test_merchant_activation;
 pub mod test_merchant_key;
 pub mod test_merchant_tokens;
 mod test_merchant_verification;
 pub mod test_merchant_webhook;
+pub mod test_multi_token;
 pub mod test_pausable;
 pub mod test_payment;
 pub mod test_payment_payload;
+pub mod test_payment_terms;
 pub mod test_querying;
 pub mod test_reentrancy;
+pub mod test_referral;
 pub mod test_refund;
+pub mod test_refund_policy;
+pub mod test_refund_request;
 pub mod test_shade_restriction;
 pub mod test_signatures;
+pub mod test_split_bill;
 pub mod test_subscription;
 pub mod test_subscription_enrollment;
+pub mod test_tax;
 pub mod test_time_locked_fees;
+pub mod test_tips;
 pub mod test_transaction_history;
 pub mod test_upgrade;
-pub mod test_fiat_pricing;
-pub mod test_event_tickets;
+pub mod test_volume_window;
@@ -108,8 +108,8 @@ fn test_register_merchant_integration_and_uniqueness() {
     let merchant_b = Address::generate(&env);
 
     // Register merchants in shade
-    client.register_merchant(&merchant_a);
-    client.register_merchant(&merchant_b);
+    client.register_merchant(&merchant_a, &None);
+    client.register_merchant(&merchant_b, &None);
 
     // For testing integration we register account instances directly and simulate factory behavior
     let deployed_a = {
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::{InvoiceStatus, Role};
+use crate::types::{InvoiceOptions, InvoiceStatus, Role};
 use soroban_sdk::testutils::Address as _;
 use soroban_sdk::{Address, Env, String};
 
@@ -47,7 +47,7 @@ fn test_invoice_state_validation() {
     let (env, client, _admin, _manager, merchant, _payer, token) = setup_invoice_test();
 
     // Register merchant
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Create invoice
     let invoice_id = client.create_invoice(
@@ -55,7 +55,7 @@ fn test_invoice_state_validation() {
         &String::from_str(&env, "Test Invoice"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     // Verify initial state
@@ -70,7 +70,7 @@ fn test_multiple_invoices_independent() {
     let (env, client, _admin, _manager, merchant, _payer, token) = setup_invoice_test();
 
     // Register merchant
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Create multiple invoices
     let id_1 = client.create_invoice(
@@ -78,14 +78,14 @@ fn test_multiple_invoices_independent() {
         &String::from_str(&env, "Invoice 1"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     let id_2 = client.create_invoice(
         &merchant,
         &String::from_str(&env, "Invoice 2"),
         &2000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     // Set second to Paid via storage manipulation
@@ -108,7 +108,7 @@ fn test_fee_preservation() {
     let (env, client, admin, _manager, merchant, _payer, token) = setup_invoice_test();
 
     // Register merchant
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Set custom fee
     let fee = 250i128;
@@ -120,7 +120,7 @@ fn test_fee_preservation() {
         &String::from_str(&env, "Test Invoice"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     // Verify fee and invoice data
@@ -147,7 +147,7 @@ fn test_contract_pause_and_unpause() {
     let (env, client, admin, _manager, merchant, _payer, token) = setup_invoice_test();
 
     // Register merchant
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Pause contract
     client.pause(&admin);
@@ -163,7 +163,7 @@ fn test_contract_pause_and_unpause() {
         &String::from_str(&env, "Post-unpause invoice"),
         &500,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     assert!(invoice_id > 0);
 }
@@ -0,0 +1,308 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::{CreditNoteStatus, InvoiceOptions, InvoiceStatus};
+use soroban_sdk::testutils::{Address as _, Ledger as _};
+use soroban_sdk::{Address, String};
+
+/// The shared fixture plus a customer holding 2_000.
+fn setup_test() -> (ShadeFixture, Address) {
+    let s = ShadeFixture::setup();
+    let customer = s.funded_payer(2_000);
+    (s, customer)
+}
+
+#[test]
+fn test_credit_reduces_tokens_transferred() {
+    let (s, customer) = setup_test();
+    let note_id = s
+        .client
+        .issue_credit_note(&s.merchant, &customer, &s.token, &300, &None, &None);
+    let invoice_id = s.create_invoice(1_000);
+
+    s.client
+        .pay_invoice(&customer, &invoice_id, &None, &Some(note_id), &None);
+
+    assert_eq!(s.balance(&customer), 1_300);
+    assert_eq!(s.balance(&s.merchant_account), 700);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount_paid, 1_000);
+    assert_eq!(invoice.credit_applied, 300);
+
+    let note = s.client.get_credit_note(&note_id);
+    assert_eq!(note.amount_redeemed, 300);
+    assert_eq!(note.status, CreditNoteStatus::Redeemed);
+
+    let analytics = s.client.get_merchant_analytics(&s.merchant, &s.token);
+    assert_eq!(analytics.total_volume, 700);
+    assert_eq!(analytics.total_credit, 300);
+    assert_eq!(
+        s.client
+            .get_merchant_analytics_summary(&s.merchant)
+            .total_credit,
+        300
+    );
+}
+
+#[test]
+fn test_credit_larger_than_invoice_keeps_balance() {
+    let (s, customer) = setup_test();
+    let note_id =
+        s.client
+            .issue_credit_note(&s.merchant, &customer, &s.token, &1_500, &None, &None);
+    let invoice_id = s.create_invoice(1_000);
+
+    s.client
+        .pay_invoice(&customer, &invoice_id, &None, &Some(note_id), &None);
+
+    assert_eq!(s.balance(&customer), 2_000);
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+    let note = s.client.get_credit_note(&note_id);
+    assert_eq!(note.amount_redeemed, 1_000);
+    assert_eq!(note.status, CreditNoteStatus::Active);
+}
+
+#[test]
+fn test_credit_against_invoice_is_not_refundable() {
+    let (s, customer) = setup_test();
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .pay_invoice(&customer, &invoice_id, &None, &None, &None);
+
+    let note_id = s.client.issue_credit_note(
+        &s.merchant,
+        &customer,
+        &s.token,
+        &400,
+        &Some(invoice_id),
+        &None,
+    );
+    assert_eq!(
+        s.client.get_credit_note(&note_id).invoice_id,
+        Some(invoice_id)
+    );
+    assert_eq!(s.client.get_invoice(&invoice_id).credit_issued, 400);
+
+    s.client.refund_invoice(&s.merchant, &invoice_id);
+    assert_eq!(s.balance(&customer), 1_600);
+    assert_eq!(s.balance(&s.merchant_account), 400);
+}
+
+#[test]
+fn test_credited_portion_of_invoice_is_not_refundable() {
+    let (s, customer) = setup_test();
+    let note_id = s
+        .client
+        .issue_credit_note(&s.merchant, &customer, &s.token, &300, &None, &None);
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .pay_invoice(&customer, &invoice_id, &None, &Some(note_id), &None);
+
+    s.client.refund_invoice(&s.merchant, &invoice_id);
+    assert_eq!(s.balance(&customer), 2_000);
+    assert_eq!(s.client.get_invoice(&invoice_id).amount_refunded, 700);
+}
+
+#[test]
+fn test_cancel_returns_credit_to_note_and_tokens_to_customer() {
+    let (s, customer) = setup_test();
+    let note_id = s
+        .client
+        .issue_credit_note(&s.merchant, &customer, &s.token, &300, &None, &None);
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .pay_invoice_partial(&customer, &invoice_id, &400, &Some(note_id), &None);
+    assert_eq!(s.balance(&s.merchant_account), 100);
+    assert_eq!(
+        s.client.get_credit_note(&note_id).status,
+        CreditNoteStatus::Redeemed
+    );
+
+    s.client.cancel_invoice(&s.merchant, &invoice_id);
+
+    // Only the 100 paid in tokens comes back as tokens.
+    assert_eq!(s.balance(&customer), 2_000);
+    assert_eq!(s.balance(&s.merchant_account), 0);
+    assert_eq!(s.client.get_invoice(&invoice_id).amount_refunded, 100);
+
+    let note = s.client.get_credit_note(&note_id);
+    assert_eq!(note.amount_redeemed, 0);
+    assert_eq!(note.status, CreditNoteStatus::Active);
+    assert_eq!(
+        s.client
+            .get_merchant_analytics(&s.merchant, &s.token)
+            .total_credit,
+        0
+    );
+}
+
+#[test]
+fn test_expiry_returns_credit_to_note() {
+    let (s, customer) = setup_test();
+    let note_id = s
+        .client
+        .issue_credit_note(&s.merchant, &customer, &s.token, &300, &None, &None);
+    let invoice_id = s.client.create_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Order"),
+        &1_000,
+        &s.token,
+        &InvoiceOptions {
+            expires_at: Some(500),
+            ..Default::default()
+        },
+    );
+    s.client
+        .pay_invoice_partial(&customer, &invoice_id, &500, &Some(note_id), &None);
+
+    s.env.ledger().set_timestamp(1_000);
+    assert_eq!(
+        s.client
+            .expire_invoices(&soroban_sdk::vec![&s.env, invoice_id]),
+        1
+    );
+
+    assert_eq!(s.balance(&customer), 2_000);
+    assert_eq!(s.client.get_invoice(&invoice_id).amount_refunded, 200);
+    assert_eq!(s.client.get_credit_note(&note_id).amount_redeemed, 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_credit_against_invoice_capped_at_refundable() {
+    let (s, customer) = setup_test();
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .pay_invoice(&customer, &invoice_id, &None, &None, &None);
+    s.client.issue_credit_note(
+        &s.merchant,
+        &customer,
+        &s.token,
+        &600,
+        &Some(invoice_id),
+        &None,
+    );
+    s.client.issue_credit_note(
+        &s.merchant,
+        &customer,
+        &s.token,
+        &600,
+        &Some(invoice_id),
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #125)")]
+fn test_credit_against_invoice_paid_by_someone_else() {
+    let (s, customer) = setup_test();
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .pay_invoice(&customer, &invoice_id, &None, &None, &None);
+    s.client.issue_credit_note(
+        &s.merchant,
+        &Address::generate(&s.env),
+        &s.token,
+        &100,
+        &Some(invoice_id),
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_credit_against_unpaid_invoice() {
+    let (s, customer) = setup_test();
+    let invoice_id = s.create_invoice(1_000);
+    s.client.issue_credit_note(
+        &s.merchant,
+        &customer,
+        &s.token,
+        &100,
+        &Some(invoice_id),
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_only_customer_can_redeem() {
+    let (s, customer) = setup_test();
+    let note_id = s
+        .client
+        .issue_credit_note(&s.merchant, &customer, &s.token, &300, &None, &None);
+    let invoice_id = s.create_invoice(1_000);
+    let other = s.funded_payer(1_000);
+    s.client
+        .pay_invoice(&other, &invoice_id, &None, &Some(note_id), &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #125)")]
+fn test_credit_not_redeemable_with_other_merchant() {
+    let (s, customer) = setup_test();
+    let note_id = s
+        .client
+        .issue_credit_note(&s.merchant, &customer, &s.token, &300, &None, &None);
+
+    let other_merchant = Address::generate(&s.env);
+    s.client.register_merchant(&other_merchant, &None);
+    s.client
+        .set_merchant_account(&other_merchant, &Address::generate(&s.env));
+    let invoice_id = s.client.create_invoice(
+        &other_merchant,
+        &String::from_str(&s.env, "Elsewhere"),
+        &1_000,
+        &s.token,
+        &InvoiceOptions::default(),
+    );
+    s.client
+        .pay_invoice(&customer, &invoice_id, &None, &Some(note_id), &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #123)")]
+fn test_expired_credit_not_redeemable() {
+    let (s, customer) = setup_test();
+    let note_id =
+        s.client
+            .issue_credit_note(&s.merchant, &customer, &s.token, &300, &None, &Some(1_000));
+    s.env.ledger().set_timestamp(1_000);
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .pay_invoice(&customer, &invoice_id, &None, &Some(note_id), &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #124)")]
+fn test_voided_credit_not_redeemable() {
+    let (s, customer) = setup_test();
+    let note_id = s
+        .client
+        .issue_credit_note(&s.merchant, &customer, &s.token, &300, &None, &None);
+    s.client.void_credit_note(&s.merchant, &note_id);
+    assert_eq!(
+        s.client.get_credit_note(&note_id).status,
+        CreditNoteStatus::Voided
+    );
+
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .pay_invoice(&customer, &invoice_id, &None, &Some(note_id), &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_only_issuer_can_void() {
+    let (s, customer) = setup_test();
+    let note_id = s
+        .client
+        .issue_credit_note(&s.merchant, &customer, &s.token, &300, &None, &None);
+    s.client
+        .void_credit_note(&Address::generate(&s.env), &note_id);
+}
@@ -6,7 +6,7 @@
 //! `InvoiceFilter` in isolation and in combination with other filters.
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::{InvoiceFilter, InvoiceStatus};
+use crate::types::{InvoiceFilter, InvoiceOptions, InvoiceStatus};
 use soroban_sdk::testutils::{Address as _, Ledger as _};
 use soroban_sdk::{Address, Env, String};
 
@@ -38,7 +38,7 @@ fn create_invoices_at(
     timestamps_and_amounts: &[(u64, i128)],
 ) -> (Address, Address, soroban_sdk::Vec<u64>) {
     let merchant = Address::generate(env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = make_token(env, admin);
     client.add_accepted_token(admin, &token);
@@ -51,7 +51,7 @@ fn create_invoices_at(
             &String::from_str(env, "Test invoice"),
             amount,
             &token,
-            &None,
+            &InvoiceOptions::default(),
         );
         ids.push_back(id);
     }
@@ -392,8 +392,8 @@ fn test_date_range_combined_with_merchant_filter() {
     // Two merchants, each with invoices at different timestamps
     let merchant_a = Address::generate(&env);
     let merchant_b = Address::generate(&env);
-    client.register_merchant(&merchant_a);
-    client.register_merchant(&merchant_b);
+    client.register_merchant(&merchant_a, &None);
+    client.register_merchant(&merchant_b, &None);
 
     let token = make_token(&env, &admin);
     client.add_accepted_token(&admin, &token);
@@ -404,7 +404,7 @@ fn test_date_range_combined_with_merchant_filter() {
         &String::from_str(&env, "A1"),
         &100,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     env.ledger().set_timestamp(2000);
@@ -413,7 +413,7 @@ fn test_date_range_combined_with_merchant_filter() {
         &String::from_str(&env, "B1"),
         &200,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     env.ledger().set_timestamp(3000);
@@ -422,7 +422,7 @@ fn test_date_range_combined_with_merchant_filter() {
         &String::from_str(&env, "A2"),
         &300,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     // Filter: merchant_a AND date range [1000, 2000] ? only A1
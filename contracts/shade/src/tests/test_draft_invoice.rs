@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::InvoiceStatus;
+use crate::types::{InvoiceOptions, InvoiceStatus};
 use account::account::MerchantAccount;
 use soroban_sdk::testutils::Address as _;
 use soroban_sdk::{token, Address, Env, String};
@@ -30,12 +30,18 @@ fn test_create_draft_invoice() {
     let (env, client, _contract_id, _admin, token) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let description = String::from_str(&env, "Draft Invoice");
     let amount: i128 = 1000;
 
-    let invoice_id = client.create_invoice_draft(&merchant, &description, &amount, &token, &None);
+    let invoice_id = client.create_invoice_draft(
+        &merchant,
+        &description,
+        &amount,
+        &token,
+        &InvoiceOptions::default(),
+    );
     assert_eq!(invoice_id, 1);
 
     let invoice = client.get_invoice(&invoice_id);
@@ -54,17 +60,23 @@ fn test_pay_draft_invoice_fails() {
     let (env, client, _contract_id, _admin, token) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let description = String::from_str(&env, "Draft Invoice");
-    let invoice_id = client.create_invoice_draft(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice_draft(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let customer = Address::generate(&env);
     let token_client = token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
 
     // Try to pay draft invoice (should panic with InvalidInvoiceStatus = #16)
-    client.pay_invoice(&customer, &invoice_id);
+    client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 }
 
 #[test]
@@ -72,10 +84,16 @@ fn test_finalize_invoice_success() {
     let (env, client, _contract_id, _admin, token) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let description = String::from_str(&env, "Draft Invoice");
-    let invoice_id = client.create_invoice_draft(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice_draft(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Verify invoice is Draft
     let invoice_before = client.get_invoice(&invoice_id);
@@ -95,7 +113,7 @@ fn test_pay_finalized_invoice_success() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = env.register(MerchantAccount, ());
@@ -106,7 +124,13 @@ fn test_pay_finalized_invoice_success() {
 
     // Create draft invoice
     let description = String::from_str(&env, "Draft Invoice");
-    let invoice_id = client.create_invoice_draft(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice_draft(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Finalize invoice
     client.finalize_invoice(&merchant, &invoice_id);
@@ -116,7 +140,7 @@ fn test_pay_finalized_invoice_success() {
     token_admin.mint(&customer, &1000);
 
     // Pay invoice
-    client.pay_invoice(&customer, &invoice_id);
+    client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     let invoice_after = client.get_invoice(&invoice_id);
     assert_eq!(invoice_after.status, InvoiceStatus::Paid);
@@ -128,13 +152,19 @@ fn test_finalize_invoice_non_owner_fails() {
     let (env, client, _contract_id, _admin, token) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let description = String::from_str(&env, "Draft Invoice");
-    let invoice_id = client.create_invoice_draft(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice_draft(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let other_merchant = Address::generate(&env);
-    client.register_merchant(&other_merchant);
+    client.register_merchant(&other_merchant, &None);
 
     // Try to finalize with different merchant (should panic with NotAuthorized = #1)
     client.finalize_invoice(&other_merchant, &invoice_id);
@@ -146,10 +176,16 @@ fn test_finalize_non_draft_invoice_fails() {
     let (env, client, _contract_id, _admin, token) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let description = String::from_str(&env, "Standard Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Try to finalize a standard Pending invoice (should panic with InvalidInvoiceStatus = #16)
     client.finalize_invoice(&merchant, &invoice_id);
@@ -1,9 +1,11 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use soroban_sdk::testutils::{Address as _, Ledger as _, MockAuth, MockAuthInvoke};
+use soroban_sdk::testutils::{
+    Address as _, AuthorizedFunction, Ledger as _, MockAuth, MockAuthInvoke,
+};
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
-use soroban_sdk::{Address, Env, IntoVal, String};
+use soroban_sdk::{Address, Env, IntoVal, String, Symbol};
 
 const TOKEN_INITIAL_BALANCE: i128 = 1_000_000;
 
@@ -60,7 +62,7 @@ fn register_merchant_with_account(
 ) -> (Address, Address) {
     let merchant = Address::generate(env);
     let merchant_account = Address::generate(env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
     client.set_merchant_account(&merchant, &merchant_account);
     client.set_merchant_accepted_tokens(
         &merchant,
@@ -173,8 +175,7 @@ fn create_event_rejects_past_date() {
 #[test]
 fn purchase_ticket_transfers_funds_and_mints() {
     let f = setup();
-    let (merchant, merchant_account) =
-        register_merchant_with_account(&f.env, &f.client, &f.token);
+    let (merchant, merchant_account) = register_merchant_with_account(&f.env, &f.client, &f.token);
     let buyer = Address::generate(&f.env);
     fund(&f.env, &f.token, &buyer, TOKEN_INITIAL_BALANCE);
 
@@ -217,8 +218,7 @@ fn purchase_ticket_transfers_funds_and_mints() {
 #[test]
 fn purchase_ticket_routes_fee_to_platform_when_configured() {
     let f = setup();
-    let (merchant, merchant_account) =
-        register_merchant_with_account(&f.env, &f.client, &f.token);
+    let (merchant, merchant_account) = register_merchant_with_account(&f.env, &f.client, &f.token);
     // 10% platform fee on this token.
     f.client.set_fee(&f.admin, &f.token, &1_000i128);
 
@@ -241,7 +241,10 @@ fn purchase_ticket_routes_fee_to_platform_when_configured() {
     let token_client = TokenClient::new(&f.env, &f.token);
     let platform = f.client.get_platform_account();
     let expected_fee = price / 10; // 10% in bps == 1000
-    assert_eq!(token_client.balance(&merchant_account), price - expected_fee);
+    assert_eq!(
+        token_client.balance(&merchant_account),
+        price - expected_fee
+    );
     assert_eq!(token_client.balance(&platform), expected_fee);
 }
 
@@ -283,8 +286,7 @@ fn purchase_ticket_panics_when_event_missing() {
 #[test]
 fn resale_splits_royalty_and_proceeds() {
     let f = setup();
-    let (merchant, merchant_account) =
-        register_merchant_with_account(&f.env, &f.client, &f.token);
+    let (merchant, merchant_account) = register_merchant_with_account(&f.env, &f.client, &f.token);
 
     let buyer1 = Address::generate(&f.env);
     let buyer2 = Address::generate(&f.env);
@@ -373,6 +375,44 @@ fn resale_with_zero_royalty_pays_seller_in_full() {
     assert_eq!(token_client.balance(&buyer1), buyer1_before + resale_price);
 }
 
+#[test]
+fn resale_is_authorised_by_buyer() {
+    let f = setup();
+    let (merchant, _) = register_merchant_with_account(&f.env, &f.client, &f.token);
+
+    let buyer1 = Address::generate(&f.env);
+    let buyer2 = Address::generate(&f.env);
+    fund(&f.env, &f.token, &buyer1, TOKEN_INITIAL_BALANCE);
+    fund(&f.env, &f.token, &buyer2, TOKEN_INITIAL_BALANCE);
+
+    let event_id = f.client.create_event(
+        &merchant,
+        &String::from_str(&f.env, "X"),
+        &100i128,
+        &f.token,
+        &5u32,
+        &future_date(&f.env),
+        &500u32,
+    );
+    let ticket_id = f.client.purchase_ticket(&event_id, &buyer1);
+    f.client
+        .resell_ticket(&buyer1, &buyer2, &ticket_id, &200i128);
+
+    // The buyer signs the resale itself, which covers the payment it makes.
+    let auths = f.env.auths();
+    let (_, invocation) = auths
+        .iter()
+        .find(|(address, _)| *address == buyer2)
+        .unwrap();
+    match &invocation.function {
+        AuthorizedFunction::Contract((contract, function, _)) => {
+            assert_eq!(*contract, f.client.address);
+            assert_eq!(*function, Symbol::new(&f.env, "resell_ticket"));
+        }
+        _ => panic!("buyer did not authorise the resale"),
+    }
+}
+
 #[test]
 #[should_panic(expected = "Error(Contract, #52)")] // NotTicketOwner
 fn resale_rejects_non_owner_seller() {
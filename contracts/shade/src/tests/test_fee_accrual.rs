@@ -0,0 +1,273 @@
+#![cfg(test)]
+
+use crate::shade::{Shade, ShadeClient};
+use crate::types::{InvoiceOptions, ReferralConfig, TreasurySplit};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{vec, Address, Env, String, Vec};
+
+struct AccrualContext<'a> {
+    env: Env,
+    client: ShadeClient<'a>,
+    admin: Address,
+    token: Address,
+    platform: Address,
+    operations: Address,
+    reserve: Address,
+    merchant: Address,
+    payer: Address,
+}
+
+/// 10% fee with accrual on, swept 70/30 to an operations and a reserve
+/// treasury.
+fn setup() -> AccrualContext<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let shade_id = env.register(Shade, ());
+    let client = ShadeClient::new(&env, &shade_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    client.add_accepted_token(&admin, &token);
+    client.set_fee(&admin, &token, &1000);
+    let platform = Address::generate(&env);
+    client.set_platform_account(&admin, &platform);
+
+    let operations = Address::generate(&env);
+    let reserve = Address::generate(&env);
+    client.set_fee_accrual(&admin, &true);
+    client.set_treasury_splits(
+        &admin,
+        &vec![
+            &env,
+            TreasurySplit {
+                account: operations.clone(),
+                share_bps: 7_000,
+            },
+            TreasurySplit {
+                account: reserve.clone(),
+                share_bps: 3_000,
+            },
+        ],
+    );
+
+    let merchant = Address::generate(&env);
+    client.register_merchant(&merchant, &None);
+    client.set_merchant_account(&merchant, &Address::generate(&env));
+
+    let payer = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&payer, &1_000_000);
+
+    AccrualContext {
+        env,
+        client,
+        admin,
+        token,
+        platform,
+        operations,
+        reserve,
+        merchant,
+        payer,
+    }
+}
+
+fn pay(ctx: &AccrualContext, merchant: &Address, amount: i128) {
+    let invoice_id = ctx.client.create_invoice(
+        merchant,
+        &String::from_str(&ctx.env, "Accrual invoice"),
+        &amount,
+        &ctx.token,
+        &InvoiceOptions::default(),
+    );
+    ctx.client
+        .pay_invoice(&ctx.payer, &invoice_id, &None, &None, &None);
+}
+
+fn balance(ctx: &AccrualContext, account: &Address) -> i128 {
+    TokenClient::new(&ctx.env, &ctx.token).balance(account)
+}
+
+#[test]
+fn test_fees_accrue_in_contract() {
+    let ctx = setup();
+    assert!(ctx.client.is_fee_accrual_enabled());
+
+    pay(&ctx, &ctx.merchant, 1_000);
+    pay(&ctx, &ctx.merchant, 2_000);
+
+    assert_eq!(ctx.client.get_accrued_fees(&ctx.token), 300);
+    assert_eq!(balance(&ctx, &ctx.client.address), 300);
+    assert_eq!(balance(&ctx, &ctx.platform), 0);
+}
+
+#[test]
+fn test_sweep_pays_treasuries_by_share() {
+    let ctx = setup();
+    pay(&ctx, &ctx.merchant, 1_000);
+    pay(&ctx, &ctx.merchant, 330);
+
+    let swept = ctx.client.sweep_fees(&ctx.admin, &ctx.token);
+
+    // 133 accrued: 70% rounds down to 93 and the reserve takes the rest.
+    assert_eq!(swept, 133);
+    assert_eq!(balance(&ctx, &ctx.operations), 93);
+    assert_eq!(balance(&ctx, &ctx.reserve), 40);
+    assert_eq!(balance(&ctx, &ctx.client.address), 0);
+    assert_eq!(ctx.client.get_accrued_fees(&ctx.token), 0);
+}
+
+#[test]
+fn test_fees_go_to_platform_when_accrual_off() {
+    let ctx = setup();
+    ctx.client.set_fee_accrual(&ctx.admin, &false);
+
+    pay(&ctx, &ctx.merchant, 1_000);
+
+    assert_eq!(balance(&ctx, &ctx.platform), 100);
+    assert_eq!(ctx.client.get_accrued_fees(&ctx.token), 0);
+}
+
+#[test]
+fn test_referral_share_is_paid_out_of_accrued_fee() {
+    let ctx = setup();
+    ctx.client.set_referral_config(
+        &ctx.admin,
+        &ReferralConfig {
+            share_bps: 2_000,
+            duration: 86_400,
+        },
+    );
+    let referrer = Address::generate(&ctx.env);
+    let merchant = Address::generate(&ctx.env);
+    ctx.client
+        .register_merchant(&merchant, &Some(referrer.clone()));
+    ctx.client
+        .set_merchant_account(&merchant, &Address::generate(&ctx.env));
+
+    pay(&ctx, &merchant, 1_000);
+
+    assert_eq!(balance(&ctx, &referrer), 20);
+    assert_eq!(ctx.client.get_accrued_fees(&ctx.token), 80);
+}
+
+#[test]
+fn test_payer_makes_one_gross_transfer_into_contract() {
+    let ctx = setup();
+    let invoice_id = ctx.client.create_invoice(
+        &ctx.merchant,
+        &String::from_str(&ctx.env, "Accrual invoice"),
+        &1_000,
+        &ctx.token,
+        &InvoiceOptions::default(),
+    );
+
+    ctx.client
+        .pay_invoice(&ctx.payer, &invoice_id, &None, &None, &None);
+
+    let auths = ctx.env.auths();
+    let (_, invocation) = auths
+        .iter()
+        .find(|(address, _)| *address == ctx.payer)
+        .unwrap();
+    assert_eq!(invocation.sub_invocations.len(), 1);
+    assert_eq!(balance(&ctx, &ctx.payer), 999_000);
+    assert_eq!(balance(&ctx, &ctx.client.address), 100);
+}
+
+#[test]
+fn test_tip_fee_accrues_with_payment() {
+    let ctx = setup();
+    let invoice_id = ctx.client.create_invoice(
+        &ctx.merchant,
+        &String::from_str(&ctx.env, "Accrual invoice"),
+        &1_000,
+        &ctx.token,
+        &InvoiceOptions::default(),
+    );
+
+    ctx.client
+        .pay_invoice_with_tip(&ctx.payer, &invoice_id, &None, &200, &None);
+
+    assert_eq!(ctx.client.get_accrued_fees(&ctx.token), 120);
+    assert_eq!(balance(&ctx, &ctx.client.address), 120);
+    assert_eq!(balance(&ctx, &ctx.payer), 998_800);
+}
+
+#[test]
+fn test_subscription_and_ticket_fees_accrue() {
+    let ctx = setup();
+    let plan_id = ctx.client.create_subscription_plan(
+        &ctx.merchant,
+        &String::from_str(&ctx.env, "Monthly"),
+        &ctx.token,
+        &1_000,
+        &86_400,
+    );
+    TokenClient::new(&ctx.env, &ctx.token).approve(
+        &ctx.payer,
+        &ctx.client.address,
+        &10_000,
+        &1_000_000,
+    );
+    let sub_id = ctx.client.subscribe(&ctx.payer, &plan_id);
+    ctx.client.charge_subscription(&sub_id);
+
+    let event_id = ctx.client.create_event(
+        &ctx.merchant,
+        &String::from_str(&ctx.env, "Launch party"),
+        &500,
+        &ctx.token,
+        &100,
+        &86_400,
+        &0,
+    );
+    ctx.client.purchase_ticket(&event_id, &ctx.payer);
+
+    assert_eq!(ctx.client.get_accrued_fees(&ctx.token), 150);
+    assert_eq!(balance(&ctx, &ctx.client.address), 150);
+    assert_eq!(balance(&ctx, &ctx.platform), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #207)")]
+fn test_sweep_with_nothing_accrued_fails() {
+    let ctx = setup();
+    ctx.client.sweep_fees(&ctx.admin, &ctx.token);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #206)")]
+fn test_treasury_shares_must_total_full_amount() {
+    let ctx = setup();
+    ctx.client.set_treasury_splits(
+        &ctx.admin,
+        &vec![
+            &ctx.env,
+            TreasurySplit {
+                account: ctx.operations.clone(),
+                share_bps: 6_000,
+            },
+        ],
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #206)")]
+fn test_empty_treasury_splits_rejected() {
+    let ctx = setup();
+    ctx.client
+        .set_treasury_splits(&ctx.admin, &Vec::new(&ctx.env));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_non_admin_cannot_sweep() {
+    let ctx = setup();
+    pay(&ctx, &ctx.merchant, 1_000);
+    ctx.client.sweep_fees(&ctx.merchant, &ctx.token);
+}
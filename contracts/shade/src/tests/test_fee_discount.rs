@@ -29,7 +29,7 @@ fn test_no_discount_below_threshold() {
     let env = Env::default();
     let (_admin, client, _contract_id, token) = setup(&env);
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let fee = client.calculate_fee(&merchant, &token, &10_000_000);
     let expected = 10_000_000i128 * 500 / 10_000;
@@ -126,7 +126,7 @@ fn test_zero_fee_returns_zero() {
     client.add_accepted_token(&admin, &token);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     assert_eq!(client.calculate_fee(&merchant, &token, &10_000_000), 0);
 }
@@ -1,9 +1,32 @@
 #![cfg(test)]
 
+use crate::components::admin::FEE_UPDATE_DELAY;
 use crate::shade::Shade;
 use crate::shade::ShadeClient;
-use soroban_sdk::testutils::Address as _;
-use soroban_sdk::{Address, Env, String};
+use crate::types::{InvoiceOptions, VolumeDiscount};
+use soroban_sdk::testutils::{Address as _, Ledger as _};
+use soroban_sdk::{vec, Address, Env, String, Vec};
+
+fn tier(min_volume: i128, discount_bps: i128) -> VolumeDiscount {
+    VolumeDiscount {
+        min_volume,
+        discount_bps,
+    }
+}
+
+/// Proposes `tiers` for `token` and executes them once the delay has passed.
+fn set_volume_discounts(
+    env: &Env,
+    client: &ShadeClient,
+    admin: &Address,
+    token: &Address,
+    tiers: &Vec<VolumeDiscount>,
+) {
+    client.propose_volume_discounts(admin, token, tiers);
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + FEE_UPDATE_DELAY);
+    client.execute_volume_discounts(admin, token);
+}
 
 fn setup(env: &Env) -> (Address, ShadeClient<'_>, Address, Address, Address) {
     env.mock_all_auths();
@@ -20,8 +43,11 @@ fn setup(env: &Env) -> (Address, ShadeClient<'_>, Address, Address, Address) {
     client.add_accepted_token(&admin, &token);
     client.set_fee(&admin, &token, &1000); // 10% base fee in bps
 
+    // No tiers are set, so the default 10%/25%/50% discounts from 10k, 50k
+    // and 200k volume apply.
+
     let merchant = Address::generate(env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
     client.verify_merchant(&admin, &1, &true);
 
     // Set a distinct merchant account address (a plain wallet), same pattern as test_payment tests.
@@ -49,9 +75,9 @@ fn test_fee_discounts_based_on_volume() {
         &String::from_str(&env, "inv1"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
-    client.pay_invoice(&payer, &inv1);
+    client.pay_invoice(&payer, &inv1, &None, &None, &None);
 
     // Volume 1,000 -> Tier 0. Fee 10% (900). Merchant receives 8,100. Volume becomes 10,000.
     let inv2 = client.create_invoice(
@@ -59,9 +85,9 @@ fn test_fee_discounts_based_on_volume() {
         &String::from_str(&env, "inv2"),
         &9000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
-    client.pay_invoice(&payer, &inv2);
+    client.pay_invoice(&payer, &inv2, &None, &None, &None);
 
     // Volume 10,000 -> Tier 1. Discount 10% -> Fee 9% (90). Merchant receives 910. Volume becomes 11,000.
     let inv3 = client.create_invoice(
@@ -69,9 +95,9 @@ fn test_fee_discounts_based_on_volume() {
         &String::from_str(&env, "inv3"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
-    client.pay_invoice(&payer, &inv3);
+    client.pay_invoice(&payer, &inv3, &None, &None, &None);
 
     // 900 + 8100 + 910 = 9910.
     assert_eq!(token_balance_client.balance(&merchant_account), 9910);
@@ -82,9 +108,9 @@ fn test_fee_discounts_based_on_volume() {
         &String::from_str(&env, "inv4"),
         &39000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
-    client.pay_invoice(&payer, &inv4);
+    client.pay_invoice(&payer, &inv4, &None, &None, &None);
 
     // Volume 50,000 -> Tier 2. Discount 25% -> Fee 7.5% (75). Merchant receives 925. Volume becomes 51,000.
     let inv5 = client.create_invoice(
@@ -92,9 +118,9 @@ fn test_fee_discounts_based_on_volume() {
         &String::from_str(&env, "inv5"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
-    client.pay_invoice(&payer, &inv5);
+    client.pay_invoice(&payer, &inv5, &None, &None, &None);
 
     // 9910 + 35490 + 925 = 46325.
     assert_eq!(token_balance_client.balance(&merchant_account), 46325);
@@ -117,9 +143,9 @@ fn test_volume_tracking_tier_3() {
         &String::from_str(&env, "inv_tier3"),
         &200000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
-    client.pay_invoice(&payer, &inv1);
+    client.pay_invoice(&payer, &inv1, &None, &None, &None);
 
     // Volume 200,000 -> Tier 3. Discount 50% -> Fee 5% (50). Merchant receives 950.
     let inv2 = client.create_invoice(
@@ -127,10 +153,96 @@ fn test_volume_tracking_tier_3() {
         &String::from_str(&env, "inv_tier3_next"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
-    client.pay_invoice(&payer, &inv2);
+    client.pay_invoice(&payer, &inv2, &None, &None, &None);
 
     // 180,000 + 950 = 180,950.
     assert_eq!(token_balance_client.balance(&merchant_account), 180950);
 }
+
+#[test]
+fn test_volume_discounts_are_per_token() {
+    let env = Env::default();
+    let (admin, client, token, merchant, _) = setup(&env);
+    let other_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_accepted_token(&admin, &other_token);
+    client.set_fee(&admin, &other_token, &1000);
+    // A token with more decimals needs proportionally larger thresholds.
+    let tiers = vec![&env, tier(10_000_000, 2_000)];
+    set_volume_discounts(&env, &client, &admin, &other_token, &tiers);
+
+    assert_eq!(client.get_volume_discounts(&other_token), tiers);
+    assert_eq!(client.get_volume_discounts(&token).len(), 3);
+    assert_eq!(client.calculate_fee(&merchant, &other_token, &1_000), 100);
+}
+
+#[test]
+fn test_tokens_without_tiers_use_default_discounts() {
+    let env = Env::default();
+    let (_admin, client, token, _, _) = setup(&env);
+    assert_eq!(
+        client.get_volume_discounts(&token),
+        vec![
+            &env,
+            tier(10_000, 1_000),
+            tier(50_000, 2_500),
+            tier(200_000, 5_000),
+        ]
+    );
+}
+
+#[test]
+fn test_proposed_volume_discounts_wait_for_delay() {
+    let env = Env::default();
+    let (admin, client, token, _, _) = setup(&env);
+    let tiers = vec![&env, tier(1, 9_000)];
+    client.propose_volume_discounts(&admin, &token, &tiers);
+
+    assert_eq!(client.get_pending_volume_discounts(&token).tiers, tiers);
+    assert_eq!(client.get_volume_discounts(&token).len(), 3);
+    assert_eq!(
+        client.try_execute_volume_discounts(&admin, &token),
+        Err(Ok(crate::errors::ContractError::FeeUpdateTooEarly.into()))
+    );
+}
+
+#[test]
+fn test_empty_volume_discounts_remove_tiers() {
+    let env = Env::default();
+    let (admin, client, token, merchant, _) = setup(&env);
+    let payer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&payer, &200_000);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "volume"),
+        &200_000,
+        &token,
+        &InvoiceOptions::default(),
+    );
+    client.pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    assert_eq!(client.calculate_fee(&merchant, &token, &1_000), 50);
+
+    set_volume_discounts(&env, &client, &admin, &token, &Vec::new(&env));
+    assert_eq!(client.calculate_fee(&merchant, &token, &1_000), 100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #200)")]
+fn test_volume_discounts_must_be_ascending() {
+    let env = Env::default();
+    let (admin, client, token, _, _) = setup(&env);
+    let tiers = vec![&env, tier(50_000, 2_500), tier(10_000, 1_000)];
+    client.propose_volume_discounts(&admin, &token, &tiers);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #200)")]
+fn test_volume_discount_capped_at_full_fee() {
+    let env = Env::default();
+    let (admin, client, token, _, _) = setup(&env);
+    let tiers = vec![&env, tier(10_000, 10_001)];
+    client.propose_volume_discounts(&admin, &token, &tiers);
+}
@@ -0,0 +1,217 @@
+#![cfg(test)]
+
+use crate::components::admin::FEE_UPDATE_DELAY;
+use crate::tests::common::ShadeFixture;
+use crate::types::{FeeOverridePriority, FeeSchedule, MerchantFeeOverride, Role, VolumeDiscount};
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+use soroban_sdk::{vec, Address, Map, Symbol, TryIntoVal, Val};
+
+/// A 10% token fee on the shared fixture.
+fn setup_test() -> ShadeFixture {
+    ShadeFixture::setup().with_fee(1_000)
+}
+
+fn bps_override(
+    fee_bps: i128,
+    priority: FeeOverridePriority,
+    effective_from: u64,
+    expires_at: Option<u64>,
+) -> MerchantFeeOverride {
+    MerchantFeeOverride {
+        schedule: FeeSchedule {
+            flat_fee: 0,
+            fee_bps,
+            min_fee: 0,
+            max_fee: 0,
+        },
+        priority,
+        effective_from,
+        expires_at,
+    }
+}
+
+/// 50% off the token fee once the merchant has any volume.
+fn set_half_off_volume_discount(s: &ShadeFixture) {
+    let tiers = vec![
+        &s.env,
+        VolumeDiscount {
+            min_volume: 1,
+            discount_bps: 5_000,
+        },
+    ];
+    s.client
+        .propose_volume_discounts(&s.admin, &s.token, &tiers);
+    s.env
+        .ledger()
+        .set_timestamp(s.env.ledger().timestamp() + FEE_UPDATE_DELAY);
+    s.client.execute_volume_discounts(&s.admin, &s.token);
+}
+
+fn pay_new_invoice(s: &ShadeFixture, amount: i128) -> u64 {
+    let invoice_id = s.create_invoice(amount);
+    let payer = s.funded_payer(amount);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    invoice_id
+}
+
+fn fee_discount_events(s: &ShadeFixture) -> u32 {
+    let name = Symbol::new(&s.env, "fee_discount_applied_event");
+    let mut count = 0;
+    for (contract_id, topics, _) in s.env.events().all().iter() {
+        let topic: Symbol = topics.get(0).unwrap().try_into_val(&s.env).unwrap();
+        if contract_id == s.contract_id && topic == name {
+            count += 1;
+        }
+    }
+    count
+}
+
+#[test]
+fn test_override_replaces_token_fee() {
+    let s = setup_test();
+    s.client.set_merchant_fee_override(
+        &s.admin,
+        &s.merchant,
+        &s.token,
+        &bps_override(200, FeeOverridePriority::OverrideOnly, 0, None),
+    );
+
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &10_000), 200);
+    let other = Address::generate(&s.env);
+    assert_eq!(s.client.calculate_fee(&other, &s.token, &10_000), 1_000);
+}
+
+#[test]
+fn test_override_applies_within_its_window() {
+    let s = setup_test();
+    s.client.set_merchant_fee_override(
+        &s.admin,
+        &s.merchant,
+        &s.token,
+        &bps_override(200, FeeOverridePriority::OverrideOnly, 100, Some(200)),
+    );
+
+    assert_eq!(
+        s.client.calculate_fee(&s.merchant, &s.token, &10_000),
+        1_000
+    );
+    s.env.ledger().set_timestamp(100);
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &10_000), 200);
+    s.env.ledger().set_timestamp(200);
+    assert_eq!(
+        s.client.calculate_fee(&s.merchant, &s.token, &10_000),
+        1_000
+    );
+}
+
+#[test]
+fn test_override_priority_against_volume_discounts() {
+    let s = setup_test();
+    set_half_off_volume_discount(&s);
+    pay_new_invoice(&s, 1_000);
+    // Token fee with the volume discount is now 5%.
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &10_000), 500);
+
+    let mut fee_override = bps_override(800, FeeOverridePriority::OverrideOnly, 0, None);
+    s.client
+        .set_merchant_fee_override(&s.admin, &s.merchant, &s.token, &fee_override);
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &10_000), 800);
+
+    fee_override.priority = FeeOverridePriority::StackVolumeDiscount;
+    s.client
+        .set_merchant_fee_override(&s.admin, &s.merchant, &s.token, &fee_override);
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &10_000), 400);
+
+    fee_override.priority = FeeOverridePriority::LowestFee;
+    s.client
+        .set_merchant_fee_override(&s.admin, &s.merchant, &s.token, &fee_override);
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &10_000), 500);
+}
+
+#[test]
+fn test_charge_with_override_emits_fee_discount_event() {
+    let s = setup_test();
+    pay_new_invoice(&s, 1_000);
+    assert_eq!(fee_discount_events(&s), 0);
+
+    s.client.set_merchant_fee_override(
+        &s.admin,
+        &s.merchant,
+        &s.token,
+        &bps_override(200, FeeOverridePriority::OverrideOnly, 0, None),
+    );
+    let invoice_id = pay_new_invoice(&s, 1_000);
+
+    let events = s.env.events().all();
+    let (_, _, data) = events
+        .iter()
+        .find(|(_, topics, _)| {
+            let topic: Symbol = topics.get(0).unwrap().try_into_val(&s.env).unwrap();
+            topic == Symbol::new(&s.env, "fee_discount_applied_event")
+        })
+        .unwrap();
+    let data: Map<Symbol, Val> = data.try_into_val(&s.env).unwrap();
+    let standard_fee: i128 = data
+        .get(Symbol::new(&s.env, "standard_fee"))
+        .unwrap()
+        .try_into_val(&s.env)
+        .unwrap();
+    let fee: i128 = data
+        .get(Symbol::new(&s.env, "fee"))
+        .unwrap()
+        .try_into_val(&s.env)
+        .unwrap();
+    assert_eq!((standard_fee, fee), (100, 20));
+    assert_eq!(s.client.get_invoice(&invoice_id).fee_amount, 20);
+}
+
+#[test]
+fn test_manager_can_set_and_remove_override() {
+    let s = setup_test();
+    let manager = Address::generate(&s.env);
+    s.client.grant_role(&s.admin, &manager, &Role::Manager);
+    let fee_override = bps_override(200, FeeOverridePriority::OverrideOnly, 0, None);
+
+    s.client
+        .set_merchant_fee_override(&manager, &s.merchant, &s.token, &fee_override);
+    assert_eq!(
+        s.client.get_merchant_fee_override(&s.merchant, &s.token),
+        Some(fee_override)
+    );
+
+    s.client
+        .remove_merchant_fee_override(&manager, &s.merchant, &s.token);
+    assert_eq!(
+        s.client.get_merchant_fee_override(&s.merchant, &s.token),
+        None
+    );
+    assert_eq!(
+        s.client.calculate_fee(&s.merchant, &s.token, &10_000),
+        1_000
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_merchant_cannot_set_own_override() {
+    let s = setup_test();
+    s.client.set_merchant_fee_override(
+        &s.merchant,
+        &s.merchant,
+        &s.token,
+        &bps_override(0, FeeOverridePriority::OverrideOnly, 0, None),
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #202)")]
+fn test_override_must_expire_after_it_starts() {
+    let s = setup_test();
+    s.client.set_merchant_fee_override(
+        &s.admin,
+        &s.merchant,
+        &s.token,
+        &bps_override(200, FeeOverridePriority::OverrideOnly, 100, Some(100)),
+    );
+}
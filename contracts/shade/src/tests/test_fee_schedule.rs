@@ -0,0 +1,123 @@
+#![cfg(test)]
+
+use crate::components::admin::FEE_UPDATE_DELAY;
+use crate::tests::common::ShadeFixture;
+use crate::types::{DataKey, FeeSchedule};
+use soroban_sdk::testutils::Ledger as _;
+
+fn schedule(flat_fee: i128, fee_bps: i128, min_fee: i128, max_fee: i128) -> FeeSchedule {
+    FeeSchedule {
+        flat_fee,
+        fee_bps,
+        min_fee,
+        max_fee,
+    }
+}
+
+/// Proposes `fee_schedule` and executes it once the delay has passed.
+fn set_schedule(s: &ShadeFixture, fee_schedule: &FeeSchedule) {
+    s.client
+        .propose_fee_schedule(&s.admin, &s.token, fee_schedule);
+    s.env
+        .ledger()
+        .set_timestamp(s.env.ledger().timestamp() + FEE_UPDATE_DELAY);
+    s.client.execute_fee(&s.admin, &s.token);
+}
+
+#[test]
+fn test_flat_plus_percentage_fee() {
+    let s = ShadeFixture::setup();
+    set_schedule(&s, &schedule(30, 250, 0, 0));
+
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &10_000), 280);
+    assert_eq!(s.client.get_fee(&s.token), 250);
+}
+
+#[test]
+fn test_fee_raised_to_minimum_and_capped_at_maximum() {
+    let s = ShadeFixture::setup();
+    set_schedule(&s, &schedule(0, 100, 50, 500));
+
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &1_000), 50);
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &20_000), 200);
+    assert_eq!(
+        s.client.calculate_fee(&s.merchant, &s.token, &1_000_000),
+        500
+    );
+}
+
+#[test]
+fn test_fee_never_exceeds_amount() {
+    let s = ShadeFixture::setup();
+    set_schedule(&s, &schedule(100, 0, 0, 0));
+
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &40), 40);
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &0), 0);
+}
+
+#[test]
+fn test_fee_schedule_waits_for_execution() {
+    let s = ShadeFixture::setup();
+    let proposed = schedule(10, 300, 20, 1_000);
+    s.client.propose_fee_schedule(&s.admin, &s.token, &proposed);
+
+    assert_eq!(s.client.get_pending_fee(&s.token).schedule, proposed);
+    assert_eq!(s.client.get_fee_schedule(&s.token), schedule(0, 0, 0, 0));
+
+    s.env.ledger().set_timestamp(FEE_UPDATE_DELAY);
+    s.client.execute_fee(&s.admin, &s.token);
+    assert_eq!(s.client.get_fee_schedule(&s.token), proposed);
+}
+
+#[test]
+fn test_bps_fee_replaces_schedule() {
+    let s = ShadeFixture::setup();
+    set_schedule(&s, &schedule(10, 300, 20, 1_000));
+    s.client.set_fee(&s.admin, &s.token, &400);
+
+    assert_eq!(s.client.get_fee_schedule(&s.token), schedule(0, 400, 0, 0));
+}
+
+#[test]
+fn test_rate_stored_before_schedules_still_applies() {
+    let s = ShadeFixture::setup();
+    s.env.as_contract(&s.contract_id, || {
+        s.env
+            .storage()
+            .persistent()
+            .set(&DataKey::TokenFee(s.token.clone()), &700_i128);
+    });
+
+    assert_eq!(s.client.get_fee_schedule(&s.token), schedule(0, 700, 0, 0));
+    assert_eq!(s.client.calculate_fee(&s.merchant, &s.token, &1_000), 70);
+}
+
+#[test]
+fn test_invoice_payment_charges_schedule() {
+    let s = ShadeFixture::setup();
+    set_schedule(&s, &schedule(25, 100, 0, 0));
+
+    let invoice_id = s.create_invoice(1_000);
+    let payer = s.funded_payer(1_000);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+
+    assert_eq!(s.balance(&s.platform), 35);
+    assert_eq!(s.balance(&s.merchant_account), 965);
+    assert_eq!(s.client.get_invoice(&invoice_id).fee_amount, 35);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #201)")]
+fn test_maximum_below_minimum_rejected() {
+    let s = ShadeFixture::setup();
+    s.client
+        .propose_fee_schedule(&s.admin, &s.token, &schedule(0, 100, 500, 100));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #201)")]
+fn test_rate_above_full_amount_rejected() {
+    let s = ShadeFixture::setup();
+    s.client.set_fee(&s.admin, &s.token, &10_001);
+}
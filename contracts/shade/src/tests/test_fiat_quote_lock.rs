@@ -0,0 +1,182 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::tests::test_fiat_pricing::{MockOracle, MockOracleClient};
+use crate::types::{FiatQuote, InvoiceDataKey, InvoiceOptions, InvoiceStatus, OracleConfig};
+use soroban_sdk::testutils::storage::Temporary as _;
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+use soroban_sdk::{Address, Map, String, Symbol, TryIntoVal, Val};
+
+// $10.00 invoice; at $2.00 per token it costs 5 tokens (7 decimals).
+const AT_TWO_DOLLARS: i128 = 50_000_000;
+const AT_FOUR_DOLLARS: i128 = 25_000_000;
+
+/// The shared fixture with the token priced at $2.00 by a mock oracle.
+fn setup_test() -> (ShadeFixture, MockOracleClient<'static>) {
+    let s = ShadeFixture::setup();
+    s.env.ledger().set_timestamp(1_000);
+    let oracle_id = s.env.register(MockOracle, ());
+    let oracle = MockOracleClient::new(&s.env, &oracle_id);
+    oracle.set_price(&200_000_000);
+    s.client.set_token_oracle(
+        &s.admin,
+        &s.token,
+        &OracleConfig {
+            contract: oracle_id,
+            price_decimals: 8,
+            token_decimals: 7,
+        },
+    );
+    (s, oracle)
+}
+
+fn create_fiat_invoice(s: &ShadeFixture) -> u64 {
+    s.client.create_fiat_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Fiat invoice"),
+        &1_000,
+        &String::from_str(&s.env, "USD"),
+        &2,
+        &s.token,
+        &InvoiceOptions::default(),
+    )
+}
+
+#[test]
+fn test_locked_quote_survives_price_move() {
+    let (s, oracle) = setup_test();
+    let invoice_id = create_fiat_invoice(&s);
+    let payer = s.funded_payer(AT_TWO_DOLLARS);
+
+    assert_eq!(
+        s.client.lock_fiat_quote(&payer, &invoice_id, &None),
+        AT_TWO_DOLLARS
+    );
+
+    oracle.set_price(&400_000_000);
+    s.env.ledger().set_timestamp(1_200);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &Some(AT_TWO_DOLLARS), &None, &None);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount_paid, AT_TWO_DOLLARS);
+}
+
+#[test]
+fn test_expired_lock_falls_back_to_spot_price() {
+    let (s, oracle) = setup_test();
+    let invoice_id = create_fiat_invoice(&s);
+    let payer = s.funded_payer(AT_TWO_DOLLARS);
+    s.client.lock_fiat_quote(&payer, &invoice_id, &None);
+
+    oracle.set_price(&400_000_000);
+    s.env.ledger().set_timestamp(1_301);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).amount_paid,
+        AT_FOUR_DOLLARS
+    );
+}
+
+#[test]
+fn test_lock_only_applies_to_its_payer() {
+    let (s, oracle) = setup_test();
+    let invoice_id = create_fiat_invoice(&s);
+    let locker = s.funded_payer(AT_TWO_DOLLARS);
+    s.client.lock_fiat_quote(&locker, &invoice_id, &None);
+
+    oracle.set_price(&400_000_000);
+    let other = s.funded_payer(AT_TWO_DOLLARS);
+    s.client
+        .pay_invoice(&other, &invoice_id, &None, &None, &None);
+
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).amount_paid,
+        AT_FOUR_DOLLARS
+    );
+}
+
+#[test]
+fn test_quote_window_is_configurable() {
+    let (s, oracle) = setup_test();
+    assert_eq!(s.client.get_fiat_quote_window(), 300);
+    s.client.set_fiat_quote_window(&s.admin, &3_600);
+    assert_eq!(s.client.get_fiat_quote_window(), 3_600);
+
+    let invoice_id = create_fiat_invoice(&s);
+    let payer = s.funded_payer(AT_TWO_DOLLARS);
+    s.client.lock_fiat_quote(&payer, &invoice_id, &None);
+
+    oracle.set_price(&400_000_000);
+    s.env.ledger().set_timestamp(4_000);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).amount_paid,
+        AT_TWO_DOLLARS
+    );
+}
+
+#[test]
+fn test_locked_quote_lapses_from_temporary_storage() {
+    let (s, _) = setup_test();
+    s.client.set_fiat_quote_window(&s.admin, &3_600);
+    let invoice_id = create_fiat_invoice(&s);
+    let payer = s.funded_payer(AT_TWO_DOLLARS);
+    s.client.lock_fiat_quote(&payer, &invoice_id, &None);
+
+    s.env.as_contract(&s.client.address, || {
+        let key = InvoiceDataKey::FiatQuote(invoice_id, payer.clone());
+        let quote: FiatQuote = s.env.storage().temporary().get(&key).unwrap();
+        assert_eq!(quote.valid_until, 4_600);
+        // One hour of five-second ledgers.
+        assert_eq!(s.env.storage().temporary().get_ttl(&key), 720);
+        assert!(!s.env.storage().persistent().has(&key));
+    });
+}
+
+#[test]
+fn test_setting_quote_window_emits_event() {
+    let (s, _) = setup_test();
+    s.client.set_fiat_quote_window(&s.admin, &600);
+
+    let event = s.env.events().all().last().unwrap();
+    let data: Map<Symbol, Val> = event.2.try_into_val(&s.env).unwrap();
+    let window: u64 = data
+        .get(Symbol::new(&s.env, "window"))
+        .unwrap()
+        .try_into_val(&s.env)
+        .unwrap();
+    assert_eq!(window, 600);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #109)")]
+fn test_max_amount_rejects_price_rise() {
+    let (s, oracle) = setup_test();
+    let invoice_id = create_fiat_invoice(&s);
+    let payer = s.funded_payer(AT_TWO_DOLLARS * 2);
+
+    oracle.set_price(&100_000_000);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &Some(AT_TWO_DOLLARS), &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #108)")]
+fn test_quote_window_above_one_day_rejected() {
+    let (s, _) = setup_test();
+    s.client.set_fiat_quote_window(&s.admin, &86_401);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_lock_rejected_for_fixed_crypto_invoice() {
+    let (s, _) = setup_test();
+    let invoice_id = s.create_invoice(1_000);
+    let payer = Address::generate(&s.env);
+    s.client.lock_fiat_quote(&payer, &invoice_id, &None);
+}
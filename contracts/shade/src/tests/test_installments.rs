@@ -0,0 +1,177 @@
+#![cfg(test)]
+
+use crate::shade::{Shade, ShadeClient};
+use crate::types::{Installment, InvoiceOptions, InvoiceStatus};
+use soroban_sdk::testutils::{Address as _, Ledger as _};
+use soroban_sdk::{token, vec, Address, Env, String, Vec};
+
+fn setup_test() -> (Env, ShadeClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(Shade, ());
+    let client = ShadeClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_accepted_token(&admin, &token);
+
+    let merchant = Address::generate(&env);
+    client.register_merchant(&merchant, &None);
+    client.set_merchant_account(&merchant, &Address::generate(&env));
+
+    (env, client, merchant, token)
+}
+
+fn create_invoice(env: &Env, client: &ShadeClient<'_>, merchant: &Address, token: &Address) -> u64 {
+    client.create_invoice(
+        merchant,
+        &String::from_str(env, "Equipment lease"),
+        &900,
+        token,
+        &InvoiceOptions::default(),
+    )
+}
+
+fn three_installments(env: &Env) -> Vec<Installment> {
+    vec![
+        env,
+        Installment {
+            due_date: 2_000,
+            amount: 300,
+        },
+        Installment {
+            due_date: 3_000,
+            amount: 300,
+        },
+        Installment {
+            due_date: 4_000,
+            amount: 300,
+        },
+    ]
+}
+
+fn funded_payer(env: &Env, token: &Address, amount: i128) -> Address {
+    let payer = Address::generate(env);
+    token::StellarAssetClient::new(env, token).mint(&payer, &amount);
+    payer
+}
+
+#[test]
+fn test_installments_track_payments() {
+    let (env, client, merchant, token) = setup_test();
+    let invoice_id = create_invoice(&env, &client, &merchant, &token);
+    client.set_invoice_installments(&merchant, &invoice_id, &three_installments(&env));
+
+    let payer = funded_payer(&env, &token, 900);
+    client.pay_invoice_partial(&payer, &invoice_id, &300, &None, &None);
+    assert_eq!(client.get_invoice_installments(&invoice_id).paid_count, 1);
+
+    // One payment may cover several installments at once.
+    client.pay_invoice_partial(&payer, &invoice_id, &600, &None, &None);
+    let plan = client.get_invoice_installments(&invoice_id);
+    assert_eq!(plan.paid_count, 3);
+    assert!(!plan.overdue);
+    assert_eq!(client.get_invoice(&invoice_id).status, InvoiceStatus::Paid);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #105)")]
+fn test_payment_below_next_installment_rejected() {
+    let (env, client, merchant, token) = setup_test();
+    let invoice_id = create_invoice(&env, &client, &merchant, &token);
+    client.set_invoice_installments(&merchant, &invoice_id, &three_installments(&env));
+
+    let payer = funded_payer(&env, &token, 900);
+    client.pay_invoice_partial(&payer, &invoice_id, &200, &None, &None);
+}
+
+#[test]
+fn test_missed_installment_flags_invoice_overdue() {
+    let (env, client, merchant, token) = setup_test();
+    let invoice_id = create_invoice(&env, &client, &merchant, &token);
+    client.set_invoice_installments(&merchant, &invoice_id, &three_installments(&env));
+
+    assert!(!client.check_installments_overdue(&invoice_id));
+
+    env.ledger().set_timestamp(2_500);
+    assert!(client.check_installments_overdue(&invoice_id));
+    assert!(client.get_invoice_installments(&invoice_id).overdue);
+
+    // Catching up on the missed installment clears the flag.
+    let payer = funded_payer(&env, &token, 900);
+    client.pay_invoice_partial(&payer, &invoice_id, &300, &None, &None);
+    assert!(!client.get_invoice_installments(&invoice_id).overdue);
+
+    env.ledger().set_timestamp(3_500);
+    assert!(client.check_installments_overdue(&invoice_id));
+}
+
+#[test]
+fn test_empty_schedule_removes_plan() {
+    let (env, client, merchant, token) = setup_test();
+    let invoice_id = create_invoice(&env, &client, &merchant, &token);
+    client.set_invoice_installments(&merchant, &invoice_id, &three_installments(&env));
+    client.set_invoice_installments(&merchant, &invoice_id, &Vec::new(&env));
+
+    assert!(client.try_get_invoice_installments(&invoice_id).is_err());
+
+    let payer = funded_payer(&env, &token, 900);
+    client.pay_invoice_partial(&payer, &invoice_id, &100, &None, &None);
+    assert_eq!(client.get_invoice(&invoice_id).amount_paid, 100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #104)")]
+fn test_schedule_must_sum_to_invoice_amount() {
+    let (env, client, merchant, token) = setup_test();
+    let invoice_id = create_invoice(&env, &client, &merchant, &token);
+
+    let mut installments = three_installments(&env);
+    installments.pop_back();
+    client.set_invoice_installments(&merchant, &invoice_id, &installments);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #104)")]
+fn test_schedule_due_dates_must_increase() {
+    let (env, client, merchant, token) = setup_test();
+    let invoice_id = create_invoice(&env, &client, &merchant, &token);
+
+    let installments = vec![
+        &env,
+        Installment {
+            due_date: 3_000,
+            amount: 450,
+        },
+        Installment {
+            due_date: 2_000,
+            amount: 450,
+        },
+    ];
+    client.set_invoice_installments(&merchant, &invoice_id, &installments);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_schedule_cannot_change_after_payment() {
+    let (env, client, merchant, token) = setup_test();
+    let invoice_id = create_invoice(&env, &client, &merchant, &token);
+
+    let payer = funded_payer(&env, &token, 900);
+    client.pay_invoice_partial(&payer, &invoice_id, &100, &None, &None);
+    client.set_invoice_installments(&merchant, &invoice_id, &three_installments(&env));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #104)")]
+fn test_amending_invoice_with_plan_rejected() {
+    let (env, client, merchant, token) = setup_test();
+    let invoice_id = create_invoice(&env, &client, &merchant, &token);
+    client.set_invoice_installments(&merchant, &invoice_id, &three_installments(&env));
+
+    client.amend_invoice(&merchant, &invoice_id, &Some(1_200), &None);
+}
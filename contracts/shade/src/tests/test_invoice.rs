@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::{DataKey, InvoiceStatus};
+use crate::types::{DataKey, InvoiceOptions, InvoiceStatus};
 use account::account::{MerchantAccount, MerchantAccountClient};
 use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
 use soroban_sdk::{token, Address, Env, Map, String, Symbol, TryIntoVal, Val};
@@ -86,7 +86,7 @@ fn test_create_and_get_invoice_success() {
     let (env, client, contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
@@ -95,7 +95,13 @@ fn test_create_and_get_invoice_success() {
     let description = String::from_str(&env, "Test Invoice");
     let amount: i128 = 1000;
 
-    let invoice_id = client.create_invoice(&merchant, &description, &amount, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &amount,
+        &token,
+        &InvoiceOptions::default(),
+    );
     assert_eq!(invoice_id, 1);
 
     assert_latest_invoice_event(&env, &contract_id, invoice_id, &merchant, amount, &token);
@@ -115,7 +121,7 @@ fn test_create_multiple_invoices() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token1 = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
@@ -131,21 +137,21 @@ fn test_create_multiple_invoices() {
         &String::from_str(&env, "Invoice 1"),
         &1000,
         &token1,
-        &None,
+        &InvoiceOptions::default(),
     );
     let id2 = client.create_invoice(
         &merchant,
         &String::from_str(&env, "Invoice 2"),
         &2000,
         &token2,
-        &None,
+        &InvoiceOptions::default(),
     );
     let id3 = client.create_invoice(
         &merchant,
         &String::from_str(&env, "Invoice 3"),
         &500,
         &token1,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     assert_eq!(id1, 1);
@@ -173,7 +179,13 @@ fn test_create_invoice_unregistered_merchant() {
     let description = String::from_str(&env, "Test Invoice");
     let amount: i128 = 1000;
 
-    client.create_invoice(&unregistered_merchant, &description, &amount, &token, &None);
+    client.create_invoice(
+        &unregistered_merchant,
+        &description,
+        &amount,
+        &token,
+        &InvoiceOptions::default(),
+    );
 }
 
 #[should_panic(expected = "HostError: Error(Contract, #7)")]
@@ -182,7 +194,7 @@ fn test_create_invoice_invalid_amount() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
@@ -191,21 +203,33 @@ fn test_create_invoice_invalid_amount() {
     let description = String::from_str(&env, "Test Invoice");
     let amount: i128 = 0;
 
-    client.create_invoice(&merchant, &description, &amount, &token, &None);
+    client.create_invoice(
+        &merchant,
+        &description,
+        &amount,
+        &token,
+        &InvoiceOptions::default(),
+    );
 }
 
 #[test]
 fn test_refund_invoice_success_within_window() {
     let (env, client, shade_contract_id, admin) = setup_test();
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_test_token(&env);
     client.add_accepted_token(&admin, &token);
     let payer = Address::generate(&env);
     let description = String::from_str(&env, "Refundable Invoice");
     let amount = 1_000_i128;
-    let invoice_id = client.create_invoice(&merchant, &description, &amount, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &amount,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let merchant_account_id = env.register(MerchantAccount, ());
     let merchant_account = MerchantAccountClient::new(&env, &merchant_account_id);
@@ -243,7 +267,7 @@ fn test_refund_invoice_success_within_window() {
 fn test_refund_invoice_fails_after_refund_window() {
     let (env, client, shade_contract_id, admin) = setup_test();
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_test_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -253,7 +277,7 @@ fn test_refund_invoice_fails_after_refund_window() {
         &String::from_str(&env, "Expired refund"),
         &500_i128,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     let merchant_account_id = env.register(MerchantAccount, ());
@@ -282,14 +306,20 @@ fn test_void_invoice_success() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Verify invoice is Pending
     let invoice_before = client.get_invoice(&invoice_id);
@@ -309,8 +339,8 @@ fn test_refund_invoice_fails_for_non_owner() {
     let (env, client, shade_contract_id, admin) = setup_test();
     let merchant = Address::generate(&env);
     let other_merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
-    client.register_merchant(&other_merchant);
+    client.register_merchant(&merchant, &None);
+    client.register_merchant(&other_merchant, &None);
 
     let token = create_test_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -320,7 +350,7 @@ fn test_refund_invoice_fails_for_non_owner() {
         &String::from_str(&env, "Wrong owner"),
         &250_i128,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     let merchant_account_id = env.register(MerchantAccount, ());
@@ -348,18 +378,24 @@ fn test_void_invoice_non_owner() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Try to void with different merchant (should panic with NotAuthorized)
     let other_merchant = Address::generate(&env);
-    client.register_merchant(&other_merchant);
+    client.register_merchant(&other_merchant, &None);
     client.void_invoice(&other_merchant, &invoice_id);
 }
 
@@ -370,7 +406,7 @@ fn test_void_invoice_already_paid() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -378,13 +414,19 @@ fn test_void_invoice_already_paid() {
 
     // Create and pay invoice
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let customer = Address::generate(&env);
     let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
 
-    client.pay_invoice(&customer, &invoice_id);
+    client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     // Try to void paid invoice (should panic with InvalidInvoiceStatus)
     client.void_invoice(&merchant, &invoice_id);
@@ -396,14 +438,20 @@ fn test_void_invoice_already_cancelled() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Void the invoice once
     client.void_invoice(&merchant, &invoice_id);
@@ -419,7 +467,7 @@ fn test_pay_cancelled_invoice() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -427,7 +475,13 @@ fn test_pay_cancelled_invoice() {
 
     // Create invoice
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Void the invoice
     client.void_invoice(&merchant, &invoice_id);
@@ -437,7 +491,7 @@ fn test_pay_cancelled_invoice() {
     let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
 
-    client.pay_invoice(&customer, &invoice_id);
+    client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 }
 
 #[test]
@@ -446,7 +500,7 @@ fn test_void_non_existent_invoice() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Try to void non-existent invoice (should panic with InvoiceNotFound)
     client.void_invoice(&merchant, &999);
@@ -459,14 +513,20 @@ fn test_amend_invoice_amount_success() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Original Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Amend the amount
     client.amend_invoice(&merchant, &invoice_id, &Some(2000), &None);
@@ -483,14 +543,20 @@ fn test_amend_invoice_description_success() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Original Description");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Amend the description
     let new_description = String::from_str(&env, "Updated Description");
@@ -513,14 +579,20 @@ fn test_amend_invoice_both_fields_success() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Original");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Amend both amount and description
     let new_description = String::from_str(&env, "Updated");
@@ -544,7 +616,7 @@ fn test_amend_invoice_paid_fails() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -552,13 +624,19 @@ fn test_amend_invoice_paid_fails() {
 
     // Create and pay invoice
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let customer = Address::generate(&env);
     let token_client = token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
 
-    client.pay_invoice(&customer, &invoice_id);
+    client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     // Try to amend paid invoice (should panic with InvalidInvoiceStatus)
     let new_description = String::from_str(&env, "Updated");
@@ -571,14 +649,20 @@ fn test_amend_invoice_cancelled_fails() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Void the invoice
     client.void_invoice(&merchant, &invoice_id);
@@ -593,18 +677,24 @@ fn test_amend_invoice_non_owner_fails() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Try to amend with different merchant (should panic with NotAuthorized)
     let other_merchant = Address::generate(&env);
-    client.register_merchant(&other_merchant);
+    client.register_merchant(&other_merchant, &None);
     client.amend_invoice(&other_merchant, &invoice_id, &Some(2000), &None);
 }
 
@@ -614,14 +704,20 @@ fn test_amend_invoice_invalid_amount_fails() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Try to amend with invalid amount (should panic with InvalidAmount)
     client.amend_invoice(&merchant, &invoice_id, &Some(0), &None);
@@ -633,14 +729,20 @@ fn test_amend_invoice_negative_amount_fails() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Try to amend with negative amount (should panic with InvalidAmount)
     client.amend_invoice(&merchant, &invoice_id, &Some(-100), &None);
@@ -652,7 +754,7 @@ fn test_amend_non_existent_invoice_fails() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Try to amend non-existent invoice (should panic with InvoiceNotFound)
     client.amend_invoice(&merchant, &999, &Some(2000), &None);
@@ -1,4 +1,4 @@
-﻿#![cfg(test)]
+#![cfg(test)]
 
 //! Comprehensive tests for the `amend_invoice` function.
 //!
@@ -10,7 +10,7 @@
 //! - Either field can be updated independently or together.
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::{DataKey, InvoiceStatus};
+use crate::types::{DataKey, InvoiceOptions, InvoiceStatus, PaymentTerms};
 use soroban_sdk::testutils::Address as _;
 use soroban_sdk::{Address, Env, String};
 
@@ -42,7 +42,7 @@ fn create_pending_invoice(
     description: &str,
 ) -> (Address, Address, u64) {
     let merchant = Address::generate(env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
     let token = make_token(env, admin);
     client.add_accepted_token(admin, &token);
     let id = client.create_invoice(
@@ -50,7 +50,7 @@ fn create_pending_invoice(
         &String::from_str(env, description),
         &amount,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     (merchant, token, id)
 }
@@ -217,7 +217,7 @@ fn test_amend_invoice_by_wrong_merchant_fails() {
 
     // Register a second merchant and try to amend the first merchant's invoice
     let other_merchant = Address::generate(&env);
-    client.register_merchant(&other_merchant);
+    client.register_merchant(&other_merchant, &None);
 
     client.amend_invoice(&other_merchant, &id, &Some(999), &None);
 }
@@ -296,3 +296,126 @@ fn test_amend_description_exactly_100_chars_succeeds() {
     let invoice = client.get_invoice(&id);
     assert_eq!(invoice.description, desc_100);
 }
+
+// ---------------------------------------------------------------------------
+// Revisions
+// ---------------------------------------------------------------------------
+
+/// Give the merchant an account and return a funded payer.
+fn fund_payment(
+    env: &Env,
+    client: &ShadeClient<'_>,
+    merchant: &Address,
+    token: &Address,
+) -> Address {
+    client.set_merchant_account(merchant, &Address::generate(env));
+    let payer = Address::generate(env);
+    soroban_sdk::token::StellarAssetClient::new(env, token).mint(&payer, &10_000);
+    payer
+}
+
+#[test]
+fn test_amendments_recorded_as_revisions() {
+    let (env, client, _, admin) = setup_env();
+    let (merchant, _, id) = create_pending_invoice(&env, &client, &admin, 500, "Original");
+    assert_eq!(client.get_invoice_revisions(&id).len(), 0);
+
+    client.amend_invoice(&merchant, &id, &Some(800), &None);
+    client.amend_invoice(
+        &merchant,
+        &id,
+        &None,
+        &Some(String::from_str(&env, "Renamed")),
+    );
+
+    assert_eq!(client.get_invoice(&id).revision, 2);
+    let revisions = client.get_invoice_revisions(&id);
+    assert_eq!(revisions.len(), 2);
+
+    let first = revisions.get(0).unwrap();
+    assert_eq!(first.revision, 1);
+    assert_eq!(first.old_amount, 500);
+    assert_eq!(first.new_amount, 800);
+    assert_eq!(first.old_description, String::from_str(&env, "Original"));
+    assert_eq!(first.new_description, String::from_str(&env, "Original"));
+    assert_eq!(first.amended_by, merchant);
+
+    let second = revisions.get(1).unwrap();
+    assert_eq!(second.old_amount, 800);
+    assert_eq!(second.new_amount, 800);
+    assert_eq!(second.new_description, String::from_str(&env, "Renamed"));
+}
+
+#[test]
+fn test_pay_pinned_to_current_revision() {
+    let (env, client, _, admin) = setup_env();
+    let (merchant, token, id) = create_pending_invoice(&env, &client, &admin, 500, "Invoice");
+    let payer = fund_payment(&env, &client, &merchant, &token);
+    client.amend_invoice(&merchant, &id, &Some(800), &None);
+
+    client.pay_invoice(&payer, &id, &None, &None, &Some(1));
+    assert_eq!(client.get_invoice(&id).status, InvoiceStatus::Paid);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #126)")]
+fn test_pay_rejected_after_amendment_front_runs_payer() {
+    let (env, client, _, admin) = setup_env();
+    let (merchant, token, id) = create_pending_invoice(&env, &client, &admin, 500, "Invoice");
+    let payer = fund_payment(&env, &client, &merchant, &token);
+    client.amend_invoice(&merchant, &id, &Some(5_000), &None);
+
+    client.pay_invoice(&payer, &id, &None, &None, &Some(0));
+}
+
+#[test]
+fn test_terms_and_split_changes_recorded_as_revisions() {
+    let (env, client, _, admin) = setup_env();
+    let (merchant, _, id) = create_pending_invoice(&env, &client, &admin, 500, "Invoice");
+
+    client.set_invoice_terms(
+        &merchant,
+        &id,
+        &Some(PaymentTerms {
+            discount_bps: 0,
+            discount_deadline: 0,
+            penalty_bps: 1_000,
+            due_date: 100,
+        }),
+    );
+    client.set_invoice_split_bill(&merchant, &id, &true);
+
+    assert_eq!(client.get_invoice(&id).revision, 2);
+    assert_eq!(client.get_invoice_revisions(&id).len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #126)")]
+fn test_partial_pay_rejected_after_terms_change() {
+    let (env, client, _, admin) = setup_env();
+    let (merchant, token, id) = create_pending_invoice(&env, &client, &admin, 500, "Invoice");
+    let payer = fund_payment(&env, &client, &merchant, &token);
+    client.set_invoice_terms(
+        &merchant,
+        &id,
+        &Some(PaymentTerms {
+            discount_bps: 0,
+            discount_deadline: 0,
+            penalty_bps: 1_000,
+            due_date: 0,
+        }),
+    );
+
+    client.pay_invoice_partial(&payer, &id, &100, &None, &Some(0));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #126)")]
+fn test_tip_payment_rejected_after_amendment() {
+    let (env, client, _, admin) = setup_env();
+    let (merchant, token, id) = create_pending_invoice(&env, &client, &admin, 500, "Invoice");
+    let payer = fund_payment(&env, &client, &merchant, &token);
+    client.amend_invoice(&merchant, &id, &Some(5_000), &None);
+
+    client.pay_invoice_with_tip(&payer, &id, &None, &10, &Some(0));
+}
@@ -0,0 +1,104 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::InvoiceStatus;
+use soroban_sdk::testutils::{Address as _, Events as _};
+use soroban_sdk::{token, Address, Map, String, Symbol, TryIntoVal, Val};
+
+/// A 1_000 invoice with 400 paid under a 5% platform fee, so the merchant
+/// account only holds 380 of the payer's money.
+fn setup_test() -> (ShadeFixture, Address, u64) {
+    let s = ShadeFixture::setup().with_fee(500);
+    let invoice_id = s.create_invoice(1_000);
+    let payer = s.funded_payer(400);
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &400, &None, &None);
+    (s, payer, invoice_id)
+}
+
+#[test]
+fn test_cancel_refunds_what_merchant_received() {
+    let (s, payer, invoice_id) = setup_test();
+    assert_eq!(s.client.get_invoice(&invoice_id).fee_amount, 20);
+
+    s.client.cancel_invoice(&s.merchant, &invoice_id);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Cancelled);
+    assert_eq!(invoice.amount_refunded, 380);
+    assert_eq!(s.balance(&payer), 380);
+    assert_eq!(s.balance(&s.merchant_account), 0);
+}
+
+#[test]
+fn test_cancel_keeps_fee_and_tax_out_of_refund() {
+    let (s, _, _) = setup_test();
+    let code = String::from_str(&s.env, "DE");
+    let tax_account = Address::generate(&s.env);
+    s.client.set_merchant_tax_account(&s.merchant, &tax_account);
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &1_000);
+    let invoice_id = s.create_invoice(1_000);
+    s.client.apply_invoice_tax(&s.merchant, &invoice_id, &code);
+    let payer = s.funded_payer(550);
+    // 50 of the 550 is tax and 5% of the other 500 is the platform fee.
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &550, &None, &None);
+
+    s.client.cancel_invoice(&s.merchant, &invoice_id);
+
+    let event = s.env.events().all().last().unwrap();
+    let data: Map<Symbol, Val> = event.2.try_into_val(&s.env).unwrap();
+    let retained = |name: &str| -> i128 {
+        data.get(Symbol::new(&s.env, name))
+            .unwrap()
+            .try_into_val(&s.env)
+            .unwrap()
+    };
+    assert_eq!(retained("amount_refunded"), 475);
+    assert_eq!(retained("fee_retained"), 25);
+    assert_eq!(retained("tax_retained"), 50);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.tax_paid, 50);
+    assert_eq!(invoice.fee_amount, 25);
+    assert_eq!(invoice.amount_refunded, 475);
+    assert_eq!(s.balance(&payer), 475);
+    assert_eq!(s.balance(&tax_account), 50);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #30)")]
+fn test_cancel_needs_merchant_account_to_cover_refund() {
+    let (s, _, invoice_id) = setup_test();
+    let elsewhere = Address::generate(&s.env);
+    token::TokenClient::new(&s.env, &s.token).transfer(&s.merchant_account, &elsewhere, &1);
+    s.client.cancel_invoice(&s.merchant, &invoice_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_only_owning_merchant_can_cancel() {
+    let (s, _, invoice_id) = setup_test();
+    let other = Address::generate(&s.env);
+    s.client.register_merchant(&other, &None);
+    s.client.cancel_invoice(&other, &invoice_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_cancel_rejects_pending_invoice() {
+    let (s, _, _) = setup_test();
+    let invoice_id = s.create_invoice(1_000);
+    s.client.cancel_invoice(&s.merchant, &invoice_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_cancelled_invoice_cannot_be_paid() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client.cancel_invoice(&s.merchant, &invoice_id);
+
+    s.mint(&s.token, &payer, 600);
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &600, &None, &None);
+}
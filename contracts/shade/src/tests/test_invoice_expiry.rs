@@ -2,8 +2,10 @@
 
 use crate::errors::ContractError;
 use crate::shade::{Shade, ShadeClient};
+use crate::types::{ExpiryRefundPolicy, InvoiceIndex, InvoiceOptions, InvoiceStatus};
+use account::account::{MerchantAccount, MerchantAccountClient};
 use soroban_sdk::testutils::{Address as _, Ledger as _};
-use soroban_sdk::{token, Address, Env, String};
+use soroban_sdk::{token, vec, Address, Env, String};
 
 fn setup_test() -> (Env, ShadeClient<'static>, Address, Address) {
     let env = Env::default();
@@ -32,7 +34,7 @@ fn test_pay_invoice_panics_when_expired() {
     client.add_accepted_token(&admin, &token);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_account = Address::generate(&env);
     client.set_merchant_account(&merchant, &merchant_account);
@@ -44,7 +46,10 @@ fn test_pay_invoice_panics_when_expired() {
         &String::from_str(&env, "Expiring Invoice"),
         &1000,
         &token,
-        &Some(1000u64),
+        &InvoiceOptions {
+            expires_at: Some(1000u64),
+            ..Default::default()
+        },
     );
 
     // mint tokens for payer
@@ -57,7 +62,7 @@ fn test_pay_invoice_panics_when_expired() {
 
     let expected_error =
         soroban_sdk::Error::from_contract_error(ContractError::InvoiceExpired as u32);
-    let result = client.try_pay_invoice(&payer, &invoice_id);
+    let result = client.try_pay_invoice(&payer, &invoice_id, &None, &None, &None);
     assert!(matches!(result, Err(Ok(err)) if err == expected_error));
 }
 
@@ -69,7 +74,7 @@ fn test_pay_invoice_panics_well_past_expiry() {
     client.add_accepted_token(&admin, &token);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_account = Address::generate(&env);
     client.set_merchant_account(&merchant, &merchant_account);
@@ -80,7 +85,10 @@ fn test_pay_invoice_panics_well_past_expiry() {
         &String::from_str(&env, "Long Expired Invoice"),
         &500,
         &token,
-        &Some(200u64),
+        &InvoiceOptions {
+            expires_at: Some(200u64),
+            ..Default::default()
+        },
     );
 
     let payer = Address::generate(&env);
@@ -92,7 +100,7 @@ fn test_pay_invoice_panics_well_past_expiry() {
 
     let expected_error =
         soroban_sdk::Error::from_contract_error(ContractError::InvoiceExpired as u32);
-    let result = client.try_pay_invoice(&payer, &invoice_id);
+    let result = client.try_pay_invoice(&payer, &invoice_id, &None, &None, &None);
     assert!(matches!(result, Err(Ok(err)) if err == expected_error));
 }
 
@@ -109,7 +117,7 @@ fn test_pay_invoice_succeeds_before_expiry() {
     client.set_fee(&admin, &token, &0);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_account = Address::generate(&env);
     client.set_merchant_account(&merchant, &merchant_account);
@@ -120,7 +128,10 @@ fn test_pay_invoice_succeeds_before_expiry() {
         &String::from_str(&env, "Valid Invoice"),
         &1000,
         &token,
-        &Some(2000u64),
+        &InvoiceOptions {
+            expires_at: Some(2000u64),
+            ..Default::default()
+        },
     );
 
     let payer = Address::generate(&env);
@@ -130,7 +141,7 @@ fn test_pay_invoice_succeeds_before_expiry() {
     // still before expiry
     env.ledger().set_timestamp(999);
 
-    client.pay_invoice(&payer, &invoice_id);
+    client.pay_invoice(&payer, &invoice_id, &None, &None, &None);
 
     let invoice = client.get_invoice(&invoice_id);
     assert_eq!(invoice.status, crate::types::InvoiceStatus::Paid);
@@ -149,7 +160,7 @@ fn test_pay_invoice_no_expiry_always_valid() {
     client.set_fee(&admin, &token, &0);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_account = Address::generate(&env);
     client.set_merchant_account(&merchant, &merchant_account);
@@ -160,7 +171,7 @@ fn test_pay_invoice_no_expiry_always_valid() {
         &String::from_str(&env, "No Expiry Invoice"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     let payer = Address::generate(&env);
@@ -170,7 +181,7 @@ fn test_pay_invoice_no_expiry_always_valid() {
     // advance to a very large timestamp — no expiry set, should still succeed
     env.ledger().set_timestamp(u64::MAX / 2);
 
-    client.pay_invoice(&payer, &invoice_id);
+    client.pay_invoice(&payer, &invoice_id, &None, &None, &None);
 
     let invoice = client.get_invoice(&invoice_id);
     assert_eq!(invoice.status, crate::types::InvoiceStatus::Paid);
@@ -188,7 +199,7 @@ fn test_create_invoice_panics_when_expires_at_in_past() {
     client.add_accepted_token(&admin, &token);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     env.ledger().set_timestamp(5000);
 
@@ -200,7 +211,190 @@ fn test_create_invoice_panics_when_expires_at_in_past() {
         &String::from_str(&env, "Already Expired"),
         &100,
         &token,
-        &Some(1000u64),
+        &InvoiceOptions {
+            expires_at: Some(1000u64),
+            ..Default::default()
+        },
     );
     assert!(matches!(result, Err(Ok(err)) if err == expected_error));
 }
+
+// ---------------------------------------------------------------------------
+// Expired status: expire_invoices and the cursor sweep
+// ---------------------------------------------------------------------------
+
+fn setup_merchant(
+    env: &Env,
+    client: &ShadeClient<'_>,
+    contract_id: &Address,
+    admin: &Address,
+) -> (Address, Address, Address) {
+    let token = create_token(env);
+    client.add_accepted_token(admin, &token);
+
+    let merchant = Address::generate(env);
+    client.register_merchant(&merchant, &None);
+
+    let merchant_account = env.register(MerchantAccount, ());
+    MerchantAccountClient::new(env, &merchant_account).initialize(&merchant, contract_id, &1_u64);
+    client.set_merchant_account(&merchant, &merchant_account);
+
+    (merchant, merchant_account, token)
+}
+
+fn create_expiring_invoice(
+    env: &Env,
+    client: &ShadeClient<'_>,
+    merchant: &Address,
+    token: &Address,
+    expires_at: Option<u64>,
+) -> u64 {
+    client.create_invoice(
+        merchant,
+        &String::from_str(env, "Expiring Invoice"),
+        &1000,
+        token,
+        &InvoiceOptions {
+            expires_at,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn test_expire_invoices_moves_only_overdue_invoices() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (merchant, _merchant_account, token) = setup_merchant(&env, &client, &contract_id, &admin);
+
+    env.ledger().set_timestamp(100);
+    let overdue = create_expiring_invoice(&env, &client, &merchant, &token, Some(500));
+    let not_due = create_expiring_invoice(&env, &client, &merchant, &token, Some(5000));
+    let no_expiry = create_expiring_invoice(&env, &client, &merchant, &token, None);
+
+    env.ledger().set_timestamp(1000);
+    let expired = client.expire_invoices(&vec![&env, overdue, not_due, no_expiry, 99]);
+
+    assert_eq!(expired, 1);
+    assert_eq!(client.get_invoice(&overdue).status, InvoiceStatus::Expired);
+    assert_eq!(client.get_invoice(&not_due).status, InvoiceStatus::Pending);
+    assert_eq!(
+        client.get_invoice(&no_expiry).status,
+        InvoiceStatus::Pending
+    );
+
+    let page = client.get_invoices_page(&InvoiceIndex::Status(InvoiceStatus::Expired), &None, &10);
+    assert_eq!(page.invoices.len(), 1);
+    assert_eq!(page.invoices.get(0).unwrap().id, overdue);
+
+    // Already expired invoices are skipped on later calls.
+    assert_eq!(client.expire_invoices(&vec![&env, overdue]), 0);
+}
+
+#[test]
+fn test_sweep_expires_open_invoices_across_pages() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (merchant, _merchant_account, token) = setup_merchant(&env, &client, &contract_id, &admin);
+
+    env.ledger().set_timestamp(100);
+    let mut overdue = soroban_sdk::Vec::new(&env);
+    for _ in 0..4 {
+        overdue.push_back(create_expiring_invoice(
+            &env,
+            &client,
+            &merchant,
+            &token,
+            Some(500),
+        ));
+    }
+    let not_due = create_expiring_invoice(&env, &client, &merchant, &token, Some(5000));
+
+    env.ledger().set_timestamp(1000);
+    let mut cursor = None;
+    let mut sweeps = 0;
+    loop {
+        cursor = client.sweep_expired_invoices(&cursor, &2);
+        sweeps += 1;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(sweeps, 3);
+    for invoice_id in overdue.iter() {
+        assert_eq!(
+            client.get_invoice(&invoice_id).status,
+            InvoiceStatus::Expired
+        );
+    }
+    assert_eq!(client.get_invoice(&not_due).status, InvoiceStatus::Pending);
+}
+
+#[test]
+fn test_expiry_refunds_partial_payment_by_default() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (merchant, merchant_account, token) = setup_merchant(&env, &client, &contract_id, &admin);
+
+    env.ledger().set_timestamp(100);
+    let invoice_id = create_expiring_invoice(&env, &client, &merchant, &token, Some(500));
+
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&payer, &400);
+    client.pay_invoice_partial(&payer, &invoice_id, &400, &None, &None);
+
+    env.ledger().set_timestamp(600);
+    client.expire_invoices(&vec![&env, invoice_id]);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Expired);
+    assert_eq!(invoice.amount_refunded, 400);
+
+    let token_client = token::TokenClient::new(&env, &token);
+    assert_eq!(token_client.balance(&payer), 400);
+    assert_eq!(token_client.balance(&merchant_account), 0);
+}
+
+#[test]
+fn test_expiry_retains_partial_payment_when_merchant_opts_out() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (merchant, merchant_account, token) = setup_merchant(&env, &client, &contract_id, &admin);
+    client.set_merchant_expiry_policy(&merchant, &ExpiryRefundPolicy::RetainPayments);
+    assert_eq!(
+        client.get_merchant_expiry_policy(&1),
+        ExpiryRefundPolicy::RetainPayments
+    );
+
+    env.ledger().set_timestamp(100);
+    let invoice_id = create_expiring_invoice(&env, &client, &merchant, &token, Some(500));
+
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&payer, &400);
+    client.pay_invoice_partial(&payer, &invoice_id, &400, &None, &None);
+
+    env.ledger().set_timestamp(600);
+    client.sweep_expired_invoices(&None, &10);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Expired);
+    assert_eq!(invoice.amount_refunded, 0);
+    assert_eq!(
+        token::TokenClient::new(&env, &token).balance(&merchant_account),
+        400
+    );
+}
+
+#[test]
+fn test_expired_invoice_cannot_be_paid() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (merchant, _merchant_account, token) = setup_merchant(&env, &client, &contract_id, &admin);
+
+    env.ledger().set_timestamp(100);
+    let invoice_id = create_expiring_invoice(&env, &client, &merchant, &token, Some(500));
+    env.ledger().set_timestamp(600);
+    client.expire_invoices(&vec![&env, invoice_id]);
+
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&payer, &1000);
+    assert!(client
+        .try_pay_invoice(&payer, &invoice_id, &None, &None, &None)
+        .is_err());
+}
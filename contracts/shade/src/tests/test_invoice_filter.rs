@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::{InvoiceFilter, InvoiceStatus};
+use crate::types::{InvoiceFilter, InvoiceOptions, InvoiceStatus};
 use soroban_sdk::testutils::{Address as _, Ledger as _};
 use soroban_sdk::{Address, Env, String};
 
@@ -28,7 +28,7 @@ fn setup_invoices_at_timestamps(
     admin: &Address,
 ) -> (Address, Address, [u64; 3]) {
     let merchant = Address::generate(env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_token(env);
     client.add_accepted_token(admin, &token);
@@ -39,7 +39,7 @@ fn setup_invoices_at_timestamps(
         &String::from_str(env, "Invoice 1"),
         &100,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     env.ledger().set_timestamp(2_000);
@@ -48,7 +48,7 @@ fn setup_invoices_at_timestamps(
         &String::from_str(env, "Invoice 2"),
         &200,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     env.ledger().set_timestamp(3_000);
@@ -57,7 +57,7 @@ fn setup_invoices_at_timestamps(
         &String::from_str(env, "Invoice 3"),
         &300,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     (merchant, token, [id1, id2, id3])
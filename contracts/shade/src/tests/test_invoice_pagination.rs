@@ -0,0 +1,260 @@
+#![cfg(test)]
+
+use crate::shade::{Shade, ShadeClient};
+use crate::types::{
+    InvoiceFilter, InvoiceIndex, InvoiceIndexDataKey, InvoiceIndexKey, InvoiceOptions,
+    InvoiceStatus,
+};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, Env, String};
+
+fn setup_test() -> (Env, ShadeClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(Shade, ());
+    let client = ShadeClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_accepted_token(&admin, &token);
+
+    (env, client, admin, token)
+}
+
+fn register_merchant(env: &Env, client: &ShadeClient<'_>) -> Address {
+    let merchant = Address::generate(env);
+    client.register_merchant(&merchant, &None);
+    client.set_merchant_account(&merchant, &Address::generate(env));
+    merchant
+}
+
+fn create_invoice(env: &Env, client: &ShadeClient<'_>, merchant: &Address, token: &Address) -> u64 {
+    client.create_invoice(
+        merchant,
+        &String::from_str(env, "Paged invoice"),
+        &1_000,
+        token,
+        &InvoiceOptions::default(),
+    )
+}
+
+#[test]
+fn test_merchant_index_pages_with_cursor() {
+    let (env, client, _admin, token) = setup_test();
+    let merchant_a = register_merchant(&env, &client);
+    let merchant_b = register_merchant(&env, &client);
+
+    let mut expected = soroban_sdk::Vec::new(&env);
+    for _ in 0..5 {
+        expected.push_back(create_invoice(&env, &client, &merchant_a, &token));
+        create_invoice(&env, &client, &merchant_b, &token);
+    }
+
+    let first = client.get_invoices_page(&InvoiceIndex::Merchant(merchant_a.clone()), &None, &2);
+    assert_eq!(first.invoices.len(), 2);
+    assert_eq!(first.invoices.get(0).unwrap().id, expected.get(0).unwrap());
+    assert_eq!(first.invoices.get(1).unwrap().id, expected.get(1).unwrap());
+    assert_eq!(first.next_cursor, Some(2));
+
+    let second = client.get_invoices_page(
+        &InvoiceIndex::Merchant(merchant_a.clone()),
+        &first.next_cursor,
+        &2,
+    );
+    assert_eq!(second.invoices.get(0).unwrap().id, expected.get(2).unwrap());
+    assert_eq!(second.invoices.get(1).unwrap().id, expected.get(3).unwrap());
+
+    let last = client.get_invoices_page(
+        &InvoiceIndex::Merchant(merchant_a.clone()),
+        &second.next_cursor,
+        &2,
+    );
+    assert_eq!(last.invoices.len(), 1);
+    assert_eq!(last.invoices.get(0).unwrap().id, expected.get(4).unwrap());
+    assert_eq!(last.next_cursor, None);
+}
+
+#[test]
+fn test_status_index_follows_status_changes() {
+    let (env, client, _admin, token) = setup_test();
+    let merchant = register_merchant(&env, &client);
+
+    let id1 = create_invoice(&env, &client, &merchant, &token);
+    let id2 = create_invoice(&env, &client, &merchant, &token);
+    let id3 = create_invoice(&env, &client, &merchant, &token);
+
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&payer, &1_000);
+    client.pay_invoice(&payer, &id2, &None, &None, &None);
+    client.void_invoice(&merchant, &id3);
+
+    let pending =
+        client.get_invoices_page(&InvoiceIndex::Status(InvoiceStatus::Pending), &None, &10);
+    assert_eq!(pending.invoices.len(), 1);
+    assert_eq!(pending.invoices.get(0).unwrap().id, id1);
+
+    let paid = client.get_invoices_page(&InvoiceIndex::Status(InvoiceStatus::Paid), &None, &10);
+    assert_eq!(paid.invoices.len(), 1);
+    assert_eq!(paid.invoices.get(0).unwrap().id, id2);
+
+    let cancelled =
+        client.get_invoices_page(&InvoiceIndex::Status(InvoiceStatus::Cancelled), &None, &10);
+    assert_eq!(cancelled.invoices.len(), 1);
+    assert_eq!(cancelled.invoices.get(0).unwrap().id, id3);
+}
+
+#[test]
+fn test_payer_index_lists_paid_invoices() {
+    let (env, client, _admin, token) = setup_test();
+    let merchant = register_merchant(&env, &client);
+
+    let id1 = create_invoice(&env, &client, &merchant, &token);
+    let _id2 = create_invoice(&env, &client, &merchant, &token);
+    let id3 = create_invoice(&env, &client, &merchant, &token);
+
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&payer, &2_000);
+    client.pay_invoice(&payer, &id3, &None, &None, &None);
+    client.pay_invoice(&payer, &id1, &None, &None, &None);
+
+    // Payer entries are kept in the order the invoices were paid.
+    let page = client.get_invoices_page(&InvoiceIndex::Payer(payer.clone()), &None, &10);
+    assert_eq!(page.invoices.len(), 2);
+    assert_eq!(page.invoices.get(0).unwrap().id, id3);
+    assert_eq!(page.invoices.get(1).unwrap().id, id1);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_status_index_keeps_order_after_removal() {
+    let (env, client, _admin, token) = setup_test();
+    let merchant = register_merchant(&env, &client);
+
+    let id1 = create_invoice(&env, &client, &merchant, &token);
+    let id2 = create_invoice(&env, &client, &merchant, &token);
+    let id3 = create_invoice(&env, &client, &merchant, &token);
+
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&payer, &2_000);
+    client.pay_invoice(&payer, &id1, &None, &None, &None);
+
+    // id1's slot is left empty, so the rest keep their order.
+    let pending = InvoiceIndex::Status(InvoiceStatus::Pending);
+    let first = client.get_invoices_page(&pending, &None, &2);
+    assert_eq!(first.invoices.len(), 1);
+    assert_eq!(first.invoices.get(0).unwrap().id, id2);
+    assert_eq!(first.next_cursor, Some(2));
+
+    // Removing an invoice already paged past does not shift the cursor.
+    client.pay_invoice(&payer, &id2, &None, &None, &None);
+    let second = client.get_invoices_page(&pending, &first.next_cursor, &2);
+    assert_eq!(second.invoices.len(), 1);
+    assert_eq!(second.invoices.get(0).unwrap().id, id3);
+    assert_eq!(second.next_cursor, None);
+}
+
+#[test]
+fn test_all_index_pages_by_id() {
+    let (env, client, _admin, token) = setup_test();
+    let merchant = register_merchant(&env, &client);
+    for _ in 0..3 {
+        create_invoice(&env, &client, &merchant, &token);
+    }
+
+    let first = client.get_invoices_page(&InvoiceIndex::All, &None, &2);
+    assert_eq!(first.invoices.len(), 2);
+    assert_eq!(first.next_cursor, Some(2));
+
+    let second = client.get_invoices_page(&InvoiceIndex::All, &first.next_cursor, &2);
+    assert_eq!(second.invoices.len(), 1);
+    assert_eq!(second.invoices.get(0).unwrap().id, 3);
+    assert_eq!(second.next_cursor, None);
+}
+
+#[test]
+fn test_get_invoices_merchant_filter_uses_index() {
+    let (env, client, _admin, token) = setup_test();
+    let merchant_a = register_merchant(&env, &client);
+    let merchant_b = register_merchant(&env, &client);
+    create_invoice(&env, &client, &merchant_a, &token);
+    create_invoice(&env, &client, &merchant_b, &token);
+    create_invoice(&env, &client, &merchant_a, &token);
+
+    let filter = InvoiceFilter {
+        status: Some(InvoiceStatus::Pending as u32),
+        merchant: Some(merchant_a),
+        min_amount: None,
+        max_amount: None,
+        start_date: None,
+        end_date: None,
+    };
+    let invoices = client.get_invoices(&filter);
+    assert_eq!(invoices.len(), 2);
+    assert_eq!(invoices.get(0).unwrap().id, 1);
+    assert_eq!(invoices.get(1).unwrap().id, 3);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #55)")]
+fn test_page_limit_above_max_rejected() {
+    let (_env, client, _admin, _token) = setup_test();
+    client.get_invoices_page(&InvoiceIndex::All, &None, &101);
+}
+
+// Drops the invoices from their indexes, as if they were created before the
+// indexes existed.
+fn forget_indexes(env: &Env, client: &ShadeClient<'_>, invoice_ids: &[u64]) {
+    let merchant_id = client.get_invoice(&invoice_ids[0]).merchant_id;
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        for index in [
+            InvoiceIndexKey::Merchant(merchant_id),
+            InvoiceIndexKey::Status(InvoiceStatus::Pending),
+            InvoiceIndexKey::Open,
+        ] {
+            storage.remove(&InvoiceIndexDataKey::Length(index.clone()));
+            for (position, invoice_id) in invoice_ids.iter().enumerate() {
+                storage.remove(&InvoiceIndexDataKey::Entry(index.clone(), position as u32));
+                storage.remove(&InvoiceIndexDataKey::Position(index.clone(), *invoice_id));
+            }
+        }
+    });
+}
+
+#[test]
+fn test_backfill_indexes_existing_invoices() {
+    let (env, client, admin, token) = setup_test();
+    let merchant = register_merchant(&env, &client);
+    let id1 = create_invoice(&env, &client, &merchant, &token);
+    let id2 = create_invoice(&env, &client, &merchant, &token);
+    forget_indexes(&env, &client, &[id1, id2]);
+
+    let merchant_index = InvoiceIndex::Merchant(merchant.clone());
+    let page = client.get_invoices_page(&merchant_index, &None, &10);
+    assert_eq!(page.invoices.len(), 0);
+
+    let next = client.backfill_invoice_indexes(&admin, &None, &1);
+    assert_eq!(next, Some(1));
+    assert_eq!(client.backfill_invoice_indexes(&admin, &next, &1), None);
+    // Invoices that are already indexed are not added twice.
+    client.backfill_invoice_indexes(&admin, &None, &10);
+
+    let page = client.get_invoices_page(&merchant_index, &None, &10);
+    assert_eq!(page.invoices.len(), 2);
+    assert_eq!(page.invoices.get(0).unwrap().id, id1);
+    assert_eq!(page.invoices.get(1).unwrap().id, id2);
+
+    let pending = InvoiceIndex::Status(InvoiceStatus::Pending);
+    let page = client.get_invoices_page(&pending, &None, &10);
+    assert_eq!(page.invoices.len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_non_admin_cannot_backfill_indexes() {
+    let (env, client, _admin, _token) = setup_test();
+    client.backfill_invoice_indexes(&Address::generate(&env), &None, &10);
+}
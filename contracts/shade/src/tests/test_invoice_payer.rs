@@ -0,0 +1,214 @@
+#![cfg(test)]
+
+use crate::shade::{Shade, ShadeClient};
+use crate::types::{InvoiceIndex, InvoiceOptions, InvoiceStatus};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, vec, Address, Env, String};
+
+fn setup_test() -> (Env, ShadeClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(Shade, ());
+    let client = ShadeClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_accepted_token(&admin, &token);
+
+    let merchant = Address::generate(&env);
+    client.register_merchant(&merchant, &None);
+    client.set_merchant_account(&merchant, &Address::generate(&env));
+
+    (env, client, merchant, token)
+}
+
+fn funded_payer(env: &Env, token: &Address, amount: i128) -> Address {
+    let payer = Address::generate(env);
+    token::StellarAssetClient::new(env, token).mint(&payer, &amount);
+    payer
+}
+
+#[test]
+fn test_expected_payer_can_pay() {
+    let (env, client, merchant, token) = setup_test();
+    let treasury = funded_payer(&env, &token, 1_000);
+
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "B2B invoice"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            payer: Some(treasury.clone()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        client.get_invoice(&invoice_id).payer,
+        Some(treasury.clone())
+    );
+
+    client.pay_invoice(&treasury, &invoice_id, &None, &None, &None);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.payer, Some(treasury));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_other_payer_rejected() {
+    let (env, client, merchant, token) = setup_test();
+    let treasury = Address::generate(&env);
+    let stranger = funded_payer(&env, &token, 1_000);
+
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "B2B invoice"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            payer: Some(treasury),
+            ..Default::default()
+        },
+    );
+
+    client.pay_invoice(&stranger, &invoice_id, &None, &None, &None);
+}
+
+#[test]
+fn test_other_payer_rejected_before_any_transfer() {
+    let (env, client, merchant, token) = setup_test();
+    let treasury = Address::generate(&env);
+    let stranger = funded_payer(&env, &token, 1_000);
+
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "B2B invoice"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            payer: Some(treasury),
+            ..Default::default()
+        },
+    );
+
+    assert!(client
+        .try_pay_invoice_partial(&stranger, &invoice_id, &400, &None, &None)
+        .is_err());
+    assert_eq!(
+        token::TokenClient::new(&env, &token).balance(&stranger),
+        1_000
+    );
+    assert_eq!(client.get_invoice(&invoice_id).amount_paid, 0);
+}
+
+#[test]
+fn test_batch_payment_by_expected_payer() {
+    let (env, client, merchant, token) = setup_test();
+    let treasury = funded_payer(&env, &token, 2_000);
+
+    let first = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "B2B invoice 1"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            payer: Some(treasury.clone()),
+            ..Default::default()
+        },
+    );
+    let second = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "B2B invoice 2"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            payer: Some(treasury.clone()),
+            ..Default::default()
+        },
+    );
+
+    client.pay_invoices_batch(&treasury, &vec![&env, first, second]);
+
+    assert_eq!(client.get_invoice(&first).status, InvoiceStatus::Paid);
+    assert_eq!(client.get_invoice(&second).status, InvoiceStatus::Paid);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_batch_payment_rejects_other_payer() {
+    let (env, client, merchant, token) = setup_test();
+    let treasury = Address::generate(&env);
+    let stranger = funded_payer(&env, &token, 2_000);
+
+    let open_invoice = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "Open invoice"),
+        &1_000,
+        &token,
+        &InvoiceOptions::default(),
+    );
+    let restricted_invoice = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "B2B invoice"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            payer: Some(treasury),
+            ..Default::default()
+        },
+    );
+
+    client.pay_invoices_batch(&stranger, &vec![&env, open_invoice, restricted_invoice]);
+}
+
+#[test]
+fn test_restricted_draft_keeps_payer_after_finalize() {
+    let (env, client, merchant, token) = setup_test();
+    let treasury = funded_payer(&env, &token, 1_000);
+
+    let invoice_id = client.create_invoice_draft(
+        &merchant,
+        &String::from_str(&env, "Draft"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            payer: Some(treasury.clone()),
+            ..Default::default()
+        },
+    );
+    client.finalize_invoice(&merchant, &invoice_id);
+
+    let stranger = funded_payer(&env, &token, 1_000);
+    assert!(client
+        .try_pay_invoice(&stranger, &invoice_id, &None, &None, &None)
+        .is_err());
+
+    client.pay_invoice(&treasury, &invoice_id, &None, &None, &None);
+    assert_eq!(client.get_invoice(&invoice_id).status, InvoiceStatus::Paid);
+}
+
+#[test]
+fn test_restricted_invoice_listed_under_payer_index() {
+    let (env, client, merchant, token) = setup_test();
+    let treasury = Address::generate(&env);
+
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "B2B invoice"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            payer: Some(treasury.clone()),
+            ..Default::default()
+        },
+    );
+
+    let page = client.get_invoices_page(&InvoiceIndex::Payer(treasury), &None, &10);
+    assert_eq!(page.invoices.len(), 1);
+    assert_eq!(page.invoices.get(0).unwrap().id, invoice_id);
+}
@@ -0,0 +1,182 @@
+#![cfg(test)]
+
+use crate::shade::{Shade, ShadeClient};
+use crate::types::{InvoiceOptions, InvoiceStatus, LineItem};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{vec, Address, Env, String};
+
+fn setup_test() -> (Env, ShadeClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(Shade, ());
+    let client = ShadeClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_accepted_token(&admin, &token);
+
+    let merchant = Address::generate(&env);
+    client.register_merchant(&merchant, &None);
+    (env, client, merchant, token)
+}
+
+fn create_with_ref(
+    env: &Env,
+    client: &ShadeClient,
+    merchant: &Address,
+    token: &Address,
+    external_ref: &str,
+) -> u64 {
+    client.create_invoice(
+        merchant,
+        &String::from_str(env, "Order"),
+        &1_000,
+        token,
+        &InvoiceOptions {
+            external_ref: Some(String::from_str(env, external_ref)),
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn test_lookup_invoice_by_reference() {
+    let (env, client, merchant, token) = setup_test();
+    let invoice_id = create_with_ref(&env, &client, &merchant, &token, "ORD-1001");
+
+    let invoice = client.get_invoice_by_reference(&merchant, &String::from_str(&env, "ORD-1001"));
+    assert_eq!(invoice.id, invoice_id);
+    assert_eq!(
+        invoice.external_ref,
+        Some(String::from_str(&env, "ORD-1001"))
+    );
+}
+
+#[test]
+fn test_same_reference_allowed_for_different_merchants() {
+    let (env, client, merchant, token) = setup_test();
+    let other = Address::generate(&env);
+    client.register_merchant(&other, &None);
+
+    let first = create_with_ref(&env, &client, &merchant, &token, "ORD-1");
+    let second = create_with_ref(&env, &client, &other, &token, "ORD-1");
+
+    let reference = String::from_str(&env, "ORD-1");
+    assert_eq!(
+        client.get_invoice_by_reference(&merchant, &reference).id,
+        first
+    );
+    assert_eq!(
+        client.get_invoice_by_reference(&other, &reference).id,
+        second
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #119)")]
+fn test_duplicate_reference_rejected() {
+    let (env, client, merchant, token) = setup_test();
+    create_with_ref(&env, &client, &merchant, &token, "ORD-1001");
+    create_with_ref(&env, &client, &merchant, &token, "ORD-1001");
+}
+
+#[test]
+fn test_multi_token_invoice_can_carry_reference() {
+    let (env, client, merchant, token) = setup_test();
+    let reference = String::from_str(&env, "ORD-2001");
+    let invoice_id = client.create_multi_token_invoice(
+        &merchant,
+        &String::from_str(&env, "Order"),
+        &1_000,
+        &token,
+        &String::from_str(&env, "USD"),
+        &InvoiceOptions {
+            external_ref: Some(reference.clone()),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        client.get_invoice_by_reference(&merchant, &reference).id,
+        invoice_id
+    );
+}
+
+#[test]
+fn test_draft_and_itemized_invoices_can_carry_reference() {
+    let (env, client, merchant, token) = setup_test();
+    let draft_ref = String::from_str(&env, "DRAFT-1");
+    let items_ref = String::from_str(&env, "ITEMS-1");
+
+    let draft_id = client.create_invoice_draft(
+        &merchant,
+        &String::from_str(&env, "Draft"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            external_ref: Some(draft_ref.clone()),
+            ..Default::default()
+        },
+    );
+    let items = vec![
+        &env,
+        LineItem {
+            sku: String::from_str(&env, "SKU-1"),
+            quantity: 2,
+            unit_price: 500,
+            tax_bps: 0,
+        },
+    ];
+    let itemized_id = client.create_itemized_invoice(
+        &merchant,
+        &String::from_str(&env, "Itemized"),
+        &items,
+        &token,
+        &InvoiceOptions {
+            external_ref: Some(items_ref.clone()),
+            ..Default::default()
+        },
+    );
+
+    let draft = client.get_invoice_by_reference(&merchant, &draft_ref);
+    assert_eq!(draft.id, draft_id);
+    assert_eq!(draft.status, InvoiceStatus::Draft);
+    assert_eq!(
+        client.get_invoice_by_reference(&merchant, &items_ref).id,
+        itemized_id
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #119)")]
+fn test_draft_cannot_reuse_reference() {
+    let (env, client, merchant, token) = setup_test();
+    create_with_ref(&env, &client, &merchant, &token, "ORD-1001");
+    client.create_invoice_draft(
+        &merchant,
+        &String::from_str(&env, "Draft"),
+        &1_000,
+        &token,
+        &InvoiceOptions {
+            external_ref: Some(String::from_str(&env, "ORD-1001")),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #118)")]
+fn test_empty_reference_rejected() {
+    let (env, client, merchant, token) = setup_test();
+    create_with_ref(&env, &client, &merchant, &token, "");
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #8)")]
+fn test_unknown_reference_not_found() {
+    let (env, client, merchant, _token) = setup_test();
+    client.get_invoice_by_reference(&merchant, &String::from_str(&env, "missing"));
+}
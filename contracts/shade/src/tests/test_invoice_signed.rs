@@ -2,7 +2,7 @@
 extern crate alloc;
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::Role;
+use crate::types::{FiatPricingData, Role, SignedInvoicePayload};
 use ed25519_dalek::{Signer, SigningKey};
 use rand::rngs::OsRng;
 use soroban_sdk::testutils::Address as _;
@@ -19,30 +19,41 @@ fn setup_test() -> (Env, ShadeClient<'static>, Address, Address) {
     (env, client, contract_id, admin)
 }
 
-/// Build the same message as signature_util::build_message
-///
-/// Format: [contract_address, merchant_address, nonce, amount, token_address, description_bytes]
-fn build_test_message(
-    env: &Env,
-    contract_id: &Address,
+/// Payload for a token-priced invoice whose signature stays valid for the
+/// whole test.
+fn invoice_payload(
     merchant: &Address,
     description: &String,
     amount: i128,
     token: &Address,
     nonce: &BytesN<32>,
+) -> SignedInvoicePayload {
+    SignedInvoicePayload {
+        version: 1,
+        merchant: merchant.clone(),
+        description: description.clone(),
+        amount,
+        token: token.clone(),
+        payer: None,
+        expires_at: None,
+        fiat_pricing: FiatPricingData::None,
+        nonce: nonce.clone(),
+        deadline: 1_000_000,
+    }
+}
+
+/// Build the same message that `signature_util::build_message` constructs.
+///
+/// Format: [domain tag, contract_address, network_id, payload XDR]
+fn build_test_message(
+    env: &Env,
+    contract_id: &Address,
+    payload: &SignedInvoicePayload,
 ) -> alloc::vec::Vec<u8> {
-    let mut msg = Bytes::new(env);
+    let mut msg = Bytes::from_slice(env, b"shade:signed-invoice");
     msg.append(&contract_id.clone().to_xdr(env));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&merchant.clone().to_xdr(env));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(nonce.as_ref());
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&Bytes::from_slice(env, &amount.to_be_bytes()));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&token.clone().to_xdr(env));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&description.clone().to_xdr(env));
+    msg.append(env.ledger().network_id().as_ref());
+    msg.append(&payload.clone().to_xdr(env));
 
     let mut result = alloc::vec![0u8; msg.len() as usize];
     for i in 0..msg.len() {
@@ -65,6 +76,17 @@ fn generate_keypair() -> TestKeypair {
     }
 }
 
+fn sign_payload(
+    env: &Env,
+    contract_id: &Address,
+    keypair: &TestKeypair,
+    payload: &SignedInvoicePayload,
+) -> BytesN<64> {
+    let message = build_test_message(env, contract_id, payload);
+    let sig = keypair.signing_key.sign(&message);
+    BytesN::from_array(env, &sig.to_bytes())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn sign_invoice(
     env: &Env,
@@ -76,17 +98,8 @@ fn sign_invoice(
     token: &Address,
     nonce: &BytesN<32>,
 ) -> BytesN<64> {
-    let message = build_test_message(
-        env,
-        contract_id,
-        merchant,
-        description,
-        amount,
-        token,
-        nonce,
-    );
-    let sig = keypair.signing_key.sign(&message);
-    BytesN::from_array(env, &sig.to_bytes())
+    let payload = invoice_payload(merchant, description, amount, token, nonce);
+    sign_payload(env, contract_id, keypair, &payload)
 }
 
 fn create_nonce(env: &Env) -> BytesN<32> {
@@ -114,7 +127,7 @@ fn test_create_invoice_signed_manager_success() {
     client.grant_role(&admin, &manager, &Role::Manager);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -141,11 +154,7 @@ fn test_create_invoice_signed_manager_success() {
 
     let invoice_id = client.create_invoice_signed(
         &manager,
-        &merchant,
-        &description,
-        &amount,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, amount, &token, &nonce),
         &signature,
     );
 
@@ -162,7 +171,7 @@ fn test_create_invoice_signed_admin_success() {
     let (env, client, contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -189,11 +198,7 @@ fn test_create_invoice_signed_admin_success() {
 
     let invoice_id = client.create_invoice_signed(
         &admin,
-        &merchant,
-        &description,
-        &amount,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, amount, &token, &nonce),
         &signature,
     );
 
@@ -211,7 +216,7 @@ fn test_create_invoice_signed_guest_unauthorized() {
 
     let guest = Address::generate(&env);
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -236,11 +241,7 @@ fn test_create_invoice_signed_guest_unauthorized() {
 
     client.create_invoice_signed(
         &guest,
-        &merchant,
-        &description,
-        &1000,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, 1000, &token, &nonce),
         &signature,
     );
 }
@@ -255,7 +256,7 @@ fn test_create_invoice_signed_operator_unauthorized() {
     client.grant_role(&admin, &operator, &Role::Operator);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -280,11 +281,7 @@ fn test_create_invoice_signed_operator_unauthorized() {
 
     client.create_invoice_signed(
         &operator,
-        &merchant,
-        &description,
-        &1000,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, 1000, &token, &nonce),
         &signature,
     );
 }
@@ -299,7 +296,7 @@ fn test_create_invoice_signed_invalid_amount_zero() {
     client.grant_role(&admin, &manager, &Role::Manager);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -317,11 +314,7 @@ fn test_create_invoice_signed_invalid_amount_zero() {
 
     client.create_invoice_signed(
         &manager,
-        &merchant,
-        &description,
-        &0,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, 0, &token, &nonce),
         &signature,
     );
 }
@@ -336,7 +329,7 @@ fn test_create_invoice_signed_invalid_amount_negative() {
     client.grant_role(&admin, &manager, &Role::Manager);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -353,11 +346,7 @@ fn test_create_invoice_signed_invalid_amount_negative() {
 
     client.create_invoice_signed(
         &manager,
-        &merchant,
-        &description,
-        &-1000,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, -1000, &token, &nonce),
         &signature,
     );
 }
@@ -384,11 +373,7 @@ fn test_create_invoice_signed_unregistered_merchant() {
 
     client.create_invoice_signed(
         &manager,
-        &unregistered_merchant,
-        &description,
-        &1000,
-        &token,
-        &nonce,
+        &invoice_payload(&unregistered_merchant, &description, 1000, &token, &nonce),
         &signature,
     );
 }
@@ -404,8 +389,8 @@ fn test_create_invoice_signed_multiple_invoices() {
     let merchant1 = Address::generate(&env);
     let merchant2 = Address::generate(&env);
 
-    client.register_merchant(&merchant1);
-    client.register_merchant(&merchant2);
+    client.register_merchant(&merchant1, &None);
+    client.register_merchant(&merchant2, &None);
 
     let keypair1 = generate_keypair();
     let keypair2 = generate_keypair();
@@ -438,11 +423,7 @@ fn test_create_invoice_signed_multiple_invoices() {
     );
     let invoice_id_1 = client.create_invoice_signed(
         &manager,
-        &merchant1,
-        &description,
-        &1000,
-        &token,
-        &nonce1,
+        &invoice_payload(&merchant1, &description, 1000, &token, &nonce1),
         &sig1,
     );
 
@@ -459,11 +440,7 @@ fn test_create_invoice_signed_multiple_invoices() {
     );
     let invoice_id_2 = client.create_invoice_signed(
         &manager,
-        &merchant2,
-        &description,
-        &2000,
-        &token,
-        &nonce2,
+        &invoice_payload(&merchant2, &description, 2000, &token, &nonce2),
         &sig2,
     );
 
@@ -480,11 +457,7 @@ fn test_create_invoice_signed_multiple_invoices() {
     );
     let invoice_id_3 = client.create_invoice_signed(
         &manager,
-        &merchant1,
-        &description,
-        &3000,
-        &token,
-        &nonce3,
+        &invoice_payload(&merchant1, &description, 3000, &token, &nonce3),
         &sig3,
     );
 
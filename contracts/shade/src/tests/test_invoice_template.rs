@@ -0,0 +1,188 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::{InvoiceStatus, InvoiceTemplateStatus};
+use soroban_sdk::testutils::{Address as _, Ledger as _};
+use soroban_sdk::{vec, Address, String};
+
+const MONTH: u64 = 2_592_000;
+
+/// The shared fixture plus the payer templates are issued to.
+fn setup_test() -> (ShadeFixture, Address) {
+    let s = ShadeFixture::setup();
+    s.env.ledger().set_timestamp(1_000);
+    let payer = Address::generate(&s.env);
+    (s, payer)
+}
+
+fn create_template(s: &ShadeFixture, payer: &Address) -> u64 {
+    s.client.create_invoice_template(
+        &s.merchant,
+        payer,
+        &String::from_str(&s.env, "Monthly retainer"),
+        &500,
+        &s.token,
+        &MONTH,
+    )
+}
+
+#[test]
+fn test_issue_due_invoices_follows_interval() {
+    let (s, payer) = setup_test();
+    let template_id = create_template(&s, &payer);
+
+    assert_eq!(s.client.issue_due_invoices(&None, &10), None);
+    let invoice = s.client.get_invoice(&1);
+    assert_eq!(invoice.template_id, Some(template_id));
+    assert_eq!(invoice.payer, Some(payer.clone()));
+    assert_eq!(invoice.amount, 500);
+    assert_eq!(invoice.status, InvoiceStatus::Pending);
+
+    // Not due again until a full interval has passed.
+    s.client.issue_due_invoices(&None, &10);
+    assert!(s.client.try_get_invoice(&2).is_err());
+
+    s.env.ledger().set_timestamp(1_000 + MONTH);
+    s.client.issue_due_invoices(&None, &10);
+    assert_eq!(s.client.get_invoice(&2).template_id, Some(template_id));
+
+    let template = s.client.get_invoice_template(&template_id);
+    assert_eq!(template.issued_count, 2);
+    assert_eq!(template.next_issue_at, 1_000 + 2 * MONTH);
+}
+
+#[test]
+fn test_issued_invoice_only_payable_by_template_payer() {
+    let (s, payer) = setup_test();
+    create_template(&s, &payer);
+    s.client.issue_due_invoices(&None, &10);
+
+    s.mint(&s.token, &payer, 500);
+    s.client.pay_invoice(&payer, &1, &None, &None, &None);
+    assert_eq!(s.client.get_invoice(&1).status, InvoiceStatus::Paid);
+}
+
+#[test]
+fn test_paused_template_skips_periods() {
+    let (s, payer) = setup_test();
+    let template_id = create_template(&s, &payer);
+    s.client.issue_due_invoices(&None, &10);
+
+    s.client.pause_invoice_template(&s.merchant, &template_id);
+    s.env.ledger().set_timestamp(1_000 + 3 * MONTH);
+    s.client.issue_due_invoices(&None, &10);
+    assert_eq!(s.client.get_invoice_template(&template_id).issued_count, 1);
+
+    s.client.resume_invoice_template(&s.merchant, &template_id);
+    s.client.issue_due_invoices(&None, &10);
+    s.client.issue_due_invoices(&None, &10);
+
+    let template = s.client.get_invoice_template(&template_id);
+    assert_eq!(template.status, InvoiceTemplateStatus::Active);
+    assert_eq!(template.issued_count, 2);
+    assert_eq!(template.next_issue_at, 1_000 + 4 * MONTH);
+}
+
+#[test]
+fn test_ended_template_issues_nothing() {
+    let (s, payer) = setup_test();
+    let template_id = create_template(&s, &payer);
+    s.client.end_invoice_template(&s.merchant, &template_id);
+
+    s.client.issue_due_invoices(&None, &10);
+    assert_eq!(s.client.get_invoice_template(&template_id).issued_count, 0);
+    assert!(s
+        .client
+        .try_resume_invoice_template(&s.merchant, &template_id)
+        .is_err());
+}
+
+#[test]
+fn test_issue_due_invoices_pages_through_templates() {
+    let (s, payer) = setup_test();
+    for _ in 0..3 {
+        create_template(&s, &payer);
+    }
+
+    assert_eq!(s.client.issue_due_invoices(&None, &2), Some(2));
+    assert_eq!(s.client.issue_due_invoices(&Some(2), &2), None);
+    assert_eq!(s.client.get_invoice(&3).template_id, Some(3));
+}
+
+#[test]
+fn test_delisted_token_leaves_template_due() {
+    let (s, payer) = setup_test();
+    let template_id = create_template(&s, &payer);
+    s.client.remove_accepted_token(&s.admin, &s.token);
+
+    s.client.issue_due_invoices(&None, &10);
+    let template = s.client.get_invoice_template(&template_id);
+    assert_eq!(template.issued_count, 0);
+    assert_eq!(template.next_issue_at, 1_000);
+}
+
+#[test]
+fn test_inactive_merchant_leaves_template_due() {
+    let (s, payer) = setup_test();
+    let template_id = create_template(&s, &payer);
+    s.client.set_merchant_status(&s.admin, &1, &false);
+
+    s.client.issue_due_invoices(&None, &10);
+    let template = s.client.get_invoice_template(&template_id);
+    assert_eq!(template.issued_count, 0);
+
+    s.client.set_merchant_status(&s.admin, &1, &true);
+    s.client.issue_due_invoices(&None, &10);
+    let template = s.client.get_invoice_template(&template_id);
+    assert_eq!(template.issued_count, 1);
+}
+
+#[test]
+fn test_token_dropped_by_merchant_leaves_template_due() {
+    let (s, payer) = setup_test();
+    let template_id = create_template(&s, &payer);
+    let other_token = s
+        .env
+        .register_stellar_asset_contract_v2(Address::generate(&s.env))
+        .address();
+    s.client.add_accepted_token(&s.admin, &other_token);
+    s.client
+        .set_merchant_accepted_tokens(&s.merchant, &vec![&s.env, other_token]);
+
+    s.client.issue_due_invoices(&None, &10);
+    let template = s.client.get_invoice_template(&template_id);
+    assert_eq!(template.issued_count, 0);
+    assert_eq!(template.next_issue_at, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_only_owner_can_pause_template() {
+    let (s, payer) = setup_test();
+    let template_id = create_template(&s, &payer);
+    let other = Address::generate(&s.env);
+    s.client.pause_invoice_template(&other, &template_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #21)")]
+fn test_zero_interval_rejected() {
+    let (s, payer) = setup_test();
+    s.client.create_invoice_template(
+        &s.merchant,
+        &payer,
+        &String::from_str(&s.env, "Broken"),
+        &500,
+        &s.token,
+        &0,
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #117)")]
+fn test_pausing_twice_rejected() {
+    let (s, payer) = setup_test();
+    let template_id = create_template(&s, &payer);
+    s.client.pause_invoice_template(&s.merchant, &template_id);
+    s.client.pause_invoice_template(&s.merchant, &template_id);
+}
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::InvoiceStatus;
+use crate::types::{InvoiceOptions, InvoiceStatus};
 use soroban_sdk::testutils::Address as _;
 use soroban_sdk::{Address, Env, String};
 
@@ -24,7 +24,7 @@ fn test_void_invoice_success() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
@@ -32,7 +32,13 @@ fn test_void_invoice_success() {
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
     let amount: i128 = 1000;
-    let invoice_id = client.create_invoice(&merchant, &description, &amount, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &amount,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Verify invoice is Pending before voiding
     let invoice_before = client.get_invoice(&invoice_id);
@@ -55,14 +61,20 @@ fn test_void_invoice_unauthorized_random_address() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Try to void with random address (should panic with NotAuthorized)
     let random_address = Address::generate(&env);
@@ -78,17 +90,23 @@ fn test_void_invoice_unauthorized_different_merchant() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant1 = Address::generate(&env);
-    client.register_merchant(&merchant1);
+    client.register_merchant(&merchant1, &None);
 
     let merchant2 = Address::generate(&env);
-    client.register_merchant(&merchant2);
+    client.register_merchant(&merchant2, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant1, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant1,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Try to void with different merchant (should panic with NotAuthorized)
     client.void_invoice(&merchant2, &invoice_id);
@@ -117,7 +135,7 @@ fn test_void_invoice_already_paid() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -125,13 +143,19 @@ fn test_void_invoice_already_paid() {
 
     // Create invoice
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Pay the invoice
     let customer = Address::generate(&env);
     let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
-    client.pay_invoice(&customer, &invoice_id);
+    client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     // Try to void paid invoice (should panic with InvalidInvoiceStatus)
     client.void_invoice(&merchant, &invoice_id);
@@ -160,7 +184,7 @@ fn test_pay_voided_invoice() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -168,7 +192,13 @@ fn test_pay_voided_invoice() {
 
     // Create invoice
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Void the invoice
     client.void_invoice(&merchant, &invoice_id);
@@ -177,7 +207,7 @@ fn test_pay_voided_invoice() {
     let customer = Address::generate(&env);
     let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
-    client.pay_invoice(&customer, &invoice_id);
+    client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 }
 
 /// Test Case 5: Double Voiding
@@ -189,14 +219,20 @@ fn test_void_invoice_already_cancelled() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Void the invoice once
     client.void_invoice(&merchant, &invoice_id);
@@ -214,7 +250,7 @@ fn test_void_nonexistent_invoice() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Try to void non-existent invoice (should panic with InvoiceNotFound)
     client.void_invoice(&merchant, &999);
@@ -241,7 +277,7 @@ fn test_void_refunded_invoice() {
     client.set_fee(&admin, &token, &500);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_account_id = env.register(account::account::MerchantAccount, ());
     let merchant_account = account::account::MerchantAccountClient::new(&env, &merchant_account_id);
@@ -250,14 +286,20 @@ fn test_void_refunded_invoice() {
     client.set_merchant_account(&merchant, &merchant_account_id);
 
     let description = String::from_str(&env, "Refundable Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let customer = Address::generate(&env);
     let token_client = soroban_sdk::token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
     token_client.mint(&merchant_account_id, &1000);
 
-    client.pay_invoice(&customer, &invoice_id);
+    client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
     client.refund_invoice(&merchant, &invoice_id);
 
     // Try to void refunded invoice (should panic with InvalidInvoiceStatus)
@@ -271,7 +313,7 @@ fn test_void_invoice_state_isolation() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
@@ -279,9 +321,27 @@ fn test_void_invoice_state_isolation() {
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Test Invoice");
 
-    let invoice_id_1 = client.create_invoice(&merchant, &description, &1000, &token, &None);
-    let invoice_id_2 = client.create_invoice(&merchant, &description, &2000, &token, &None);
-    let invoice_id_3 = client.create_invoice(&merchant, &description, &3000, &token, &None);
+    let invoice_id_1 = client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
+    let invoice_id_2 = client.create_invoice(
+        &merchant,
+        &description,
+        &2000,
+        &token,
+        &InvoiceOptions::default(),
+    );
+    let invoice_id_3 = client.create_invoice(
+        &merchant,
+        &description,
+        &3000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Void only the second invoice
     client.void_invoice(&merchant, &invoice_id_2);
@@ -0,0 +1,167 @@
+#![cfg(test)]
+
+use crate::shade::{Shade, ShadeClient};
+use crate::types::{InvoiceOptions, InvoiceStatus, LineItem};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, vec, Address, Env, String, Vec};
+
+fn setup_test() -> (Env, ShadeClient<'static>, Address, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(Shade, ());
+    let client = ShadeClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.add_accepted_token(&admin, &token);
+
+    let merchant = Address::generate(&env);
+    client.register_merchant(&merchant, &None);
+    client.set_merchant_account(&merchant, &Address::generate(&env));
+
+    (env, client, admin, merchant, token)
+}
+
+fn line_item(env: &Env, sku: &str, quantity: u32, unit_price: i128, tax_bps: u32) -> LineItem {
+    LineItem {
+        sku: String::from_str(env, sku),
+        quantity,
+        unit_price,
+        tax_bps,
+    }
+}
+
+#[test]
+fn test_create_itemized_invoice_computes_totals() {
+    let (env, client, _admin, merchant, token) = setup_test();
+
+    let items = vec![
+        &env,
+        line_item(&env, "SKU-1", 3, 100, 1_000), // 300 + 30 tax
+        line_item(&env, "SKU-2", 2, 250, 0),     // 500 + 0 tax
+    ];
+    let invoice_id = client.create_itemized_invoice(
+        &merchant,
+        &String::from_str(&env, "Order #1"),
+        &items,
+        &token,
+        &InvoiceOptions::default(),
+    );
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.subtotal, 800);
+    assert_eq!(invoice.tax_amount, 30);
+    assert_eq!(invoice.amount, 830);
+    assert_eq!(invoice.line_items, items);
+    assert_eq!(invoice.status, InvoiceStatus::Pending);
+}
+
+#[test]
+fn test_itemized_invoice_pays_total() {
+    let (env, client, _admin, merchant, token) = setup_test();
+
+    let items = vec![&env, line_item(&env, "SKU-1", 4, 250, 500)];
+    let invoice_id = client.create_itemized_invoice(
+        &merchant,
+        &String::from_str(&env, "Order #2"),
+        &items,
+        &token,
+        &InvoiceOptions::default(),
+    );
+
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&payer, &1_050);
+    client.pay_invoice(&payer, &invoice_id, &None, &None, &None);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount_paid, 1_050);
+    assert_eq!(token::TokenClient::new(&env, &token).balance(&payer), 0);
+}
+
+#[test]
+fn test_amend_invoice_items_recomputes_totals() {
+    let (env, client, _admin, merchant, token) = setup_test();
+
+    let invoice_id = client.create_itemized_invoice(
+        &merchant,
+        &String::from_str(&env, "Order #3"),
+        &vec![&env, line_item(&env, "SKU-1", 1, 1_000, 0)],
+        &token,
+        &InvoiceOptions::default(),
+    );
+
+    let new_items = vec![
+        &env,
+        line_item(&env, "SKU-1", 1, 1_000, 0),
+        line_item(&env, "SKU-3", 5, 20, 2_000),
+    ];
+    client.amend_invoice_items(&merchant, &invoice_id, &new_items);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.subtotal, 1_100);
+    assert_eq!(invoice.tax_amount, 20);
+    assert_eq!(invoice.amount, 1_120);
+    assert_eq!(invoice.line_items.len(), 2);
+}
+
+#[test]
+fn test_single_amount_invoice_has_no_tax() {
+    let (env, client, _admin, merchant, token) = setup_test();
+
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "Plain"),
+        &500,
+        &token,
+        &InvoiceOptions::default(),
+    );
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.subtotal, 500);
+    assert_eq!(invoice.tax_amount, 0);
+    assert!(invoice.line_items.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #100)")]
+fn test_create_itemized_invoice_rejects_empty_items() {
+    let (env, client, _admin, merchant, token) = setup_test();
+    client.create_itemized_invoice(
+        &merchant,
+        &String::from_str(&env, "Empty"),
+        &Vec::new(&env),
+        &token,
+        &InvoiceOptions::default(),
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #100)")]
+fn test_create_itemized_invoice_rejects_zero_quantity() {
+    let (env, client, _admin, merchant, token) = setup_test();
+    client.create_itemized_invoice(
+        &merchant,
+        &String::from_str(&env, "Zero qty"),
+        &vec![&env, line_item(&env, "SKU-1", 0, 100, 0)],
+        &token,
+        &InvoiceOptions::default(),
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #100)")]
+fn test_amend_invoice_amount_rejected_for_itemized_invoice() {
+    let (env, client, _admin, merchant, token) = setup_test();
+    let invoice_id = client.create_itemized_invoice(
+        &merchant,
+        &String::from_str(&env, "Order"),
+        &vec![&env, line_item(&env, "SKU-1", 1, 1_000, 0)],
+        &token,
+        &InvoiceOptions::default(),
+    );
+    client.amend_invoice(&merchant, &invoice_id, &Some(2_000), &None);
+}
@@ -24,7 +24,7 @@ fn test_register_merchant_successfully() {
     let (env, client, contract_id) = setup_test();
     let merchant = Address::generate(&env);
 
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_data = client.get_merchant(&1u64);
     assert_eq!(merchant_data.id, 1);
@@ -48,8 +48,8 @@ fn test_register_multiple_merchants_with_unique_ids() {
     let merchant_1 = Address::generate(&env);
     let merchant_2 = Address::generate(&env);
 
-    client.register_merchant(&merchant_1);
-    client.register_merchant(&merchant_2);
+    client.register_merchant(&merchant_1, &None);
+    client.register_merchant(&merchant_2, &None);
 
     let merchant_data_1 = client.get_merchant(&1u64);
     let merchant_data_2 = client.get_merchant(&2u64);
@@ -73,11 +73,11 @@ fn test_register_duplicate_merchant_fails() {
     let (env, client, _contract_id) = setup_test();
     let merchant = Address::generate(&env);
 
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let expected_error =
         soroban_sdk::Error::from_contract_error(ContractError::MerchantAlreadyRegistered as u32);
-    let result = client.try_register_merchant(&merchant);
+    let result = client.try_register_merchant(&merchant, &None);
 
     assert!(matches!(result, Err(Ok(err)) if err == expected_error));
 }
@@ -87,7 +87,7 @@ fn test_get_merchant_lookup_valid_id() {
     let (env, client, _contract_id) = setup_test();
     let merchant = Address::generate(&env);
 
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_data = client.get_merchant(&1u64);
     assert_eq!(merchant_data.id, 1);
@@ -115,7 +115,7 @@ fn test_is_merchant_returns_true_for_registered_and_false_for_unknown() {
     let registered_merchant = Address::generate(&env);
     let unknown_merchant = Address::generate(&env);
 
-    client.register_merchant(&registered_merchant);
+    client.register_merchant(&registered_merchant, &None);
 
     assert!(client.is_merchant(&registered_merchant));
     assert!(!client.is_merchant(&unknown_merchant));
@@ -53,7 +53,7 @@ fn test_successful_activation() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_id = 1u64;
     assert!(client.is_merchant_active(&merchant_id));
@@ -94,7 +94,7 @@ fn test_successful_deactivation() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_id = 1u64;
     assert!(client.is_merchant_active(&merchant_id));
@@ -130,7 +130,7 @@ fn test_unauthorized_status_change() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_id = 1u64;
 
@@ -19,7 +19,7 @@ fn test_set_merchant_key_success() {
     let (env, client, contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let key = BytesN::from_array(&env, &[0u8; 32]);
     client.set_merchant_key(&merchant, &key);
@@ -56,7 +56,7 @@ fn test_update_merchant_key() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let key1 = BytesN::from_array(&env, &[0u8; 32]);
     client.set_merchant_key(&merchant, &key1);
@@ -73,7 +73,7 @@ fn test_get_non_existent_key() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     client.get_merchant_key(&merchant);
 }
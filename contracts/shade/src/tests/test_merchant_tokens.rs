@@ -3,6 +3,7 @@
 use crate::errors::ContractError;
 use crate::shade::Shade;
 use crate::shade::ShadeClient;
+use crate::types::InvoiceOptions;
 use soroban_sdk::testutils::{Address as _, Events as _};
 use soroban_sdk::{Address, Env, Map, String, Symbol, TryIntoVal, Val, Vec};
 
@@ -49,7 +50,7 @@ fn test_merchant_sets_and_gets_tokens() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token_admin = Address::generate(&env);
     let token1 = env
@@ -88,7 +89,7 @@ fn test_merchant_cannot_set_unaccepted_global_token() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = Address::generate(&env); // Not in global whitelist
 
@@ -113,7 +114,7 @@ fn test_invoice_creation_with_merchant_whitelist() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token_admin = Address::generate(&env);
     let token1 = env
@@ -137,7 +138,7 @@ fn test_invoice_creation_with_merchant_whitelist() {
         &String::from_str(&env, "Test"),
         &1000,
         &token1,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     // Invoice with token2 should fail (globally accepted but not by merchant)
@@ -146,7 +147,7 @@ fn test_invoice_creation_with_merchant_whitelist() {
         &String::from_str(&env, "Test"),
         &1000,
         &token2,
-        &None,
+        &InvoiceOptions::default(),
     );
     let expected_error =
         soroban_sdk::Error::from_contract_error(ContractError::TokenNotAcceptedByMerchant as u32);
@@ -165,7 +166,7 @@ fn test_empty_merchant_whitelist_defaults_to_global() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
@@ -178,7 +179,7 @@ fn test_empty_merchant_whitelist_defaults_to_global() {
         &String::from_str(&env, "Test"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 }
 
@@ -214,7 +215,7 @@ fn test_duplicate_tokens_are_deduplicated() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
@@ -244,7 +245,7 @@ fn test_merchant_can_overwrite_whitelist() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token_admin = Address::generate(&env);
     let token1 = env
@@ -291,7 +292,7 @@ fn test_remove_merchant_accepted_token() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token_admin = Address::generate(&env);
     let token1 = env
@@ -331,7 +332,7 @@ fn test_remove_nonexistent_token_fails() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token_admin = Address::generate(&env);
     let token1 = env
@@ -368,7 +369,7 @@ fn test_inactive_merchant_cannot_set_tokens() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Deactivate the merchant
     client.set_merchant_status(&admin, &1, &false);
@@ -399,7 +400,7 @@ fn test_is_token_accepted_for_merchant_public() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token_admin = Address::generate(&env);
     let token1 = env
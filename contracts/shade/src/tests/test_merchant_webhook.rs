@@ -19,7 +19,7 @@ fn test_merchant_webhook_defaults_to_empty() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let webhook = client.get_merchant_webhook(&1u64);
     assert_eq!(webhook, String::from_str(&env, ""));
@@ -30,7 +30,7 @@ fn test_set_merchant_webhook_success() {
     let (env, client, contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let webhook = String::from_str(&env, "https://relay.example.com/hook");
     client.set_merchant_webhook(&merchant, &webhook);
@@ -72,7 +72,7 @@ fn test_update_merchant_webhook() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let webhook1 = String::from_str(&env, "https://relay.example.com/hook");
     client.set_merchant_webhook(&merchant, &webhook1);
@@ -88,7 +88,7 @@ fn test_clear_merchant_webhook() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let webhook = String::from_str(&env, "https://relay.example.com/hook");
     client.set_merchant_webhook(&merchant, &webhook);
@@ -123,8 +123,8 @@ fn test_webhooks_are_per_merchant() {
 
     let merchant_1 = Address::generate(&env);
     let merchant_2 = Address::generate(&env);
-    client.register_merchant(&merchant_1);
-    client.register_merchant(&merchant_2);
+    client.register_merchant(&merchant_1, &None);
+    client.register_merchant(&merchant_2, &None);
 
     let webhook_1 = String::from_str(&env, "https://m1.example.com/hook");
     let webhook_2 = String::from_str(&env, "https://m2.example.com/hook");
@@ -0,0 +1,285 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::{InvoiceOptions, InvoiceStatus, OracleConfig, PaymentTerms, PaymentTermsData};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{contract, contractimpl, token, vec, Address, Env, String};
+
+#[contract]
+pub struct TokenPriceOracle;
+
+#[contractimpl]
+impl TokenPriceOracle {
+    pub fn get_price(env: Env, token: Address, _quote_currency: String) -> i128 {
+        env.storage().instance().get(&token).unwrap_or(0)
+    }
+
+    pub fn set_price(env: Env, token: Address, price: i128) {
+        env.storage().instance().set(&token, &price);
+    }
+}
+
+/// The fixture token is the reference token; a second accepted token is
+/// what payers pay in.
+fn setup_test() -> (ShadeFixture, Address, TokenPriceOracleClient<'static>) {
+    let s = ShadeFixture::setup();
+    let pay_token = s.add_token();
+
+    // Reference token at $2.00, pay token at $0.50 (8 price decimals).
+    let oracle_id = s.env.register(TokenPriceOracle, ());
+    let oracle = TokenPriceOracleClient::new(&s.env, &oracle_id);
+    oracle.set_price(&s.token, &200_000_000);
+    oracle.set_price(&pay_token, &50_000_000);
+    for token in [&s.token, &pay_token] {
+        s.client.set_token_oracle(
+            &s.admin,
+            token,
+            &OracleConfig {
+                contract: oracle_id.clone(),
+                price_decimals: 8,
+                token_decimals: 7,
+            },
+        );
+    }
+    (s, pay_token, oracle)
+}
+
+fn create_multi_token_invoice(s: &ShadeFixture, amount: i128) -> u64 {
+    s.client.create_multi_token_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Multi-token invoice"),
+        &amount,
+        &s.token,
+        &String::from_str(&s.env, "USD"),
+        &InvoiceOptions::default(),
+    )
+}
+
+#[test]
+fn test_pay_reference_invoice_in_other_token() {
+    let (s, pay_token, _) = setup_test();
+    let invoice_id = create_multi_token_invoice(&s, 1_000);
+
+    let payer = Address::generate(&s.env);
+    s.mint(&pay_token, &payer, 4_000);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &None, &None);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.token, s.token);
+    assert_eq!(invoice.paid_token, Some(pay_token.clone()));
+    assert_eq!(invoice.amount, 1_000);
+    assert_eq!(invoice.amount_paid, 4_000);
+
+    let pay_token_client = token::TokenClient::new(&s.env, &pay_token);
+    assert_eq!(pay_token_client.balance(&payer), 0);
+    assert_eq!(pay_token_client.balance(&s.merchant_account), 4_000);
+}
+
+#[test]
+fn test_fees_and_analytics_recorded_in_paid_token() {
+    let (s, pay_token, _) = setup_test();
+    s.client.set_fee(&s.admin, &pay_token, &250); // 2.5%
+    let invoice_id = create_multi_token_invoice(&s, 1_000);
+
+    let payer = Address::generate(&s.env);
+    s.mint(&pay_token, &payer, 4_000);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &None, &None);
+
+    let analytics = s.client.get_merchant_analytics(&s.merchant, &pay_token);
+    assert_eq!(analytics.total_volume, 4_000);
+    assert_eq!(analytics.total_fees, 100);
+    assert_eq!(analytics.transaction_count, 1);
+
+    let reference_analytics = s.client.get_merchant_analytics(&s.merchant, &s.token);
+    assert_eq!(reference_analytics.transaction_count, 0);
+
+    let pay_token_client = token::TokenClient::new(&s.env, &pay_token);
+    assert_eq!(pay_token_client.balance(&s.merchant_account), 3_900);
+}
+
+#[test]
+fn test_discount_recorded_in_paid_token() {
+    let (s, pay_token, _) = setup_test();
+    let terms = PaymentTerms {
+        discount_bps: 200,
+        discount_deadline: 10_000,
+        penalty_bps: 500,
+        due_date: 30_000,
+    };
+    let invoice_id = s.client.create_multi_token_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Multi-token invoice"),
+        &1_000,
+        &s.token,
+        &String::from_str(&s.env, "USD"),
+        &InvoiceOptions {
+            terms: PaymentTermsData::Some(terms),
+            ..Default::default()
+        },
+    );
+
+    // 2% off 1_000 reference tokens leaves 980, or 3_920 of the $0.50 token.
+    let payer = Address::generate(&s.env);
+    s.mint(&pay_token, &payer, 4_000);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &None, &None);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.amount, 980);
+    assert_eq!(invoice.amount_paid, 3_920);
+    assert_eq!(invoice.discount_amount, 80);
+    assert_eq!(invoice.penalty_amount, 0);
+    assert_eq!(
+        token::TokenClient::new(&s.env, &pay_token).balance(&payer),
+        80
+    );
+}
+
+#[test]
+fn test_fiat_invoice_payable_in_other_token() {
+    let (s, pay_token, _) = setup_test();
+    // $10.00 invoice settled in the $0.50 token -> 20 whole tokens.
+    let invoice_id = s.client.create_fiat_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Fiat invoice"),
+        &1_000,
+        &String::from_str(&s.env, "USD"),
+        &2,
+        &s.token,
+        &InvoiceOptions::default(),
+    );
+
+    let expected = 200_000_000;
+    let payer = Address::generate(&s.env);
+    s.mint(&pay_token, &payer, expected);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &None, &None);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount_paid, expected);
+    assert_eq!(invoice.paid_token, Some(pay_token.clone()));
+}
+
+#[test]
+fn test_refund_returns_paid_token() {
+    let (s, pay_token, _) = setup_test();
+    let invoice_id = create_multi_token_invoice(&s, 1_000);
+
+    let payer = Address::generate(&s.env);
+    s.mint(&pay_token, &payer, 4_000);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &None, &None);
+
+    s.client
+        .refund_invoice_partial(&s.merchant, &invoice_id, &1_000);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::PartiallyRefunded);
+    assert_eq!(invoice.amount_refunded, 1_000);
+    assert_eq!(
+        token::TokenClient::new(&s.env, &pay_token).balance(&payer),
+        1_000
+    );
+}
+
+#[test]
+fn test_paying_in_reference_token_uses_regular_flow() {
+    let (s, _, _) = setup_test();
+    let invoice_id = create_multi_token_invoice(&s, 1_000);
+
+    let payer = s.funded_payer(1_000);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &s.token, &None, &None);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.paid_token, None);
+    assert_eq!(invoice.amount_paid, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #103)")]
+fn test_single_token_invoice_cannot_be_paid_in_other_token() {
+    let (s, pay_token, _) = setup_test();
+    let invoice_id = s.create_invoice(1_000);
+
+    let payer = Address::generate(&s.env);
+    s.mint(&pay_token, &payer, 4_000);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #41)")]
+fn test_token_outside_merchant_list_rejected() {
+    let (s, pay_token, _) = setup_test();
+    s.client
+        .set_merchant_accepted_tokens(&s.merchant, &vec![&s.env, s.token.clone()]);
+    let invoice_id = create_multi_token_invoice(&s, 1_000);
+
+    let payer = Address::generate(&s.env);
+    s.mint(&pay_token, &payer, 4_000);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_partially_paid_invoice_cannot_switch_token() {
+    let (s, pay_token, _) = setup_test();
+    let invoice_id = create_multi_token_invoice(&s, 1_000);
+
+    let payer = s.funded_payer(500);
+    s.mint(&pay_token, &payer, 4_000);
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &500, &None, &None);
+
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #109)")]
+fn test_max_amount_bounds_converted_charge() {
+    let (s, pay_token, _) = setup_test();
+    let invoice_id = create_multi_token_invoice(&s, 1_000);
+
+    let payer = Address::generate(&s.env);
+    s.mint(&pay_token, &payer, 4_000);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &Some(3_999), &None);
+}
+
+#[test]
+fn test_quote_locked_in_pay_token_is_honoured() {
+    let (s, pay_token, oracle) = setup_test();
+    // $10.00 costs 20 pay tokens at $0.50 (7 decimals).
+    let invoice_id = s.client.create_fiat_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Fiat invoice"),
+        &1_000,
+        &String::from_str(&s.env, "USD"),
+        &2,
+        &s.token,
+        &InvoiceOptions::default(),
+    );
+    let payer = Address::generate(&s.env);
+    s.mint(&pay_token, &payer, 200_000_000);
+
+    assert_eq!(
+        s.client
+            .lock_fiat_quote(&payer, &invoice_id, &Some(pay_token.clone())),
+        200_000_000
+    );
+    oracle.set_price(&pay_token, &100_000_000);
+    s.client
+        .pay_invoice_with_token(&payer, &invoice_id, &pay_token, &Some(200_000_000), &None);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount_paid, 200_000_000);
+}
@@ -3,8 +3,9 @@
 use crate::components::pausable as pausable_component;
 use crate::errors::ContractError;
 use crate::shade::{Shade, ShadeClient};
+use crate::types::{ExpiryRefundPolicy, RefundWindowBounds, TipConfig};
 use soroban_sdk::testutils::{Address as _, Events as _};
-use soroban_sdk::{Address, Env, Map, Symbol, TryIntoVal, Val};
+use soroban_sdk::{Address, Env, Map, String, Symbol, TryIntoVal, Val, Vec};
 
 fn setup_test() -> (Env, ShadeClient<'static>, Address, Address) {
     let env = Env::default();
@@ -163,3 +164,107 @@ fn test_double_unpause_fails_with_contract_not_paused_error() {
     assert!(matches!(unpause_result, Err(Ok(err)) if err == expected_error));
     assert!(!client.is_paused());
 }
+
+#[test]
+fn test_tax_settings_cannot_change_while_paused() {
+    let (env, client, _contract_id, admin) = setup_test();
+    client.pause(&admin);
+
+    let merchant = Address::generate(&env);
+    let code = String::from_str(&env, "VAT");
+    let expected_error =
+        soroban_sdk::Error::from_contract_error(ContractError::ContractPaused as u32);
+
+    let set_rate = client.try_set_merchant_tax_rate(&merchant, &code, &1_000);
+    assert!(matches!(set_rate, Err(Ok(err)) if err == expected_error));
+
+    let remove_rate = client.try_remove_merchant_tax_rate(&merchant, &code);
+    assert!(matches!(remove_rate, Err(Ok(err)) if err == expected_error));
+
+    let set_account = client.try_set_merchant_tax_account(&merchant, &Address::generate(&env));
+    assert!(matches!(set_account, Err(Ok(err)) if err == expected_error));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_expiry_policy_cannot_change_while_paused() {
+    let (env, client, _contract_id, admin) = setup_test();
+    client.pause(&admin);
+
+    client.set_merchant_expiry_policy(
+        &Address::generate(&env),
+        &ExpiryRefundPolicy::RetainPayments,
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_installments_cannot_change_while_paused() {
+    let (env, client, _contract_id, admin) = setup_test();
+    client.pause(&admin);
+
+    client.set_invoice_installments(&Address::generate(&env), &1, &Vec::new(&env));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_fiat_quote_window_cannot_change_while_paused() {
+    let (_env, client, _contract_id, admin) = setup_test();
+    client.pause(&admin);
+
+    client.set_fiat_quote_window(&admin, &600);
+}
+
+#[test]
+fn test_refund_windows_cannot_change_while_paused() {
+    let (env, client, _contract_id, admin) = setup_test();
+    client.pause(&admin);
+
+    let merchant = Address::generate(&env);
+    let expected_error =
+        soroban_sdk::Error::from_contract_error(ContractError::ContractPaused as u32);
+
+    let bounds = RefundWindowBounds {
+        min: 60,
+        max: 86_400,
+    };
+    let set_bounds = client.try_set_refund_window_bounds(&admin, &bounds);
+    assert!(matches!(set_bounds, Err(Ok(err)) if err == expected_error));
+
+    let set_window = client.try_set_merchant_refund_window(&merchant, &None, &3_600);
+    assert!(matches!(set_window, Err(Ok(err)) if err == expected_error));
+
+    let remove_window = client.try_remove_merchant_refund_window(&merchant, &None);
+    assert!(matches!(remove_window, Err(Ok(err)) if err == expected_error));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_used_nonces_cannot_be_purged_while_paused() {
+    let (env, client, _contract_id, admin) = setup_test();
+    client.pause(&admin);
+
+    client.purge_used_nonces(&Address::generate(&env), &Vec::new(&env));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_tip_config_cannot_change_while_paused() {
+    let (env, client, _contract_id, admin) = setup_test();
+    client.pause(&admin);
+
+    let config = TipConfig {
+        tip_account: None,
+        fee_exempt: true,
+    };
+    client.set_merchant_tip_config(&Address::generate(&env), &config);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_volume_window_cannot_change_while_paused() {
+    let (_env, client, _contract_id, admin) = setup_test();
+    client.pause(&admin);
+
+    client.set_volume_window(&admin, &7);
+}
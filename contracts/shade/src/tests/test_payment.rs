@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::InvoiceStatus;
+use crate::types::{InvoiceOptions, InvoiceStatus};
 use soroban_sdk::testutils::{Address as _, Events, Ledger as _};
 use soroban_sdk::{token, Address, Env, Map, String, Symbol, TryIntoVal, Val};
 
@@ -45,7 +45,16 @@ fn assert_latest_paid_event(
     let events = env.events().all();
     assert!(!events.is_empty(), "No events captured for payment");
 
-    let (event_contract_id, _topics, data) = events.get(events.len() - 1).unwrap();
+    // Routing events can follow the paid event, so look it up by name.
+    let paid_event = Symbol::new(env, "invoice_paid_event");
+    let (event_contract_id, _topics, data) = events
+        .iter()
+        .rev()
+        .find(|(_, topics, _)| {
+            let name: Option<Symbol> = topics.get(0).and_then(|t| t.try_into_val(env).ok());
+            name == Some(paid_event.clone())
+        })
+        .expect("No invoice paid event");
     assert_eq!(&event_contract_id, contract_id);
 
     let data_map: Map<Symbol, Val> = data.try_into_val(env).unwrap();
@@ -81,7 +90,7 @@ fn test_successful_payment_with_fee() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     // Create merchant account (using a regular address as mock)
     let merchant_account = Address::generate(&env);
@@ -89,7 +98,13 @@ fn test_successful_payment_with_fee() {
 
     // Create invoice for 1000 units
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Create customer and mint tokens
     let customer = Address::generate(&env);
@@ -97,7 +112,7 @@ fn test_successful_payment_with_fee() {
     token_client.mint(&customer, &1000);
 
     // Customer pays invoice
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     // event assertion (merchant_id should be 1 for first merchant)
     assert_latest_paid_event(
@@ -114,10 +129,10 @@ fn test_successful_payment_with_fee() {
 
     // Verify balances
     let token_balance_client = token::TokenClient::new(&env, &token);
-    let shade_balance = token_balance_client.balance(&shade_contract_id);
+    let platform_balance = token_balance_client.balance(&shade_client.get_platform_account());
     let merchant_balance = token_balance_client.balance(&merchant_account);
 
-    assert_eq!(shade_balance, 50); // 5% fee = 50 units
+    assert_eq!(platform_balance, 50); // 5% fee = 50 units
     assert_eq!(merchant_balance, 950); // 95% = 950 units
 
     // Verify invoice status
@@ -129,14 +144,14 @@ fn test_successful_payment_with_fee() {
 
 #[test]
 fn test_payment_with_zero_fee() {
-    let (env, shade_client, shade_contract_id, admin, token) = setup_test_with_payment();
+    let (env, shade_client, _shade_contract_id, admin, token) = setup_test_with_payment();
 
     // Set fee to 0 bps (0%)
     shade_client.set_fee(&admin, &token, &0);
 
     // Register merchant
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -144,7 +159,13 @@ fn test_payment_with_zero_fee() {
 
     // Create invoice for 1000 units
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Create customer and mint tokens
     let customer = Address::generate(&env);
@@ -152,14 +173,14 @@ fn test_payment_with_zero_fee() {
     token_client.mint(&customer, &1000);
 
     // Customer pays invoice
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     // Verify balances
     let token_balance_client = token::TokenClient::new(&env, &token);
-    let shade_balance = token_balance_client.balance(&shade_contract_id);
+    let platform_balance = token_balance_client.balance(&shade_client.get_platform_account());
     let merchant_balance = token_balance_client.balance(&merchant_account);
 
-    assert_eq!(shade_balance, 0); // 0% fee = 0 units
+    assert_eq!(platform_balance, 0); // 0% fee = 0 units
     assert_eq!(merchant_balance, 1000); // 100% = 1000 units
 }
 
@@ -181,7 +202,7 @@ fn test_payment_with_zero_fee() {
 
 //     // Create invoice for 1000 units
 //     let description = String::from_str(&env, "Test Invoice");
-//     let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+//     let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None, &None);
 
 //     // Create customer and mint tokens
 //     let customer = Address::generate(&env);
@@ -189,7 +210,7 @@ fn test_payment_with_zero_fee() {
 //     token_client.mint(&customer, &1000);
 
 //     // Customer pays invoice
-//     shade_client.pay_invoice(&customer, &invoice_id);
+//     shade_client.pay_invoice(&customer, &invoice_id, &None);
 
 //     // Verify balances
 //     let token_balance_client = token::TokenClient::new(&env, &token);
@@ -206,22 +227,30 @@ fn test_payment_rejects_expired_invoice() {
     let (env, shade_client, _shade_contract_id, _admin, token) = setup_test_with_payment();
 
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     let merchant_account = Address::generate(&env);
     shade_client.set_merchant_account(&merchant, &merchant_account);
 
     let description = String::from_str(&env, "Expired Invoice");
     let expires_at = 1000_u64;
-    let invoice_id =
-        shade_client.create_invoice(&merchant, &description, &1000, &token, &Some(expires_at));
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions {
+            expires_at: Some(expires_at),
+            ..Default::default()
+        },
+    );
 
     let customer = Address::generate(&env);
     let token_client = token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
 
     env.ledger().set_timestamp(expires_at);
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 }
 
 #[test]
@@ -231,7 +260,7 @@ fn test_payment_invoice_already_paid() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -239,7 +268,13 @@ fn test_payment_invoice_already_paid() {
 
     // Create invoice
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Create customer and mint tokens
     let customer = Address::generate(&env);
@@ -247,10 +282,10 @@ fn test_payment_invoice_already_paid() {
     token_client.mint(&customer, &2000);
 
     // Customer pays invoice first time
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     // Attempt to pay again (should panic with InvalidInvoiceStatus)
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 }
 
 #[test]
@@ -260,7 +295,7 @@ fn test_payment_insufficient_funds() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -268,7 +303,13 @@ fn test_payment_insufficient_funds() {
 
     // Create invoice for 1000 units
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Create customer with insufficient balance (only 500)
     let customer = Address::generate(&env);
@@ -276,7 +317,7 @@ fn test_payment_insufficient_funds() {
     token_client.mint(&customer, &500);
 
     // Customer attempts to pay invoice (should panic due to insufficient funds)
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 }
 
 #[test]
@@ -286,7 +327,7 @@ fn test_payment_token_not_accepted() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -302,7 +343,7 @@ fn test_payment_token_not_accepted() {
         &description,
         &1000,
         &unaccepted_token.address(),
-        &None,
+        &InvoiceOptions::default(),
     );
 
     // Create customer and mint tokens
@@ -311,7 +352,7 @@ fn test_payment_token_not_accepted() {
     token_client.mint(&customer, &1000);
 
     // Customer attempts to pay invoice (should panic - token not accepted)
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 }
 
 #[test]
@@ -321,13 +362,19 @@ fn test_payment_merchant_account_not_set() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     // DO NOT set merchant account - this will cause the panic
 
     // Create invoice
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Create customer and mint tokens
     let customer = Address::generate(&env);
@@ -335,7 +382,7 @@ fn test_payment_merchant_account_not_set() {
     token_client.mint(&customer, &1000);
 
     // Customer attempts to pay invoice (should panic - merchant account not set)
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 }
 
 #[test]
@@ -344,7 +391,7 @@ fn test_payment_payer_authorization() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -352,7 +399,13 @@ fn test_payment_payer_authorization() {
 
     // Create invoice
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Create customer and mint tokens
     let customer = Address::generate(&env);
@@ -360,7 +413,7 @@ fn test_payment_payer_authorization() {
     token_client.mint(&customer, &1000);
 
     // Customer pays invoice (auth is automatically mocked)
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     // Verify payer is recorded in invoice
     let invoice = shade_client.get_invoice(&invoice_id);
@@ -373,7 +426,7 @@ fn test_payment_updates_invoice_timestamps() {
 
     // Register merchant
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -381,7 +434,13 @@ fn test_payment_updates_invoice_timestamps() {
 
     // Create invoice
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Get invoice before payment
     let invoice_before = shade_client.get_invoice(&invoice_id);
@@ -393,7 +452,7 @@ fn test_payment_updates_invoice_timestamps() {
     token_client.mint(&customer, &1000);
 
     // Customer pays invoice
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     // Get invoice after payment
     let invoice_after = shade_client.get_invoice(&invoice_id);
@@ -403,14 +462,14 @@ fn test_payment_updates_invoice_timestamps() {
 
 #[test]
 fn test_fee_calculation_accuracy() {
-    let (env, shade_client, shade_contract_id, admin, token) = setup_test_with_payment();
+    let (env, shade_client, _shade_contract_id, admin, token) = setup_test_with_payment();
 
     // Test with 1% fee (100 bps)
     shade_client.set_fee(&admin, &token, &100);
 
     // Register merchant
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
 
     // Create merchant account
     let merchant_account = Address::generate(&env);
@@ -418,7 +477,13 @@ fn test_fee_calculation_accuracy() {
 
     // Create invoice for 10000 units
     let description = String::from_str(&env, "Test Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &10000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &10000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Create customer and mint tokens
     let customer = Address::generate(&env);
@@ -426,76 +491,94 @@ fn test_fee_calculation_accuracy() {
     token_client.mint(&customer, &10000);
 
     // Customer pays invoice
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     // Verify balances with 1% fee
     let token_balance_client = token::TokenClient::new(&env, &token);
-    let shade_balance = token_balance_client.balance(&shade_contract_id);
+    let platform_balance = token_balance_client.balance(&shade_client.get_platform_account());
     let merchant_balance = token_balance_client.balance(&merchant_account);
 
-    assert_eq!(shade_balance, 100); // 1% of 10000 = 100
+    assert_eq!(platform_balance, 100); // 1% of 10000 = 100
     assert_eq!(merchant_balance, 9900); // 99% of 10000 = 9900
 }
 
 #[test]
 fn test_partial_payment_two_equal_steps_reaches_paid() {
-    let (env, shade_client, shade_contract_id, _admin, token) = setup_test_with_payment();
+    let (env, shade_client, _shade_contract_id, _admin, token) = setup_test_with_payment();
 
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
     let merchant_account = Address::generate(&env);
     shade_client.set_merchant_account(&merchant, &merchant_account);
 
     let description = String::from_str(&env, "Partial Payment Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let customer = Address::generate(&env);
     let token_client = token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
 
-    shade_client.pay_invoice_partial(&customer, &invoice_id, &500);
+    shade_client.pay_invoice_partial(&customer, &invoice_id, &500, &None, &None);
     let mid_invoice = shade_client.get_invoice(&invoice_id);
     assert_eq!(mid_invoice.status, InvoiceStatus::PartiallyPaid);
     assert_eq!(mid_invoice.amount_paid, 500);
     assert!(mid_invoice.date_paid.is_none());
 
-    shade_client.pay_invoice_partial(&customer, &invoice_id, &500);
+    shade_client.pay_invoice_partial(&customer, &invoice_id, &500, &None, &None);
     let final_invoice = shade_client.get_invoice(&invoice_id);
     assert_eq!(final_invoice.status, InvoiceStatus::Paid);
     assert_eq!(final_invoice.amount_paid, 1000);
     assert!(final_invoice.date_paid.is_some());
 
     let token_balance_client = token::TokenClient::new(&env, &token);
-    let shade_balance = token_balance_client.balance(&shade_contract_id);
+    let platform_balance = token_balance_client.balance(&shade_client.get_platform_account());
     let merchant_balance = token_balance_client.balance(&merchant_account);
 
-    assert_eq!(shade_balance, 50);
+    assert_eq!(platform_balance, 50);
     assert_eq!(merchant_balance, 950);
 }
 
 #[test]
 fn test_partial_payment_collects_fees_proportionally_each_step() {
-    let (env, shade_client, shade_contract_id, _admin, token) = setup_test_with_payment();
+    let (env, shade_client, _shade_contract_id, _admin, token) = setup_test_with_payment();
 
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
     let merchant_account = Address::generate(&env);
     shade_client.set_merchant_account(&merchant, &merchant_account);
 
     let description = String::from_str(&env, "Proportional Fee Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let customer = Address::generate(&env);
     let token_client = token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
 
-    shade_client.pay_invoice_partial(&customer, &invoice_id, &500);
+    shade_client.pay_invoice_partial(&customer, &invoice_id, &500, &None, &None);
     let token_balance_client = token::TokenClient::new(&env, &token);
-    assert_eq!(token_balance_client.balance(&shade_contract_id), 25);
+    assert_eq!(
+        token_balance_client.balance(&shade_client.get_platform_account()),
+        25
+    );
     assert_eq!(token_balance_client.balance(&merchant_account), 475);
 
-    shade_client.pay_invoice_partial(&customer, &invoice_id, &500);
-    assert_eq!(token_balance_client.balance(&shade_contract_id), 50);
+    shade_client.pay_invoice_partial(&customer, &invoice_id, &500, &None, &None);
+    assert_eq!(
+        token_balance_client.balance(&shade_client.get_platform_account()),
+        50
+    );
     assert_eq!(token_balance_client.balance(&merchant_account), 950);
 }
 
@@ -505,17 +588,23 @@ fn test_partial_payment_cannot_exceed_requested_amount() {
     let (env, shade_client, _shade_contract_id, _admin, token) = setup_test_with_payment();
 
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
     let merchant_account = Address::generate(&env);
     shade_client.set_merchant_account(&merchant, &merchant_account);
 
     let description = String::from_str(&env, "Overpay Guard Invoice");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let customer = Address::generate(&env);
     let token_client = token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1500);
 
-    shade_client.pay_invoice_partial(&customer, &invoice_id, &700);
-    shade_client.pay_invoice_partial(&customer, &invoice_id, &400);
+    shade_client.pay_invoice_partial(&customer, &invoice_id, &700, &None, &None);
+    shade_client.pay_invoice_partial(&customer, &invoice_id, &400, &None, &None);
 }
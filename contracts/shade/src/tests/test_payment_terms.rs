@@ -0,0 +1,273 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::{InvoiceOptions, InvoiceStatus, PaymentTerms, PaymentTermsData};
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+use soroban_sdk::{Address, Env, Map, String, Symbol, TryIntoVal, Val};
+
+fn setup_test() -> ShadeFixture {
+    let s = ShadeFixture::setup();
+    s.env.ledger().set_timestamp(1_000);
+    s
+}
+
+/// "2/10 net 30" with a 5% late penalty, counted from t = 1_000.
+fn two_ten_net_thirty() -> PaymentTerms {
+    PaymentTerms {
+        discount_bps: 200,
+        discount_deadline: 11_000,
+        penalty_bps: 500,
+        due_date: 31_000,
+    }
+}
+
+fn create_invoice_with_terms(s: &ShadeFixture) -> u64 {
+    let invoice_id = s.create_invoice(10_000);
+    s.client
+        .set_invoice_terms(&s.merchant, &invoice_id, &Some(two_ten_net_thirty()));
+    invoice_id
+}
+
+fn paid_event_adjustment(env: &Env) -> (i128, i128) {
+    let events = env.events().all();
+    for i in (0..events.len()).rev() {
+        let (_, _, data) = events.get(i).unwrap();
+        let data_map: Map<Symbol, Val> = match data.try_into_val(env) {
+            Ok(data_map) => data_map,
+            Err(_) => continue,
+        };
+        if let Some(discount) = data_map.get(Symbol::new(env, "discount")) {
+            let penalty = data_map.get(Symbol::new(env, "penalty")).unwrap();
+            return (
+                discount.try_into_val(env).unwrap(),
+                penalty.try_into_val(env).unwrap(),
+            );
+        }
+    }
+    panic!("no paid event");
+}
+
+#[test]
+fn test_early_payment_earns_discount() {
+    let s = setup_test();
+    let invoice_id = create_invoice_with_terms(&s);
+    assert_eq!(s.client.resolve_invoice_amount(&invoice_id), 9_800);
+
+    let payer = s.funded_payer(10_000);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    assert_eq!(paid_event_adjustment(&s.env), (200, 0));
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount, 9_800);
+    assert_eq!(invoice.discount_amount, 200);
+    assert_eq!(invoice.penalty_amount, 0);
+    assert_eq!(s.balance(&payer), 200);
+    assert_eq!(s.balance(&s.merchant_account), 9_800);
+}
+
+#[test]
+fn test_payment_within_terms_pays_face_value() {
+    let s = setup_test();
+    let invoice_id = create_invoice_with_terms(&s);
+    s.env.ledger().set_timestamp(20_000);
+
+    let payer = s.funded_payer(10_000);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.amount, 10_000);
+    assert_eq!(invoice.discount_amount, 0);
+    assert_eq!(invoice.penalty_amount, 0);
+}
+
+#[test]
+fn test_late_payment_charges_penalty() {
+    let s = setup_test();
+    let invoice_id = create_invoice_with_terms(&s);
+    s.env.ledger().set_timestamp(40_000);
+    assert_eq!(s.client.resolve_invoice_amount(&invoice_id), 10_500);
+
+    let payer = s.funded_payer(10_500);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    assert_eq!(paid_event_adjustment(&s.env), (0, 500));
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount, 10_500);
+    assert_eq!(invoice.penalty_amount, 500);
+    assert_eq!(s.balance(&s.merchant_account), 10_500);
+}
+
+#[test]
+fn test_partial_payment_forfeits_discount() {
+    let s = setup_test();
+    let invoice_id = create_invoice_with_terms(&s);
+
+    let payer = s.funded_payer(10_000);
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &4_000, &None, &None);
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.amount, 10_000);
+    assert_eq!(invoice.discount_amount, 0);
+
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount_paid, 10_000);
+}
+
+#[test]
+fn test_late_balance_is_penalised_after_early_partial_payment() {
+    let s = setup_test();
+    let invoice_id = create_invoice_with_terms(&s);
+    let payer = s.funded_payer(10_300);
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &4_000, &None, &None);
+
+    // 5% of the 6_000 still outstanding at the due date.
+    s.env.ledger().set_timestamp(40_000);
+    assert_eq!(s.client.resolve_invoice_amount(&invoice_id), 10_300);
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &1_000, &None, &None);
+    assert_eq!(paid_event_adjustment(&s.env), (0, 300));
+
+    // The penalty is only charged once.
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    assert_eq!(paid_event_adjustment(&s.env), (0, 0));
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount, 10_300);
+    assert_eq!(invoice.penalty_amount, 300);
+    assert_eq!(invoice.amount_paid, 10_300);
+}
+
+#[test]
+fn test_setting_terms_emits_event() {
+    let s = setup_test();
+    let invoice_id = create_invoice_with_terms(&s);
+
+    let event = s.env.events().all().last().unwrap();
+    let data: Map<Symbol, Val> = event.2.try_into_val(&s.env).unwrap();
+    let penalty_bps: u32 = data
+        .get(Symbol::new(&s.env, "penalty_bps"))
+        .unwrap()
+        .try_into_val(&s.env)
+        .unwrap();
+    let due_date: u64 = data
+        .get(Symbol::new(&s.env, "due_date"))
+        .unwrap()
+        .try_into_val(&s.env)
+        .unwrap();
+    assert_eq!((penalty_bps, due_date), (500, 31_000));
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).payment_terms,
+        PaymentTermsData::Some(two_ten_net_thirty())
+    );
+}
+
+#[test]
+fn test_terms_can_be_set_at_creation() {
+    let s = setup_test();
+    let invoice_id = s.client.create_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Consulting"),
+        &10_000,
+        &s.token,
+        &InvoiceOptions {
+            terms: PaymentTermsData::Some(two_ten_net_thirty()),
+            ..Default::default()
+        },
+    );
+
+    let events = s.env.events().all();
+    let terms_event = (0..events.len()).find_map(|i| {
+        let data: Map<Symbol, Val> = events.get(i).unwrap().2.try_into_val(&s.env).ok()?;
+        data.get(Symbol::new(&s.env, "discount_bps"))
+    });
+    let discount_bps: u32 = terms_event.unwrap().try_into_val(&s.env).unwrap();
+    assert_eq!(discount_bps, 200);
+    assert_eq!(s.client.resolve_invoice_amount(&invoice_id), 9_800);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #107)")]
+fn test_invalid_terms_rejected_at_creation() {
+    let s = setup_test();
+    let mut terms = two_ten_net_thirty();
+    terms.discount_bps = 10_000;
+    s.client.create_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Consulting"),
+        &10_000,
+        &s.token,
+        &InvoiceOptions {
+            terms: PaymentTermsData::Some(terms),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_discount_leaves_tax_untouched() {
+    let s = setup_test();
+    let code = String::from_str(&s.env, "VAT");
+    let tax_account = Address::generate(&s.env);
+    s.client.set_merchant_tax_account(&s.merchant, &tax_account);
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &1_000);
+
+    let invoice_id = create_invoice_with_terms(&s);
+    s.client.apply_invoice_tax(&s.merchant, &invoice_id, &code);
+
+    let payer = s.funded_payer(11_000);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.amount, 10_800);
+    assert_eq!(invoice.tax_amount, 1_000);
+    assert_eq!(s.balance(&tax_account), 1_000);
+    assert_eq!(s.balance(&s.merchant_account), 9_800);
+}
+
+#[test]
+fn test_clearing_terms_restores_face_value() {
+    let s = setup_test();
+    let invoice_id = create_invoice_with_terms(&s);
+    s.client.set_invoice_terms(&s.merchant, &invoice_id, &None);
+
+    assert_eq!(s.client.resolve_invoice_amount(&invoice_id), 10_000);
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).payment_terms,
+        PaymentTermsData::None
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #107)")]
+fn test_discount_deadline_after_due_date_rejected() {
+    let s = setup_test();
+    let invoice_id = create_invoice_with_terms(&s);
+
+    let mut terms = two_ten_net_thirty();
+    terms.discount_deadline = 40_000;
+    s.client
+        .set_invoice_terms(&s.merchant, &invoice_id, &Some(terms));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_terms_cannot_change_after_payment() {
+    let s = setup_test();
+    let invoice_id = create_invoice_with_terms(&s);
+
+    let payer = s.funded_payer(10_000);
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &1_000, &None, &None);
+    s.client.set_invoice_terms(&s.merchant, &invoice_id, &None);
+}
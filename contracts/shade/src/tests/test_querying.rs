@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::{InvoiceFilter, InvoiceStatus, MerchantFilter};
+use crate::types::{InvoiceFilter, InvoiceOptions, InvoiceStatus, MerchantFilter};
 use soroban_sdk::testutils::Address as _;
 use soroban_sdk::{Address, Env, String};
 
@@ -54,9 +54,9 @@ fn test_get_merchants_filter_active_only() {
     let m2 = Address::generate(&env);
     let m3 = Address::generate(&env);
 
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
-    client.register_merchant(&m3);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
+    client.register_merchant(&m3, &None);
 
     // deactivate merchant 2
     client.set_merchant_status(&admin, &2u64, &false);
@@ -79,8 +79,8 @@ fn test_get_merchants_filter_inactive_only() {
     let m1 = Address::generate(&env);
     let m2 = Address::generate(&env);
 
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
 
     client.set_merchant_status(&admin, &1u64, &false);
     client.set_merchant_status(&admin, &2u64, &false);
@@ -108,9 +108,9 @@ fn test_get_merchants_filter_verified_only() {
     let m2 = Address::generate(&env);
     let m3 = Address::generate(&env);
 
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
-    client.register_merchant(&m3);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
+    client.register_merchant(&m3, &None);
 
     // verify only merchant 1
     client.verify_merchant(&admin, &1u64, &true);
@@ -132,8 +132,8 @@ fn test_get_merchants_filter_unverified_only() {
     let m1 = Address::generate(&env);
     let m2 = Address::generate(&env);
 
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
 
     client.verify_merchant(&admin, &1u64, &true);
 
@@ -160,10 +160,10 @@ fn test_get_merchants_filter_active_and_verified() {
     let m3 = Address::generate(&env);
     let m4 = Address::generate(&env);
 
-    client.register_merchant(&m1); // active + verified
-    client.register_merchant(&m2); // inactive + verified
-    client.register_merchant(&m3); // active + unverified
-    client.register_merchant(&m4); // inactive + unverified
+    client.register_merchant(&m1, &None); // active + verified
+    client.register_merchant(&m2, &None); // inactive + verified
+    client.register_merchant(&m3, &None); // active + unverified
+    client.register_merchant(&m4, &None); // inactive + unverified
 
     client.verify_merchant(&admin, &1u64, &true);
     client.verify_merchant(&admin, &2u64, &true);
@@ -189,9 +189,9 @@ fn test_get_merchants_filter_active_and_unverified() {
     let m2 = Address::generate(&env);
     let m3 = Address::generate(&env);
 
-    client.register_merchant(&m1); // active, unverified
-    client.register_merchant(&m2); // active, verified
-    client.register_merchant(&m3); // inactive, unverified
+    client.register_merchant(&m1, &None); // active, unverified
+    client.register_merchant(&m2, &None); // active, verified
+    client.register_merchant(&m3, &None); // inactive, unverified
 
     client.verify_merchant(&admin, &2u64, &true);
     client.set_merchant_status(&admin, &3u64, &false);
@@ -213,9 +213,9 @@ fn test_get_merchants_no_filter_returns_all() {
     let m2 = Address::generate(&env);
     let m3 = Address::generate(&env);
 
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
-    client.register_merchant(&m3);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
+    client.register_merchant(&m3, &None);
 
     let result = client.get_merchants(&no_merchant_filter());
     assert_eq!(result.len(), 3);
@@ -231,15 +231,33 @@ fn test_get_invoices_filter_by_merchant() {
 
     let m1 = Address::generate(&env);
     let m2 = Address::generate(&env);
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
 
-    client.create_invoice(&m1, &String::from_str(&env, "Inv A"), &100, &token, &None);
-    client.create_invoice(&m1, &String::from_str(&env, "Inv B"), &200, &token, &None);
-    client.create_invoice(&m2, &String::from_str(&env, "Inv C"), &300, &token, &None);
+    client.create_invoice(
+        &m1,
+        &String::from_str(&env, "Inv A"),
+        &100,
+        &token,
+        &InvoiceOptions::default(),
+    );
+    client.create_invoice(
+        &m1,
+        &String::from_str(&env, "Inv B"),
+        &200,
+        &token,
+        &InvoiceOptions::default(),
+    );
+    client.create_invoice(
+        &m2,
+        &String::from_str(&env, "Inv C"),
+        &300,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let filter = InvoiceFilter {
         merchant: Some(m1.clone()),
@@ -261,7 +279,7 @@ fn test_get_invoices_filter_by_status_pending() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -271,14 +289,14 @@ fn test_get_invoices_filter_by_status_pending() {
         &String::from_str(&env, "Inv 1"),
         &100,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "Inv 2"),
         &200,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     let filter = InvoiceFilter {
@@ -297,7 +315,7 @@ fn test_get_invoices_filter_by_status_cancelled() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -307,14 +325,14 @@ fn test_get_invoices_filter_by_status_cancelled() {
         &String::from_str(&env, "Inv 1"),
         &100,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "Inv 2"),
         &200,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     client.void_invoice(&merchant, &id1);
@@ -338,7 +356,7 @@ fn test_get_invoices_filter_by_min_amount() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -348,21 +366,21 @@ fn test_get_invoices_filter_by_min_amount() {
         &String::from_str(&env, "Cheap"),
         &50,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "Mid"),
         &150,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "Expensive"),
         &500,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     let filter = InvoiceFilter {
@@ -381,7 +399,7 @@ fn test_get_invoices_filter_by_max_amount() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -391,21 +409,21 @@ fn test_get_invoices_filter_by_max_amount() {
         &String::from_str(&env, "Cheap"),
         &50,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "Mid"),
         &150,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "Expensive"),
         &500,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     let filter = InvoiceFilter {
@@ -424,32 +442,38 @@ fn test_get_invoices_filter_by_amount_range() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
 
-    client.create_invoice(&merchant, &String::from_str(&env, "10"), &10, &token, &None);
+    client.create_invoice(
+        &merchant,
+        &String::from_str(&env, "10"),
+        &10,
+        &token,
+        &InvoiceOptions::default(),
+    );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "100"),
         &100,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "200"),
         &200,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "500"),
         &500,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     let filter = InvoiceFilter {
@@ -474,8 +498,8 @@ fn test_get_invoices_combined_merchant_and_status() {
 
     let m1 = Address::generate(&env);
     let m2 = Address::generate(&env);
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -485,21 +509,21 @@ fn test_get_invoices_combined_merchant_and_status() {
         &String::from_str(&env, "M1 Active"),
         &100,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &m1,
         &String::from_str(&env, "M1 Voided"),
         &200,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &m2,
         &String::from_str(&env, "M2 Active"),
         &300,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     // void invoice 2 (id = 2)
@@ -523,26 +547,32 @@ fn test_get_invoices_combined_merchant_and_amount_range() {
 
     let m1 = Address::generate(&env);
     let m2 = Address::generate(&env);
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
 
-    client.create_invoice(&m1, &String::from_str(&env, "M1 small"), &50, &token, &None);
+    client.create_invoice(
+        &m1,
+        &String::from_str(&env, "M1 small"),
+        &50,
+        &token,
+        &InvoiceOptions::default(),
+    );
     client.create_invoice(
         &m1,
         &String::from_str(&env, "M1 large"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &m2,
         &String::from_str(&env, "M2 large"),
         &1000,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     let filter = InvoiceFilter {
@@ -562,7 +592,7 @@ fn test_get_invoices_combined_status_and_amount() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -572,14 +602,14 @@ fn test_get_invoices_combined_status_and_amount() {
         &String::from_str(&env, "Small"),
         &50,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     client.create_invoice(
         &merchant,
         &String::from_str(&env, "Large"),
         &500,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     // void small invoice
@@ -614,7 +644,7 @@ fn test_get_merchants_active_filter_no_match() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let m1 = Address::generate(&env);
-    client.register_merchant(&m1);
+    client.register_merchant(&m1, &None);
     // deactivate the only merchant
     client.set_merchant_status(&admin, &1u64, &false);
 
@@ -631,7 +661,7 @@ fn test_get_merchants_verified_filter_no_match() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let m1 = Address::generate(&env);
-    client.register_merchant(&m1);
+    client.register_merchant(&m1, &None);
     // merchant is registered but never verified
 
     let filter = MerchantFilter {
@@ -647,7 +677,7 @@ fn test_get_merchants_combined_filter_no_match() {
     let (env, client, _contract_id, _admin) = setup_test();
 
     let m1 = Address::generate(&env);
-    client.register_merchant(&m1);
+    client.register_merchant(&m1, &None);
     // active=true, verified=false → querying active+verified gives 0
 
     let filter = MerchantFilter {
@@ -672,14 +702,20 @@ fn test_get_invoices_merchant_filter_no_match() {
 
     let m1 = Address::generate(&env);
     let m2 = Address::generate(&env);
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
 
     // only m1 has invoices
-    client.create_invoice(&m1, &String::from_str(&env, "Inv"), &100, &token, &None);
+    client.create_invoice(
+        &m1,
+        &String::from_str(&env, "Inv"),
+        &100,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let filter = InvoiceFilter {
         merchant: Some(m2.clone()),
@@ -694,7 +730,7 @@ fn test_get_invoices_status_filter_no_match() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -704,7 +740,7 @@ fn test_get_invoices_status_filter_no_match() {
         &String::from_str(&env, "Inv"),
         &100,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
     // invoice is Pending; querying Paid returns nothing
 
@@ -721,7 +757,7 @@ fn test_get_invoices_amount_range_no_match() {
     let (env, client, _contract_id, admin) = setup_test();
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
@@ -731,7 +767,7 @@ fn test_get_invoices_amount_range_no_match() {
         &String::from_str(&env, "Inv"),
         &100,
         &token,
-        &None,
+        &InvoiceOptions::default(),
     );
 
     // min > max of existing invoices
@@ -750,13 +786,19 @@ fn test_get_invoices_combined_filters_no_match() {
 
     let m1 = Address::generate(&env);
     let m2 = Address::generate(&env);
-    client.register_merchant(&m1);
-    client.register_merchant(&m2);
+    client.register_merchant(&m1, &None);
+    client.register_merchant(&m2, &None);
 
     let token = create_token(&env);
     client.add_accepted_token(&admin, &token);
 
-    client.create_invoice(&m1, &String::from_str(&env, "M1 Inv"), &100, &token, &None);
+    client.create_invoice(
+        &m1,
+        &String::from_str(&env, "M1 Inv"),
+        &100,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // filter by m2 + high min amount — both conditions exclude the only invoice
     let filter = InvoiceFilter {
@@ -2,10 +2,11 @@
 
 use crate::components::admin as admin_component;
 use crate::components::reentrancy;
+use crate::components::referral as referral_component;
 use crate::errors::ContractError;
 use crate::shade::Shade;
 use crate::shade::ShadeClient;
-use crate::types::DataKey;
+use crate::types::{DataKey, ReferralConfig};
 use soroban_sdk::testutils::Address as _;
 use soroban_sdk::{Address, BytesN, Env, Vec};
 
@@ -195,6 +196,23 @@ fn test_set_fee_blocks_reentrant_call() {
     });
 }
 
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_set_referral_config_blocks_reentrant_call() {
+    let (env, _client, contract_id, admin) = setup_test();
+    let config = ReferralConfig {
+        share_bps: 1_000,
+        duration: 86_400,
+    };
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::ReentrancyStatus, &true);
+        referral_component::set_referral_config(&env, &admin, &config);
+    });
+}
+
 // ── Reentrancy via client try_ methods (verifies exact error code) ───────────
 
 #[test]
@@ -0,0 +1,225 @@
+#![cfg(test)]
+
+use crate::shade::{Shade, ShadeClient};
+use crate::types::{InvoiceOptions, ReferralConfig};
+use soroban_sdk::testutils::{Address as _, Ledger as _};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{vec, Address, Env, String};
+
+const REFERRAL_PERIOD: u64 = 30 * 86_400;
+
+struct ReferralContext<'a> {
+    env: Env,
+    client: ShadeClient<'a>,
+    admin: Address,
+    token: Address,
+    platform: Address,
+    referrer: Address,
+    merchant: Address,
+    payer: Address,
+}
+
+/// 10% platform fee, a fifth of which goes to the referrer for 30 days.
+fn setup() -> ReferralContext<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let shade_id = env.register(Shade, ());
+    let client = ShadeClient::new(&env, &shade_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    client.add_accepted_token(&admin, &token);
+    client.set_fee(&admin, &token, &1000);
+    let platform = Address::generate(&env);
+    client.set_platform_account(&admin, &platform);
+    client.set_referral_config(
+        &admin,
+        &ReferralConfig {
+            share_bps: 2_000,
+            duration: REFERRAL_PERIOD,
+        },
+    );
+
+    let referrer = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    client.register_merchant(&merchant, &Some(referrer.clone()));
+    client.set_merchant_account(&merchant, &Address::generate(&env));
+    client.set_merchant_accepted_tokens(&merchant, &vec![&env, token.clone()]);
+
+    let payer = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&payer, &1_000_000);
+
+    ReferralContext {
+        env,
+        client,
+        admin,
+        token,
+        platform,
+        referrer,
+        merchant,
+        payer,
+    }
+}
+
+fn create_invoice(ctx: &ReferralContext, merchant: &Address, amount: i128) -> u64 {
+    ctx.client.create_invoice(
+        merchant,
+        &String::from_str(&ctx.env, "Referral invoice"),
+        &amount,
+        &ctx.token,
+        &InvoiceOptions::default(),
+    )
+}
+
+fn balance(ctx: &ReferralContext, account: &Address) -> i128 {
+    TokenClient::new(&ctx.env, &ctx.token).balance(account)
+}
+
+#[test]
+fn test_register_merchant_records_referrer() {
+    let ctx = setup();
+
+    let referral = ctx.client.get_merchant_referral(&ctx.merchant).unwrap();
+    assert_eq!(referral.referrer, ctx.referrer);
+    assert_eq!(referral.referred_at, ctx.env.ledger().timestamp());
+
+    let other = Address::generate(&ctx.env);
+    ctx.client.register_merchant(&other, &None);
+    assert_eq!(ctx.client.get_merchant_referral(&other), None);
+}
+
+#[test]
+fn test_partial_payments_share_fee_with_referrer() {
+    let ctx = setup();
+    let invoice_id = create_invoice(&ctx, &ctx.merchant, 1_000);
+
+    ctx.client
+        .pay_invoice_partial(&ctx.payer, &invoice_id, &500, &None, &None);
+    ctx.client
+        .pay_invoice_partial(&ctx.payer, &invoice_id, &500, &None, &None);
+
+    // 100 in fees: 80 to the platform and 20 to the referrer.
+    assert_eq!(balance(&ctx, &ctx.platform), 80);
+    assert_eq!(balance(&ctx, &ctx.referrer), 20);
+    assert_eq!(
+        ctx.client.get_referral_earnings(&ctx.referrer, &ctx.token),
+        20
+    );
+}
+
+#[test]
+fn test_subscription_charge_shares_fee_with_referrer() {
+    let ctx = setup();
+    let plan_id = ctx.client.create_subscription_plan(
+        &ctx.merchant,
+        &String::from_str(&ctx.env, "Monthly"),
+        &ctx.token,
+        &1_000,
+        &86_400,
+    );
+    TokenClient::new(&ctx.env, &ctx.token).approve(
+        &ctx.payer,
+        &ctx.client.address,
+        &10_000,
+        &1_000_000,
+    );
+    let sub_id = ctx.client.subscribe(&ctx.payer, &plan_id);
+
+    ctx.client.charge_subscription(&sub_id);
+
+    assert_eq!(balance(&ctx, &ctx.platform), 80);
+    assert_eq!(balance(&ctx, &ctx.referrer), 20);
+}
+
+#[test]
+fn test_ticket_purchase_shares_fee_with_referrer() {
+    let ctx = setup();
+    let event_id = ctx.client.create_event(
+        &ctx.merchant,
+        &String::from_str(&ctx.env, "Launch party"),
+        &1_000,
+        &ctx.token,
+        &100,
+        &(ctx.env.ledger().timestamp() + 86_400),
+        &0,
+    );
+
+    ctx.client.purchase_ticket(&event_id, &ctx.payer);
+
+    assert_eq!(balance(&ctx, &ctx.platform), 80);
+    assert_eq!(balance(&ctx, &ctx.referrer), 20);
+}
+
+#[test]
+fn test_referral_share_stops_after_period() {
+    let ctx = setup();
+    ctx.env
+        .ledger()
+        .set_timestamp(ctx.env.ledger().timestamp() + REFERRAL_PERIOD);
+    let invoice_id = create_invoice(&ctx, &ctx.merchant, 1_000);
+
+    ctx.client
+        .pay_invoice(&ctx.payer, &invoice_id, &None, &None, &None);
+
+    assert_eq!(balance(&ctx, &ctx.platform), 100);
+    assert_eq!(balance(&ctx, &ctx.referrer), 0);
+    assert_eq!(
+        ctx.client.get_referral_earnings(&ctx.referrer, &ctx.token),
+        0
+    );
+}
+
+#[test]
+fn test_merchant_without_referrer_pays_platform_only() {
+    let ctx = setup();
+    let merchant = Address::generate(&ctx.env);
+    ctx.client.register_merchant(&merchant, &None);
+    ctx.client
+        .set_merchant_account(&merchant, &Address::generate(&ctx.env));
+    let invoice_id = create_invoice(&ctx, &merchant, 1_000);
+
+    ctx.client
+        .pay_invoice(&ctx.payer, &invoice_id, &None, &None, &None);
+
+    assert_eq!(balance(&ctx, &ctx.platform), 100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #205)")]
+fn test_merchant_cannot_refer_itself() {
+    let ctx = setup();
+    let merchant = Address::generate(&ctx.env);
+    ctx.client
+        .register_merchant(&merchant, &Some(merchant.clone()));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #204)")]
+fn test_referral_share_above_fee_rejected() {
+    let ctx = setup();
+    ctx.client.set_referral_config(
+        &ctx.admin,
+        &ReferralConfig {
+            share_bps: 10_001,
+            duration: REFERRAL_PERIOD,
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_non_admin_cannot_set_referral_config() {
+    let ctx = setup();
+    ctx.client.set_referral_config(
+        &ctx.referrer,
+        &ReferralConfig {
+            share_bps: 5_000,
+            duration: REFERRAL_PERIOD,
+        },
+    );
+}
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::InvoiceStatus;
+use crate::types::{InvoiceOptions, InvoiceStatus};
 use account::account::{MerchantAccount, MerchantAccountClient};
 use soroban_sdk::testutils::{Address as _, Ledger as _};
 use soroban_sdk::{token, Address, Env, String};
@@ -11,9 +11,9 @@ use soroban_sdk::{token, Address, Env, String};
 /// mint tokens to the customer, and pay the invoice at the given timestamp.
 ///
 /// Fee is intentionally 0 so the full `invoice.amount` lands in the merchant
-/// account and `refund_invoice` (which refunds the full amount from the
-/// merchant account) can succeed.  Fee-related interactions are covered by a
-/// dedicated test.
+/// account and `refund_invoice` returns all of it.  Refunds only ever return
+/// the merchant's net, so fee and tax interactions are covered by dedicated
+/// tests.
 struct RefundTestContext<'a> {
     env: Env,
     client: ShadeClient<'a>,
@@ -45,7 +45,7 @@ fn setup_paid_invoice(pay_timestamp: u64) -> RefundTestContext<'static> {
 
     // Register merchant + deploy merchant account contract
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_account_id = env.register(MerchantAccount, ());
     let merchant_account = MerchantAccountClient::new(&env, &merchant_account_id);
@@ -55,7 +55,13 @@ fn setup_paid_invoice(pay_timestamp: u64) -> RefundTestContext<'static> {
     // Create invoice
     let amount = 1_000_i128;
     let description = String::from_str(&env, "Refund Test Invoice");
-    let invoice_id = client.create_invoice(&merchant, &description, &amount, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &amount,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Mint tokens to the payer and pay the invoice
     let payer = Address::generate(&env);
@@ -63,7 +69,7 @@ fn setup_paid_invoice(pay_timestamp: u64) -> RefundTestContext<'static> {
     token_mint.mint(&payer, &amount);
 
     env.ledger().set_timestamp(pay_timestamp);
-    client.pay_invoice(&payer, &invoice_id);
+    client.pay_invoice(&payer, &invoice_id, &None, &None, &None);
 
     RefundTestContext {
         env,
@@ -177,7 +183,7 @@ fn test_refund_unauthorized_different_merchant() {
     ctx.env.ledger().set_timestamp(1_000 + 3_600);
 
     let other_merchant = Address::generate(&ctx.env);
-    ctx.client.register_merchant(&other_merchant);
+    ctx.client.register_merchant(&other_merchant, &None);
 
     ctx.client.refund_invoice(&other_merchant, &ctx.invoice_id);
 }
@@ -219,14 +225,20 @@ fn test_refund_pending_invoice_fails() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Never Paid");
-    let invoice_id = client.create_invoice(&merchant, &description, &500, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &500,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     // Invoice is Pending – refund should fail
     client.refund_invoice(&merchant, &invoice_id);
@@ -249,14 +261,20 @@ fn test_refund_cancelled_invoice_fails() {
     client.initialize(&admin);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let token = env
         .register_stellar_asset_contract_v2(Address::generate(&env))
         .address();
     client.add_accepted_token(&admin, &token);
     let description = String::from_str(&env, "Cancel Me");
-    let invoice_id = client.create_invoice(&merchant, &description, &500, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &500,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     client.void_invoice(&merchant, &invoice_id);
 
@@ -333,7 +351,7 @@ fn test_partial_refund_with_fee() {
     client.set_fee(&admin, &token, &500); // 5% fee
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_account_id = env.register(MerchantAccount, ());
     let merchant_account = MerchantAccountClient::new(&env, &merchant_account_id);
@@ -342,31 +360,95 @@ fn test_partial_refund_with_fee() {
 
     let amount = 1_000_i128;
     let description = String::from_str(&env, "Fee Refund");
-    let invoice_id = client.create_invoice(&merchant, &description, &amount, &token, &None);
+    let invoice_id = client.create_invoice(
+        &merchant,
+        &description,
+        &amount,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let payer = Address::generate(&env);
     let token_mint = token::StellarAssetClient::new(&env, &token);
     token_mint.mint(&payer, &amount);
 
     env.ledger().set_timestamp(1_000);
-    client.pay_invoice(&payer, &invoice_id);
+    client.pay_invoice(&payer, &invoice_id, &None, &None, &None);
 
     let tok = token::TokenClient::new(&env, &token);
     let fee = amount * 500 / 10_000; // 50
     let merchant_portion = amount - fee; // 950
 
     assert_eq!(tok.balance(&merchant_account_id), merchant_portion);
-    assert_eq!(tok.balance(&shade_id), fee);
+    assert_eq!(tok.balance(&client.get_platform_account()), fee);
 
-    // Partial refund of exactly the merchant portion
+    // Refunding the merchant portion refunds everything that can be refunded
     env.ledger().set_timestamp(1_000 + 3_600);
     client.refund_invoice_partial(&merchant, &invoice_id, &merchant_portion);
 
     let invoice = client.get_invoice(&invoice_id);
-    assert_eq!(invoice.status, InvoiceStatus::PartiallyRefunded);
+    assert_eq!(invoice.status, InvoiceStatus::Refunded);
     assert_eq!(invoice.amount_refunded, merchant_portion);
 
     assert_eq!(tok.balance(&payer), merchant_portion);
     assert_eq!(tok.balance(&merchant_account_id), 0);
-    assert_eq!(tok.balance(&shade_id), fee); // fee stays with shade
+    assert_eq!(tok.balance(&client.get_platform_account()), fee); // fee stays with the platform
+}
+
+/// Pays a 1_000 invoice carrying 20% tax (1_200 in total) with a 1% platform
+/// fee, so 990 reaches the merchant account.
+fn setup_taxed_invoice_with_fee() -> (RefundTestContext<'static>, Address) {
+    let ctx = setup_paid_invoice(1_000);
+    ctx.client.set_fee(&ctx.admin, &ctx.token, &100);
+    let tax_account = Address::generate(&ctx.env);
+    ctx.client
+        .set_merchant_tax_account(&ctx.merchant, &tax_account);
+    let code = String::from_str(&ctx.env, "UK");
+    ctx.client
+        .set_merchant_tax_rate(&ctx.merchant, &code, &2_000);
+
+    let invoice_id = ctx.client.create_invoice(
+        &ctx.merchant,
+        &String::from_str(&ctx.env, "Taxed"),
+        &1_000,
+        &ctx.token,
+        &InvoiceOptions::default(),
+    );
+    ctx.client
+        .apply_invoice_tax(&ctx.merchant, &invoice_id, &code);
+    token::StellarAssetClient::new(&ctx.env, &ctx.token).mint(&ctx.payer, &1_200);
+    ctx.client
+        .pay_invoice(&ctx.payer, &invoice_id, &None, &None, &None);
+
+    let ctx = RefundTestContext { invoice_id, ..ctx };
+    (ctx, tax_account)
+}
+
+#[test]
+fn test_refund_returns_merchant_net_only() {
+    let (ctx, tax_account) = setup_taxed_invoice_with_fee();
+    let tok = token::TokenClient::new(&ctx.env, &ctx.token);
+    let merchant_balance = tok.balance(&ctx.merchant_account_id);
+
+    ctx.client.refund_invoice(&ctx.merchant, &ctx.invoice_id);
+
+    // Tax and the platform fee were paid out and stay where they went.
+    assert_eq!(tok.balance(&ctx.payer), 990);
+    assert_eq!(
+        tok.balance(&ctx.merchant_account_id),
+        merchant_balance - 990
+    );
+    assert_eq!(tok.balance(&tax_account), 200);
+
+    let invoice = ctx.client.get_invoice(&ctx.invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Refunded);
+    assert_eq!(invoice.amount_refunded, 990);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_partial_refund_above_merchant_net_rejected() {
+    let (ctx, _tax_account) = setup_taxed_invoice_with_fee();
+    ctx.client
+        .refund_invoice_partial(&ctx.merchant, &ctx.invoice_id, &991);
 }
@@ -0,0 +1,190 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::{InvoiceStatus, RefundWindowBounds};
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+use soroban_sdk::{Address, Env, Map, Symbol, TryFromVal, TryIntoVal, Val};
+
+const DAY: u64 = 86_400;
+
+fn setup_test() -> ShadeFixture {
+    let s = ShadeFixture::setup();
+    s.env.ledger().set_timestamp(1_000);
+    s
+}
+
+fn create_paid_invoice(s: &ShadeFixture) -> u64 {
+    let invoice_id = s.create_invoice(1_000);
+    let payer = s.funded_payer(1_000);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    invoice_id
+}
+
+#[test]
+fn test_default_window_is_seven_days() {
+    let s = setup_test();
+    assert_eq!(s.client.get_refund_window(&1, &s.token), 7 * DAY);
+    assert_eq!(
+        s.client.get_refund_window_bounds(),
+        RefundWindowBounds {
+            min: 0,
+            max: 90 * DAY,
+        }
+    );
+}
+
+#[test]
+fn test_extended_window_allows_later_refund() {
+    let s = setup_test();
+    s.client
+        .set_merchant_refund_window(&s.merchant, &None, &(30 * DAY));
+    let invoice_id = create_paid_invoice(&s);
+
+    s.env.ledger().set_timestamp(1_000 + 20 * DAY);
+    s.client.refund_invoice(&s.merchant, &invoice_id);
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Refunded
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #17)")]
+fn test_zero_window_disables_refunds() {
+    let s = setup_test();
+    s.client.set_merchant_refund_window(&s.merchant, &None, &0);
+    let invoice_id = create_paid_invoice(&s);
+
+    s.client
+        .refund_invoice_partial(&s.merchant, &invoice_id, &100);
+}
+
+#[test]
+fn test_token_rule_overrides_merchant_window() {
+    let s = setup_test();
+    s.client
+        .set_merchant_refund_window(&s.merchant, &None, &(2 * DAY));
+    s.client
+        .set_merchant_refund_window(&s.merchant, &Some(s.token.clone()), &(14 * DAY));
+    assert_eq!(s.client.get_refund_window(&1, &s.token), 14 * DAY);
+
+    let other_token = Address::generate(&s.env);
+    assert_eq!(s.client.get_refund_window(&1, &other_token), 2 * DAY);
+
+    s.client
+        .remove_merchant_refund_window(&s.merchant, &Some(s.token.clone()));
+    assert_eq!(s.client.get_refund_window(&1, &s.token), 2 * DAY);
+}
+
+#[test]
+fn test_window_clamped_when_bounds_tighten() {
+    let s = setup_test();
+    s.client
+        .set_merchant_refund_window(&s.merchant, &None, &(60 * DAY));
+
+    s.client.set_refund_window_bounds(
+        &s.admin,
+        &RefundWindowBounds {
+            min: DAY,
+            max: 30 * DAY,
+        },
+    );
+    assert_eq!(s.client.get_refund_window(&1, &s.token), 30 * DAY);
+}
+
+#[test]
+fn test_refund_after_merchant_window_rejected() {
+    let s = setup_test();
+    s.client
+        .set_merchant_refund_window(&s.merchant, &None, &DAY);
+    let invoice_id = create_paid_invoice(&s);
+
+    s.env.ledger().set_timestamp(1_000 + DAY + 1);
+    assert!(s
+        .client
+        .try_refund_invoice_partial(&s.merchant, &invoice_id, &100)
+        .is_err());
+    assert!(s
+        .client
+        .try_refund_invoice(&s.merchant, &invoice_id)
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #113)")]
+fn test_window_above_admin_bound_rejected() {
+    let s = setup_test();
+    s.client
+        .set_merchant_refund_window(&s.merchant, &None, &(91 * DAY));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #113)")]
+fn test_inverted_bounds_rejected() {
+    let s = setup_test();
+    s.client.set_refund_window_bounds(
+        &s.admin,
+        &RefundWindowBounds {
+            min: 10 * DAY,
+            max: DAY,
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_non_admin_cannot_set_bounds() {
+    let s = setup_test();
+    s.client.set_refund_window_bounds(
+        &Address::generate(&s.env),
+        &RefundWindowBounds { min: 0, max: DAY },
+    );
+}
+
+fn latest_event_field<T: TryFromVal<Env, Val>>(env: &Env, name: &str, field: &str) -> T {
+    let event = env.events().all().last().unwrap();
+    let topic: Symbol = event.1.get(0).unwrap().try_into_val(env).unwrap();
+    assert_eq!(topic, Symbol::new(env, name));
+    let data: Map<Symbol, Val> = event.2.try_into_val(env).unwrap();
+    data.get(Symbol::new(env, field))
+        .unwrap()
+        .try_into_val(env)
+        .unwrap()
+}
+
+#[test]
+fn test_setting_bounds_emits_event() {
+    let s = setup_test();
+    s.client.set_refund_window_bounds(
+        &s.admin,
+        &RefundWindowBounds {
+            min: DAY,
+            max: 30 * DAY,
+        },
+    );
+
+    let name = "refund_window_bounds_set_event";
+    let min: u64 = latest_event_field(&s.env, name, "min");
+    let max: u64 = latest_event_field(&s.env, name, "max");
+    assert_eq!((min, max), (DAY, 30 * DAY));
+}
+
+#[test]
+fn test_setting_and_removing_merchant_window_emits_events() {
+    let s = setup_test();
+    let token = Some(s.token.clone());
+
+    s.client
+        .set_merchant_refund_window(&s.merchant, &token, &(14 * DAY));
+    let name = "merchant_refund_window_set_event";
+    let window: u64 = latest_event_field(&s.env, name, "window");
+    let event_token: Option<Address> = latest_event_field(&s.env, name, "token");
+    assert_eq!(window, 14 * DAY);
+    assert_eq!(event_token, token);
+
+    s.client.remove_merchant_refund_window(&s.merchant, &token);
+    let name = "refund_window_removed_event";
+    let merchant: Address = latest_event_field(&s.env, name, "merchant");
+    assert_eq!(merchant, s.merchant);
+}
@@ -0,0 +1,203 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::{InvoiceStatus, RefundRequestStatus, Role};
+use soroban_sdk::testutils::{Address as _, Ledger as _};
+use soroban_sdk::{Address, BytesN, Env};
+
+/// A payer settles a 1_000 invoice with the fixture merchant.
+fn setup_test() -> (ShadeFixture, Address, u64) {
+    let s = ShadeFixture::setup();
+    s.env.ledger().set_timestamp(1_000);
+    let invoice_id = s.create_invoice(1_000);
+    let payer = s.funded_payer(1_000);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    (s, payer, invoice_id)
+}
+
+fn reason(env: &Env) -> BytesN<32> {
+    BytesN::from_array(env, &[7; 32])
+}
+
+#[test]
+fn test_merchant_accepts_refund_request() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client
+        .request_refund(&payer, &invoice_id, &400, &reason(&s.env));
+
+    let request = s.client.get_refund_request(&invoice_id);
+    assert_eq!(request.status, RefundRequestStatus::Requested);
+    assert_eq!(request.amount, 400);
+    assert_eq!(request.respond_by, 1_000 + 259_200);
+
+    s.client
+        .respond_to_refund_request(&s.merchant, &invoice_id, &true);
+
+    let request = s.client.get_refund_request(&invoice_id);
+    assert_eq!(request.status, RefundRequestStatus::Accepted);
+    assert_eq!(request.resolved_at, Some(1_000));
+    assert_eq!(s.balance(&payer), 400);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::PartiallyRefunded);
+    assert_eq!(invoice.amount_refunded, 400);
+}
+
+#[test]
+fn test_rejected_request_approved_by_admin() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client
+        .request_refund(&payer, &invoice_id, &1_000, &reason(&s.env));
+    s.client
+        .respond_to_refund_request(&s.merchant, &invoice_id, &false);
+    assert_eq!(
+        s.client.get_refund_request(&invoice_id).status,
+        RefundRequestStatus::Rejected
+    );
+
+    s.client
+        .arbitrate_refund_request(&s.admin, &invoice_id, &true);
+
+    assert_eq!(
+        s.client.get_refund_request(&invoice_id).status,
+        RefundRequestStatus::Approved
+    );
+    assert_eq!(s.balance(&payer), 1_000);
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Refunded
+    );
+}
+
+#[test]
+fn test_manager_rules_on_unanswered_request() {
+    let (s, payer, invoice_id) = setup_test();
+    let manager = Address::generate(&s.env);
+    s.client.grant_role(&s.admin, &manager, &Role::Manager);
+
+    s.client
+        .request_refund(&payer, &invoice_id, &300, &reason(&s.env));
+
+    // The merchant still has time to answer.
+    assert!(s
+        .client
+        .try_arbitrate_refund_request(&manager, &invoice_id, &false)
+        .is_err());
+
+    s.env.ledger().set_timestamp(1_000 + 259_201);
+    s.client
+        .arbitrate_refund_request(&manager, &invoice_id, &false);
+
+    let request = s.client.get_refund_request(&invoice_id);
+    assert_eq!(request.status, RefundRequestStatus::Denied);
+    assert_eq!(s.balance(&payer), 0);
+
+    // A resolved request makes way for a new one.
+    s.client
+        .request_refund(&payer, &invoice_id, &300, &reason(&s.env));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_only_payer_can_request_refund() {
+    let (s, _, invoice_id) = setup_test();
+    let stranger = Address::generate(&s.env);
+    s.client
+        .request_refund(&stranger, &invoice_id, &100, &reason(&s.env));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_arbiter_needs_manager_role() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client
+        .request_refund(&payer, &invoice_id, &100, &reason(&s.env));
+    s.client
+        .respond_to_refund_request(&s.merchant, &invoice_id, &false);
+
+    let operator = Address::generate(&s.env);
+    s.client.grant_role(&s.admin, &operator, &Role::Operator);
+    s.client
+        .arbitrate_refund_request(&operator, &invoice_id, &true);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #111)")]
+fn test_second_request_rejected_while_first_open() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client
+        .request_refund(&payer, &invoice_id, &100, &reason(&s.env));
+    s.client
+        .request_refund(&payer, &invoice_id, &200, &reason(&s.env));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #112)")]
+fn test_merchant_cannot_respond_twice() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client
+        .request_refund(&payer, &invoice_id, &100, &reason(&s.env));
+    s.client
+        .respond_to_refund_request(&s.merchant, &invoice_id, &false);
+    s.client
+        .respond_to_refund_request(&s.merchant, &invoice_id, &true);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_request_above_refundable_amount_rejected() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client
+        .request_refund(&payer, &invoice_id, &1_001, &reason(&s.env));
+}
+
+/// A 1_000 split bill paid 600 by `first` and 400 by `second`.
+fn split_bill_invoice(s: &ShadeFixture) -> (u64, Address, Address) {
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .set_invoice_split_bill(&s.merchant, &invoice_id, &true);
+    let first = s.funded_payer(600);
+    let second = s.funded_payer(400);
+    s.client
+        .pay_invoice_partial(&first, &invoice_id, &600, &None, &None);
+    s.client
+        .pay_invoice_partial(&second, &invoice_id, &400, &None, &None);
+    (invoice_id, first, second)
+}
+
+#[test]
+fn test_split_bill_payer_refund_request_refunds_only_them() {
+    let (s, _, _) = setup_test();
+    let (invoice_id, first, second) = split_bill_invoice(&s);
+
+    s.client
+        .request_refund(&second, &invoice_id, &300, &reason(&s.env));
+    assert_eq!(s.client.get_refund_request(&invoice_id).payer, second);
+    s.client
+        .respond_to_refund_request(&s.merchant, &invoice_id, &true);
+
+    assert_eq!(s.balance(&first), 0);
+    assert_eq!(s.balance(&second), 300);
+    let contributions = s.client.get_invoice_contributions(&invoice_id);
+    assert_eq!(contributions.get_unchecked(0).amount_refunded, 0);
+    assert_eq!(contributions.get_unchecked(1).amount_refunded, 300);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_split_bill_request_capped_at_own_contribution() {
+    let (s, _, _) = setup_test();
+    let (invoice_id, _, second) = split_bill_invoice(&s);
+    s.client
+        .request_refund(&second, &invoice_id, &401, &reason(&s.env));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_non_contributor_cannot_request_split_bill_refund() {
+    let (s, payer, _) = setup_test();
+    let (invoice_id, _, _) = split_bill_invoice(&s);
+    s.client
+        .request_refund(&payer, &invoice_id, &100, &reason(&s.env));
+}
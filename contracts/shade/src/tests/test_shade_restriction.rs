@@ -23,7 +23,7 @@ fn setup() -> (Env, ShadeClient<'static>, Address, Address, Address) {
     client.grant_role(&admin, &manager, &Role::Manager);
 
     // Register Merchant
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     // Deploy MerchantAccount mock (using real contract)
     let acct_id = env.register(account::account::MerchantAccount, ());
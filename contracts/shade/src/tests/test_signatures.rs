@@ -1,13 +1,18 @@
 #![cfg(test)]
 extern crate alloc;
 
+use crate::components::signature_util::MAX_SIGNATURE_LIFETIME;
 use crate::shade::{Shade, ShadeClient};
-use crate::types::Role;
+use crate::tests::test_fiat_pricing::{MockOracle, MockOracleClient};
+use crate::types::{
+    DataKey, FiatPricing, FiatPricingData, InvoicePricingMode, InvoiceStatus, OracleConfig, Role,
+    SignedInvoicePayload,
+};
 use ed25519_dalek::{Signer, SigningKey};
 use rand::rngs::OsRng;
-use soroban_sdk::testutils::Address as _;
+use soroban_sdk::testutils::{Address as _, Ledger as _};
 use soroban_sdk::xdr::ToXdr;
-use soroban_sdk::{Address, Bytes, BytesN, Env, String};
+use soroban_sdk::{Address, Bytes, BytesN, Env, String, Vec};
 
 // ---------------------------------------------------------------------------
 // Helpers
@@ -37,30 +42,41 @@ fn generate_keypair() -> TestKeypair {
     }
 }
 
-/// Build the same message that `signature_util::build_message` constructs.
-///
-/// Format: [contract_address, merchant_address, nonce, amount, token_address, description_bytes]
-fn build_test_message(
-    env: &Env,
-    contract_id: &Address,
+/// Payload for a token-priced invoice whose signature stays valid for the
+/// whole test.
+fn invoice_payload(
     merchant: &Address,
     description: &String,
     amount: i128,
     token: &Address,
     nonce: &BytesN<32>,
+) -> SignedInvoicePayload {
+    SignedInvoicePayload {
+        version: 1,
+        merchant: merchant.clone(),
+        description: description.clone(),
+        amount,
+        token: token.clone(),
+        payer: None,
+        expires_at: None,
+        fiat_pricing: FiatPricingData::None,
+        nonce: nonce.clone(),
+        deadline: 1_000_000,
+    }
+}
+
+/// Build the same message that `signature_util::build_message` constructs.
+///
+/// Format: [domain tag, contract_address, network_id, payload XDR]
+fn build_test_message(
+    env: &Env,
+    contract_id: &Address,
+    payload: &SignedInvoicePayload,
 ) -> alloc::vec::Vec<u8> {
-    let mut msg = Bytes::new(env);
+    let mut msg = Bytes::from_slice(env, b"shade:signed-invoice");
     msg.append(&contract_id.clone().to_xdr(env));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&merchant.clone().to_xdr(env));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(nonce.as_ref());
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&Bytes::from_slice(env, &amount.to_be_bytes()));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&token.clone().to_xdr(env));
-    msg.append(&Bytes::from_array(env, b"|"));
-    msg.append(&description.clone().to_xdr(env));
+    msg.append(env.ledger().network_id().as_ref());
+    msg.append(&payload.clone().to_xdr(env));
 
     let mut result = alloc::vec![0u8; msg.len() as usize];
     for i in 0..msg.len() {
@@ -69,6 +85,17 @@ fn build_test_message(
     result
 }
 
+fn sign_payload(
+    env: &Env,
+    contract_id: &Address,
+    keypair: &TestKeypair,
+    payload: &SignedInvoicePayload,
+) -> BytesN<64> {
+    let message = build_test_message(env, contract_id, payload);
+    let sig = keypair.signing_key.sign(&message);
+    BytesN::from_array(env, &sig.to_bytes())
+}
+
 #[allow(clippy::too_many_arguments)]
 fn sign_invoice(
     env: &Env,
@@ -80,17 +107,8 @@ fn sign_invoice(
     token: &Address,
     nonce: &BytesN<32>,
 ) -> BytesN<64> {
-    let message = build_test_message(
-        env,
-        contract_id,
-        merchant,
-        description,
-        amount,
-        token,
-        nonce,
-    );
-    let sig = keypair.signing_key.sign(&message);
-    BytesN::from_array(env, &sig.to_bytes())
+    let payload = invoice_payload(merchant, description, amount, token, nonce);
+    sign_payload(env, contract_id, keypair, &payload)
 }
 
 fn create_nonce(env: &Env, seed: u8) -> BytesN<32> {
@@ -116,7 +134,7 @@ fn test_valid_signature() {
     client.grant_role(&admin, &manager, &Role::Manager);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -143,11 +161,7 @@ fn test_valid_signature() {
 
     let invoice_id = client.create_invoice_signed(
         &manager,
-        &merchant,
-        &description,
-        &amount,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, amount, &token, &nonce),
         &signature,
     );
 
@@ -169,7 +183,7 @@ fn test_invalid_signature_tampered_amount() {
     client.grant_role(&admin, &manager, &Role::Manager);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -199,11 +213,7 @@ fn test_invalid_signature_tampered_amount() {
     // Submit with the tampered amount → must panic
     client.create_invoice_signed(
         &manager,
-        &merchant,
-        &description,
-        &tampered_amount,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, tampered_amount, &token, &nonce),
         &signature,
     );
 }
@@ -218,7 +228,7 @@ fn test_invalid_signature_tampered_description() {
     client.grant_role(&admin, &manager, &Role::Manager);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -248,11 +258,7 @@ fn test_invalid_signature_tampered_description() {
     // Submit with the tampered description → must panic
     client.create_invoice_signed(
         &manager,
-        &merchant,
-        &tampered_desc,
-        &amount,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &tampered_desc, amount, &token, &nonce),
         &signature,
     );
 }
@@ -267,7 +273,7 @@ fn test_replay_attack_same_nonce() {
     client.grant_role(&admin, &manager, &Role::Manager);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let keypair = generate_keypair();
     let pub_key = BytesN::from_array(&env, &keypair.public_key_bytes);
@@ -295,22 +301,14 @@ fn test_replay_attack_same_nonce() {
     // First call succeeds
     client.create_invoice_signed(
         &manager,
-        &merchant,
-        &description,
-        &amount,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, amount, &token, &nonce),
         &signature,
     );
 
     // Second call with the same nonce → NonceAlreadyUsed (#14)
     client.create_invoice_signed(
         &manager,
-        &merchant,
-        &description,
-        &amount,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, amount, &token, &nonce),
         &signature,
     );
 }
@@ -326,8 +324,8 @@ fn test_wrong_merchant_signature() {
 
     let merchant_a = Address::generate(&env);
     let merchant_b = Address::generate(&env);
-    client.register_merchant(&merchant_a);
-    client.register_merchant(&merchant_b);
+    client.register_merchant(&merchant_a, &None);
+    client.register_merchant(&merchant_b, &None);
 
     let keypair_a = generate_keypair();
     let keypair_b = generate_keypair();
@@ -363,11 +361,7 @@ fn test_wrong_merchant_signature() {
     // Submit for Merchant B using Merchant A's signature → crypto error
     client.create_invoice_signed(
         &manager,
-        &merchant_b,
-        &description,
-        &amount,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant_b, &description, amount, &token, &nonce),
         &signature_a,
     );
 }
@@ -383,7 +377,7 @@ fn test_no_public_key() {
     client.grant_role(&admin, &manager, &Role::Manager);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
     // Deliberately do NOT set a merchant key
 
     let token = env
@@ -400,11 +394,7 @@ fn test_no_public_key() {
 
     client.create_invoice_signed(
         &manager,
-        &merchant,
-        &description,
-        &amount,
-        &token,
-        &nonce,
+        &invoice_payload(&merchant, &description, amount, &token, &nonce),
         &signature,
     );
 }
@@ -420,8 +410,8 @@ fn test_nonce_independence_per_merchant() {
 
     let merchant_a = Address::generate(&env);
     let merchant_b = Address::generate(&env);
-    client.register_merchant(&merchant_a);
-    client.register_merchant(&merchant_b);
+    client.register_merchant(&merchant_a, &None);
+    client.register_merchant(&merchant_b, &None);
 
     let keypair_a = generate_keypair();
     let keypair_b = generate_keypair();
@@ -457,11 +447,7 @@ fn test_nonce_independence_per_merchant() {
     );
     let id_a = client.create_invoice_signed(
         &manager,
-        &merchant_a,
-        &description,
-        &amount,
-        &token,
-        &shared_nonce,
+        &invoice_payload(&merchant_a, &description, amount, &token, &shared_nonce),
         &sig_a,
     );
 
@@ -478,14 +464,287 @@ fn test_nonce_independence_per_merchant() {
     );
     let id_b = client.create_invoice_signed(
         &manager,
-        &merchant_b,
-        &description,
-        &amount,
-        &token,
-        &shared_nonce,
+        &invoice_payload(&merchant_b, &description, amount, &token, &shared_nonce),
         &sig_b,
     );
 
     assert!(id_a > 0);
     assert!(id_b > id_a);
 }
+
+/// Registers a manager, a keyed merchant and an accepted token.
+fn setup_signer(
+    env: &Env,
+    client: &ShadeClient,
+    admin: &Address,
+) -> (Address, Address, TestKeypair, Address) {
+    let manager = Address::generate(env);
+    client.grant_role(admin, &manager, &Role::Manager);
+
+    let merchant = Address::generate(env);
+    client.register_merchant(&merchant, &None);
+    let keypair = generate_keypair();
+    client.set_merchant_key(
+        &merchant,
+        &BytesN::from_array(env, &keypair.public_key_bytes),
+    );
+
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    client.add_accepted_token(admin, &token);
+    (manager, merchant, keypair, token)
+}
+
+/// Test 8: A signature presented after its deadline is rejected.
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #115)")]
+fn test_signature_past_deadline_rejected() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (manager, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+
+    let description = String::from_str(&env, "Late");
+    let mut payload = invoice_payload(
+        &merchant,
+        &description,
+        1000,
+        &token,
+        &create_nonce(&env, 1),
+    );
+    payload.deadline = 500;
+    let signature = sign_payload(&env, &contract_id, &keypair, &payload);
+
+    env.ledger().set_timestamp(501);
+    client.create_invoice_signed(&manager, &payload, &signature);
+}
+
+/// Test 9: Payloads with an unknown layout version are refused.
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #114)")]
+fn test_unsupported_payload_version_rejected() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (manager, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+
+    let description = String::from_str(&env, "Future");
+    let mut payload = invoice_payload(
+        &merchant,
+        &description,
+        1000,
+        &token,
+        &create_nonce(&env, 1),
+    );
+    payload.version = 2;
+    let signature = sign_payload(&env, &contract_id, &keypair, &payload);
+
+    client.create_invoice_signed(&manager, &payload, &signature);
+}
+
+/// Test 10: A signature made for another deployment does not verify here.
+#[test]
+#[should_panic(expected = "HostError: Error(Crypto, InvalidInput)")]
+fn test_signature_for_other_contract_rejected() {
+    let (env, client, _contract_id, admin) = setup_test();
+    let (manager, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+
+    let description = String::from_str(&env, "Elsewhere");
+    let payload = invoice_payload(
+        &merchant,
+        &description,
+        1000,
+        &token,
+        &create_nonce(&env, 1),
+    );
+    let other_contract = Address::generate(&env);
+    let signature = sign_payload(&env, &other_contract, &keypair, &payload);
+
+    client.create_invoice_signed(&manager, &payload, &signature);
+}
+
+/// Test 11: Expiry, payer restriction and fiat pricing are all carried by
+/// the signed payload.
+#[test]
+fn test_signed_fiat_invoice_with_expiry() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (manager, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+
+    let oracle_id = env.register(MockOracle, ());
+    MockOracleClient::new(&env, &oracle_id).set_price(&200_000_000);
+    client.set_token_oracle(
+        &admin,
+        &token,
+        &OracleConfig {
+            contract: oracle_id,
+            price_decimals: 8,
+            token_decimals: 7,
+        },
+    );
+
+    let payer = Address::generate(&env);
+    let description = String::from_str(&env, "Signed fiat");
+    let mut payload = invoice_payload(&merchant, &description, 0, &token, &create_nonce(&env, 1));
+    payload.payer = Some(payer.clone());
+    payload.expires_at = Some(10_000);
+    payload.fiat_pricing = FiatPricingData::Some(FiatPricing {
+        currency: String::from_str(&env, "USD"),
+        amount: 1_000,
+        decimals: 2,
+    });
+    let signature = sign_payload(&env, &contract_id, &keypair, &payload);
+
+    let invoice_id = client.create_invoice_signed(&manager, &payload, &signature);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.pricing_mode, InvoicePricingMode::FixedFiat);
+    assert_eq!(invoice.amount, 50_000_000);
+    assert_eq!(invoice.expires_at, Some(10_000));
+    assert_eq!(invoice.payer, Some(payer));
+}
+
+/// Test 12: Used nonces can be purged only once their deadline has passed.
+#[test]
+fn test_purge_used_nonces_after_deadline() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (manager, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+
+    let description = String::from_str(&env, "Purge");
+    let nonce = create_nonce(&env, 1);
+    let mut payload = invoice_payload(&merchant, &description, 1000, &token, &nonce);
+    payload.deadline = 500;
+    let signature = sign_payload(&env, &contract_id, &keypair, &payload);
+    client.create_invoice_signed(&manager, &payload, &signature);
+
+    let nonces = Vec::from_array(&env, [nonce.clone(), create_nonce(&env, 2)]);
+    assert_eq!(client.purge_used_nonces(&merchant, &nonces), 0);
+
+    env.ledger().set_timestamp(501);
+    assert_eq!(client.purge_used_nonces(&merchant, &nonces), 1);
+
+    // Replaying the purged nonce is still stopped by the deadline.
+    let result = client.try_create_invoice_signed(&manager, &payload, &signature);
+    assert!(result.is_err());
+}
+
+/// Test 13: Deadlines are capped so used nonces cannot be parked forever.
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #127)")]
+fn test_signature_deadline_too_far_rejected() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (manager, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+
+    let description = String::from_str(&env, "Far off");
+    let mut payload = invoice_payload(
+        &merchant,
+        &description,
+        1000,
+        &token,
+        &create_nonce(&env, 1),
+    );
+    payload.deadline = MAX_SIGNATURE_LIFETIME + 1;
+    let signature = sign_payload(&env, &contract_id, &keypair, &payload);
+
+    client.create_invoice_signed(&manager, &payload, &signature);
+}
+
+/// Test 14: Nonces recorded as `true` before deadlines were stored are left
+/// in place by the purge instead of trapping it.
+#[test]
+fn test_purge_skips_legacy_nonces() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (_, merchant, _, _) = setup_signer(&env, &client, &admin);
+
+    let legacy = create_nonce(&env, 1);
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::UsedNonce(merchant.clone(), legacy.clone()), &true);
+    });
+
+    env.ledger().set_timestamp(10_000);
+    let nonces = Vec::from_array(&env, [legacy.clone()]);
+    assert_eq!(client.purge_used_nonces(&merchant, &nonces), 0);
+    env.as_contract(&contract_id, || {
+        assert!(env
+            .storage()
+            .persistent()
+            .has(&DataKey::UsedNonce(merchant.clone(), legacy)));
+    });
+}
+
+/// Signs a token-priced invoice for `merchant` and returns the payload with
+/// its signature.
+fn signed_payload(
+    env: &Env,
+    contract_id: &Address,
+    keypair: &TestKeypair,
+    merchant: &Address,
+    amount: i128,
+    token: &Address,
+) -> (SignedInvoicePayload, BytesN<64>) {
+    let description = String::from_str(env, "Signed invoice");
+    let payload = invoice_payload(merchant, &description, amount, token, &create_nonce(env, 1));
+    let signature = sign_payload(env, contract_id, keypair, &payload);
+    (payload, signature)
+}
+
+/// Test 15: The admin may submit a signed invoice without the manager role.
+#[test]
+fn test_admin_can_submit_signed_invoice() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (_, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+    let (payload, signature) =
+        signed_payload(&env, &contract_id, &keypair, &merchant, 2_000, &token);
+
+    let invoice_id = client.create_invoice_signed(&admin, &payload, &signature);
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.amount, 2_000);
+    assert_eq!(invoice.status, InvoiceStatus::Pending);
+}
+
+/// Test 16: Callers without the manager role are refused, even with a valid
+/// merchant signature.
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_signed_invoice_from_unauthorized_caller_rejected() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (_, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+    let (payload, signature) = signed_payload(&env, &contract_id, &keypair, &merchant, 500, &token);
+
+    client.create_invoice_signed(&Address::generate(&env), &payload, &signature);
+}
+
+/// Test 17: The payload has to name a registered merchant.
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_signed_invoice_for_unregistered_merchant_rejected() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (manager, _, keypair, token) = setup_signer(&env, &client, &admin);
+    let stranger = Address::generate(&env);
+    let (payload, signature) = signed_payload(&env, &contract_id, &keypair, &stranger, 500, &token);
+
+    client.create_invoice_signed(&manager, &payload, &signature);
+}
+
+/// Test 18: Signed invoices go through the same amount checks as
+/// `create_invoice`.
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_signed_invoice_with_zero_amount_rejected() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (manager, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+    let (payload, signature) = signed_payload(&env, &contract_id, &keypair, &merchant, 0, &token);
+
+    client.create_invoice_signed(&manager, &payload, &signature);
+}
+
+/// Test 19: Nothing is created while the contract is paused.
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #9)")]
+fn test_signed_invoice_rejected_while_paused() {
+    let (env, client, contract_id, admin) = setup_test();
+    let (manager, merchant, keypair, token) = setup_signer(&env, &client, &admin);
+    let (payload, signature) = signed_payload(&env, &contract_id, &keypair, &merchant, 500, &token);
+    client.pause(&admin);
+
+    client.create_invoice_signed(&manager, &payload, &signature);
+}
@@ -0,0 +1,170 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::{InvoiceOptions, InvoiceStatus, PayerContribution};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{Address, String};
+
+struct SplitBill {
+    alice: Address,
+    bob: Address,
+    invoice_id: u64,
+}
+
+/// A 1_000 split-bill invoice; Alice and Bob each hold 1_000.
+fn setup_test() -> (ShadeFixture, SplitBill) {
+    let s = ShadeFixture::setup();
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .set_invoice_split_bill(&s.merchant, &invoice_id, &true);
+    let bill = SplitBill {
+        alice: s.funded_payer(1_000),
+        bob: s.funded_payer(1_000),
+        invoice_id,
+    };
+    (s, bill)
+}
+
+fn pay_split(s: &ShadeFixture, bill: &SplitBill, alice_share: i128) {
+    s.client
+        .pay_invoice_partial(&bill.alice, &bill.invoice_id, &alice_share, &None, &None);
+    s.client
+        .pay_invoice(&bill.bob, &bill.invoice_id, &None, &None, &None);
+}
+
+#[test]
+fn test_contributions_tracked_per_payer() {
+    let (s, bill) = setup_test();
+    s.client
+        .pay_invoice_partial(&bill.alice, &bill.invoice_id, &300, &None, &None);
+    s.client
+        .pay_invoice_partial(&bill.bob, &bill.invoice_id, &400, &None, &None);
+    s.client
+        .pay_invoice_partial(&bill.alice, &bill.invoice_id, &300, &None, &None);
+
+    let invoice = s.client.get_invoice(&bill.invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.payer, None);
+
+    let contributions = s.client.get_invoice_contributions(&bill.invoice_id);
+    assert_eq!(contributions.len(), 2);
+    assert_eq!(
+        contributions.get_unchecked(0),
+        PayerContribution {
+            payer: bill.alice.clone(),
+            amount_paid: 600,
+            amount_refunded: 0,
+        }
+    );
+    assert_eq!(contributions.get_unchecked(1).amount_paid, 400);
+}
+
+#[test]
+fn test_full_refund_returns_each_contribution() {
+    let (s, bill) = setup_test();
+    pay_split(&s, &bill, 600);
+
+    s.client.refund_invoice(&s.merchant, &bill.invoice_id);
+
+    assert_eq!(s.balance(&bill.alice), 1_000);
+    assert_eq!(s.balance(&bill.bob), 1_000);
+    assert_eq!(
+        s.client.get_invoice(&bill.invoice_id).status,
+        InvoiceStatus::Refunded
+    );
+}
+
+#[test]
+fn test_partial_refund_split_pro_rata() {
+    let (s, bill) = setup_test();
+    pay_split(&s, &bill, 700);
+
+    s.client
+        .refund_invoice_partial(&s.merchant, &bill.invoice_id, &333);
+
+    // 233.1 and 99.9 round down; the leftover unit goes to the first payer.
+    assert_eq!(s.balance(&bill.alice), 300 + 234);
+    assert_eq!(s.balance(&bill.bob), 700 + 99);
+    let contributions = s.client.get_invoice_contributions(&bill.invoice_id);
+    assert_eq!(contributions.get_unchecked(0).amount_refunded, 234);
+    assert_eq!(contributions.get_unchecked(1).amount_refunded, 99);
+}
+
+#[test]
+fn test_refund_single_payer() {
+    let (s, bill) = setup_test();
+    pay_split(&s, &bill, 600);
+
+    s.client
+        .refund_invoice_payer(&s.merchant, &bill.invoice_id, &bill.bob, &400);
+
+    assert_eq!(s.balance(&bill.alice), 400);
+    assert_eq!(s.balance(&bill.bob), 1_000);
+    assert_eq!(
+        s.client.get_invoice(&bill.invoice_id).status,
+        InvoiceStatus::PartiallyRefunded
+    );
+}
+
+#[test]
+fn test_cancel_refunds_all_contributors() {
+    let (s, bill) = setup_test();
+    s.client
+        .pay_invoice_partial(&bill.alice, &bill.invoice_id, &200, &None, &None);
+    s.client
+        .pay_invoice_partial(&bill.bob, &bill.invoice_id, &300, &None, &None);
+
+    s.client.cancel_invoice(&s.merchant, &bill.invoice_id);
+
+    assert_eq!(s.balance(&bill.alice), 1_000);
+    assert_eq!(s.balance(&bill.bob), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_payer_refund_capped_at_contribution() {
+    let (s, bill) = setup_test();
+    pay_split(&s, &bill, 600);
+    s.client
+        .refund_invoice_payer(&s.merchant, &bill.invoice_id, &bill.bob, &401);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #121)")]
+fn test_refund_to_non_contributor_rejected() {
+    let (s, bill) = setup_test();
+    pay_split(&s, &bill, 600);
+    let stranger = Address::generate(&s.env);
+    s.client
+        .refund_invoice_payer(&s.merchant, &bill.invoice_id, &stranger, &100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_second_payer_rejected_without_split_bill() {
+    let (s, bill) = setup_test();
+    s.client
+        .set_invoice_split_bill(&s.merchant, &bill.invoice_id, &false);
+    s.client
+        .pay_invoice_partial(&bill.alice, &bill.invoice_id, &500, &None, &None);
+    s.client
+        .pay_invoice_partial(&bill.bob, &bill.invoice_id, &500, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #16)")]
+fn test_split_bill_rejected_for_restricted_invoice() {
+    let (s, bill) = setup_test();
+    let invoice_id = s.client.create_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Just Alice"),
+        &1_000,
+        &s.token,
+        &InvoiceOptions {
+            payer: Some(bill.alice.clone()),
+            ..Default::default()
+        },
+    );
+    s.client
+        .set_invoice_split_bill(&s.merchant, &invoice_id, &true);
+}
@@ -39,7 +39,7 @@ fn setup_subscription_env() -> SubTestContext<'static> {
 
     // Register merchant + merchant account
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_account_id = env.register(MerchantAccount, ());
     let merchant_account = MerchantAccountClient::new(&env, &merchant_account_id);
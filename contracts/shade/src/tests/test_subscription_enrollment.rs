@@ -33,7 +33,7 @@ fn setup_enroll_env() -> EnrollCtx<'static> {
     client.set_fee(&admin, &token_addr, &500_i128);
 
     let merchant = Address::generate(&env);
-    client.register_merchant(&merchant);
+    client.register_merchant(&merchant, &None);
 
     let merchant_account_id = env.register(MerchantAccount, ());
     let merchant_account = MerchantAccountClient::new(&env, &merchant_account_id);
@@ -48,7 +48,13 @@ fn setup_enroll_env() -> EnrollCtx<'static> {
         &MONTHLY_INTERVAL,
     );
 
-    EnrollCtx { env, client, merchant, token: token_addr, plan_id }
+    EnrollCtx {
+        env,
+        client,
+        merchant,
+        token: token_addr,
+        plan_id,
+    }
 }
 
 // ---------------------------------------------------------------------------
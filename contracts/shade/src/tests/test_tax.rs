@@ -0,0 +1,172 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::{InvoiceOptions, InvoiceStatus, LineItem};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{vec, Address, String};
+
+/// The shared fixture with a tax account set for the merchant.
+fn setup_test() -> (ShadeFixture, Address) {
+    let s = ShadeFixture::setup();
+    let tax_account = Address::generate(&s.env);
+    s.client.set_merchant_tax_account(&s.merchant, &tax_account);
+    (s, tax_account)
+}
+
+#[test]
+fn test_set_and_remove_tax_rate() {
+    let (s, _) = setup_test();
+    let code = String::from_str(&s.env, "US-CA");
+
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &725);
+    assert_eq!(s.client.get_merchant_tax_rate(&1, &code), 725);
+
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &750);
+    assert_eq!(s.client.get_merchant_tax_rate(&1, &code), 750);
+
+    s.client.remove_merchant_tax_rate(&s.merchant, &code);
+    assert!(s.client.try_get_merchant_tax_rate(&1, &code).is_err());
+}
+
+#[test]
+fn test_apply_invoice_tax_updates_totals() {
+    let (s, _) = setup_test();
+    let code = String::from_str(&s.env, "DE");
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &1_900);
+
+    let invoice_id = s.create_invoice(1_000);
+    s.client.apply_invoice_tax(&s.merchant, &invoice_id, &code);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.subtotal, 1_000);
+    assert_eq!(invoice.tax_amount, 190);
+    assert_eq!(invoice.amount, 1_190);
+    assert_eq!(invoice.tax_code, Some(code.clone()));
+    assert_eq!(invoice.tax_rate_bps, 1_900);
+
+    // Later rate changes do not touch invoices the rate was already applied to.
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &2_000);
+    assert_eq!(s.client.get_invoice(&invoice_id).tax_amount, 190);
+}
+
+#[test]
+fn test_invoice_tax_adds_to_line_item_tax() {
+    let (s, _) = setup_test();
+    let code = String::from_str(&s.env, "CITY");
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &100);
+
+    let items = vec![
+        &s.env,
+        LineItem {
+            sku: String::from_str(&s.env, "SKU-1"),
+            quantity: 2,
+            unit_price: 500,
+            tax_bps: 1_000,
+        },
+    ];
+    let invoice_id = s.client.create_itemized_invoice(
+        &s.merchant,
+        &String::from_str(&s.env, "Itemized"),
+        &items,
+        &s.token,
+        &InvoiceOptions::default(),
+    );
+    s.client.apply_invoice_tax(&s.merchant, &invoice_id, &code);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.subtotal, 1_000);
+    assert_eq!(invoice.tax_amount, 110);
+    assert_eq!(invoice.amount, 1_110);
+}
+
+#[test]
+fn test_payment_routes_tax_to_tax_account() {
+    let (s, tax_account) = setup_test();
+    let code = String::from_str(&s.env, "UK");
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &2_000);
+    s.client.set_fee(&s.admin, &s.token, &100); // 1% platform fee
+
+    let invoice_id = s.create_invoice(1_000);
+    s.client.apply_invoice_tax(&s.merchant, &invoice_id, &code);
+
+    let payer = s.funded_payer(1_200);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+    assert_eq!(s.balance(&tax_account), 200);
+    assert_eq!(s.balance(&s.merchant_account), 990);
+    assert_eq!(s.balance(&payer), 0);
+
+    let analytics = s.client.get_merchant_analytics(&s.merchant, &s.token);
+    assert_eq!(analytics.total_tax, 200);
+    assert_eq!(analytics.total_fees, 10);
+    assert_eq!(analytics.total_volume, 1_200);
+    assert_eq!(
+        s.client
+            .get_merchant_analytics_summary(&s.merchant)
+            .total_tax,
+        200
+    );
+}
+
+#[test]
+fn test_partial_payments_route_tax_pro_rata() {
+    let (s, tax_account) = setup_test();
+    let code = String::from_str(&s.env, "VAT");
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &1_000);
+
+    let invoice_id = s.create_invoice(1_000);
+    s.client.apply_invoice_tax(&s.merchant, &invoice_id, &code);
+
+    let payer = s.funded_payer(1_100);
+
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &333, &None, &None);
+    assert_eq!(s.balance(&tax_account), 30);
+
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &767, &None, &None);
+    assert_eq!(s.balance(&tax_account), 100);
+    assert_eq!(s.balance(&s.merchant_account), 1_000);
+    assert_eq!(
+        s.client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Paid
+    );
+}
+
+#[test]
+fn test_tax_goes_to_merchant_account_without_tax_account() {
+    let s = ShadeFixture::setup();
+    let code = String::from_str(&s.env, "VAT");
+    s.client.set_merchant_tax_rate(&s.merchant, &code, &500);
+    let invoice_id = s.create_invoice(1_000);
+    s.client.apply_invoice_tax(&s.merchant, &invoice_id, &code);
+
+    let payer = s.funded_payer(1_050);
+    s.client
+        .pay_invoice(&payer, &invoice_id, &None, &None, &None);
+
+    assert_eq!(s.balance(&s.merchant_account), 1_050);
+    assert_eq!(
+        s.client
+            .get_merchant_analytics(&s.merchant, &s.token)
+            .total_tax,
+        50
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #101)")]
+fn test_tax_rate_above_100_percent_rejected() {
+    let (s, _) = setup_test();
+    s.client
+        .set_merchant_tax_rate(&s.merchant, &String::from_str(&s.env, "BAD"), &10_001);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #102)")]
+fn test_apply_unknown_tax_code_rejected() {
+    let (s, _) = setup_test();
+    let invoice_id = s.create_invoice(1_000);
+    s.client
+        .apply_invoice_tax(&s.merchant, &invoice_id, &String::from_str(&s.env, "NONE"));
+}
@@ -0,0 +1,101 @@
+#![cfg(test)]
+
+use crate::tests::common::ShadeFixture;
+use crate::types::{InvoiceStatus, TipConfig};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::Address;
+
+/// A 1_000 invoice under a 5% platform fee; the payer holds 1_200.
+fn setup_test() -> (ShadeFixture, Address, u64) {
+    let s = ShadeFixture::setup().with_fee(500);
+    let invoice_id = s.create_invoice(1_000);
+    let payer = s.funded_payer(1_200);
+    (s, payer, invoice_id)
+}
+
+#[test]
+fn test_tip_routed_to_tip_account_after_fee() {
+    let (s, payer, invoice_id) = setup_test();
+    let staff = Address::generate(&s.env);
+    s.client.set_merchant_tip_config(
+        &s.merchant,
+        &TipConfig {
+            tip_account: Some(staff.clone()),
+            fee_exempt: false,
+        },
+    );
+
+    s.client
+        .pay_invoice_with_tip(&payer, &invoice_id, &None, &200, &None);
+
+    assert_eq!(s.balance(&staff), 190);
+    assert_eq!(s.balance(&s.merchant_account), 950);
+    assert_eq!(s.balance(&s.platform), 60);
+    assert_eq!(s.balance(&payer), 0);
+
+    let invoice = s.client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.amount_paid, 1_000);
+    assert_eq!(invoice.tip_amount, 200);
+    assert_eq!(invoice.fee_amount, 60);
+}
+
+#[test]
+fn test_fee_exempt_tip_paid_in_full() {
+    let (s, payer, invoice_id) = setup_test();
+    let staff = Address::generate(&s.env);
+    s.client.set_merchant_tip_config(
+        &s.merchant,
+        &TipConfig {
+            tip_account: Some(staff.clone()),
+            fee_exempt: true,
+        },
+    );
+
+    s.client
+        .pay_invoice_with_tip(&payer, &invoice_id, &None, &200, &None);
+
+    assert_eq!(s.balance(&staff), 200);
+    assert_eq!(s.balance(&s.platform), 50);
+}
+
+#[test]
+fn test_tip_defaults_to_merchant_account() {
+    let (s, payer, invoice_id) = setup_test();
+    assert_eq!(
+        s.client.get_merchant_tip_config(&1),
+        TipConfig {
+            tip_account: None,
+            fee_exempt: false,
+        }
+    );
+
+    s.client
+        .pay_invoice_with_tip(&payer, &invoice_id, &None, &100, &None);
+
+    assert_eq!(s.balance(&s.merchant_account), 950 + 95);
+}
+
+#[test]
+fn test_max_amount_excludes_tip() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client
+        .pay_invoice_with_tip(&payer, &invoice_id, &Some(1_000), &200, &None);
+    assert_eq!(s.client.get_invoice(&invoice_id).tip_amount, 200);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_negative_tip_rejected() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client
+        .pay_invoice_with_tip(&payer, &invoice_id, &None, &-1, &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #7)")]
+fn test_partial_overpayment_still_rejected() {
+    let (s, payer, invoice_id) = setup_test();
+    s.client
+        .pay_invoice_partial(&payer, &invoice_id, &1_100, &None, &None);
+}
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use crate::shade::{Shade, ShadeClient};
-use crate::types::TransactionType;
+use crate::types::{InvoiceOptions, TransactionType};
 use soroban_sdk::testutils::{Address as _, Ledger as _};
 use soroban_sdk::{token, Address, Env, String};
 
@@ -29,18 +29,24 @@ fn test_invoice_payment_records_history() {
     let (env, shade_client, _shade_contract_id, _admin, token) = setup_test();
 
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
     let merchant_account = Address::generate(&env);
     shade_client.set_merchant_account(&merchant, &merchant_account);
 
     let description = String::from_str(&env, "History Test");
-    let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
+    let invoice_id = shade_client.create_invoice(
+        &merchant,
+        &description,
+        &1000,
+        &token,
+        &InvoiceOptions::default(),
+    );
 
     let customer = Address::generate(&env);
     let token_client = token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
 
-    shade_client.pay_invoice(&customer, &invoice_id);
+    shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
 
     let history = shade_client.get_user_transactions(&customer);
     assert_eq!(history.len(), 1);
@@ -59,7 +65,7 @@ fn test_multiple_payments_record_history() {
     let (env, shade_client, _shade_contract_id, _admin, token) = setup_test();
 
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
     let merchant_account = Address::generate(&env);
     shade_client.set_merchant_account(&merchant, &merchant_account);
 
@@ -69,8 +75,14 @@ fn test_multiple_payments_record_history() {
 
     for _i in 1..=3 {
         let description = String::from_str(&env, "Test Inv");
-        let invoice_id = shade_client.create_invoice(&merchant, &description, &1000, &token, &None);
-        shade_client.pay_invoice(&customer, &invoice_id);
+        let invoice_id = shade_client.create_invoice(
+            &merchant,
+            &description,
+            &1000,
+            &token,
+            &InvoiceOptions::default(),
+        );
+        shade_client.pay_invoice(&customer, &invoice_id, &None, &None, &None);
     }
 
     let history = shade_client.get_user_transactions(&customer);
@@ -82,7 +94,7 @@ fn test_subscription_charge_records_history() {
     let (env, shade_client, _shade_contract_id, _admin, token) = setup_test();
 
     let merchant = Address::generate(&env);
-    shade_client.register_merchant(&merchant);
+    shade_client.register_merchant(&merchant, &None);
     let merchant_account = Address::generate(&env);
     shade_client.set_merchant_account(&merchant, &merchant_account);
 
@@ -92,7 +104,7 @@ fn test_subscription_charge_records_history() {
     let customer = Address::generate(&env);
     let token_client = token::StellarAssetClient::new(&env, &token);
     token_client.mint(&customer, &1000);
-    
+
     // Manual approval for subscription
     let token_token_client = token::TokenClient::new(&env, &token);
     token_token_client.approve(&customer, &shade_client.address, &1000, &2000);
@@ -0,0 +1,273 @@
+#![cfg(test)]
+
+use crate::components::admin::{DAY_IN_SECONDS, FEE_UPDATE_DELAY};
+use crate::shade::{Shade, ShadeClient};
+use crate::types::{InvoiceOptions, VolumeDiscount};
+use account::account::{MerchantAccount, MerchantAccountClient};
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+use soroban_sdk::{token, vec, Address, Env, Map, String, Symbol, TryIntoVal, Val};
+
+struct VolumeWindowContext<'a> {
+    env: Env,
+    client: ShadeClient<'a>,
+    admin: Address,
+    merchant: Address,
+    token: Address,
+    payer: Address,
+}
+
+/// 10% token fee with 10% off from 10k volume and 50% off from 50k. The
+/// merchant account is a real account contract so refunds can go through.
+fn setup() -> VolumeWindowContext<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let shade_id = env.register(Shade, ());
+    let client = ShadeClient::new(&env, &shade_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    client.add_accepted_token(&admin, &token);
+    client.set_fee(&admin, &token, &1000);
+    client.set_platform_account(&admin, &Address::generate(&env));
+
+    let tiers = vec![
+        &env,
+        VolumeDiscount {
+            min_volume: 10_000,
+            discount_bps: 1_000,
+        },
+        VolumeDiscount {
+            min_volume: 50_000,
+            discount_bps: 5_000,
+        },
+    ];
+    client.propose_volume_discounts(&admin, &token, &tiers);
+    env.ledger().set_timestamp(FEE_UPDATE_DELAY);
+    client.execute_volume_discounts(&admin, &token);
+
+    let merchant = Address::generate(&env);
+    client.register_merchant(&merchant, &None);
+    let merchant_account = env.register(MerchantAccount, ());
+    MerchantAccountClient::new(&env, &merchant_account).initialize(&merchant, &shade_id, &1_u64);
+    client.set_merchant_account(&merchant, &merchant_account);
+
+    let payer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&payer, &1_000_000);
+
+    VolumeWindowContext {
+        env,
+        client,
+        admin,
+        merchant,
+        token,
+        payer,
+    }
+}
+
+fn pay(ctx: &VolumeWindowContext, amount: i128) -> u64 {
+    let invoice_id = ctx.client.create_invoice(
+        &ctx.merchant,
+        &String::from_str(&ctx.env, "Volume window invoice"),
+        &amount,
+        &ctx.token,
+        &InvoiceOptions::default(),
+    );
+    ctx.client
+        .pay_invoice(&ctx.payer, &invoice_id, &None, &None, &None);
+    invoice_id
+}
+
+fn advance_days(env: &Env, days: u64) {
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + days * DAY_IN_SECONDS);
+}
+
+#[test]
+fn test_discount_lapses_when_volume_leaves_window() {
+    let ctx = setup();
+    assert_eq!(ctx.client.get_volume_window(), 30);
+
+    pay(&ctx, 10_000);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        10_000
+    );
+    // 10% off the 10% fee.
+    assert_eq!(
+        ctx.client.calculate_fee(&ctx.merchant, &ctx.token, &1_000),
+        90
+    );
+
+    advance_days(&ctx.env, 29);
+    assert_eq!(
+        ctx.client.calculate_fee(&ctx.merchant, &ctx.token, &1_000),
+        90
+    );
+
+    advance_days(&ctx.env, 1);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        0
+    );
+    assert_eq!(
+        ctx.client.calculate_fee(&ctx.merchant, &ctx.token, &1_000),
+        100
+    );
+    // Lifetime volume is kept for analytics.
+    assert_eq!(
+        ctx.client.get_merchant_volume(&ctx.merchant, &ctx.token),
+        10_000
+    );
+}
+
+#[test]
+fn test_window_volume_sums_days_inside_window() {
+    let ctx = setup();
+
+    pay(&ctx, 30_000);
+    advance_days(&ctx.env, 10);
+    pay(&ctx, 20_000);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        50_000
+    );
+    assert_eq!(
+        ctx.client.calculate_fee(&ctx.merchant, &ctx.token, &1_000),
+        50
+    );
+
+    // The first day drops out, the second is still counted.
+    advance_days(&ctx.env, 20);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        20_000
+    );
+    assert_eq!(
+        ctx.client.calculate_fee(&ctx.merchant, &ctx.token, &1_000),
+        90
+    );
+}
+
+#[test]
+fn test_volume_window_is_configurable() {
+    let ctx = setup();
+    ctx.client.set_volume_window(&ctx.admin, &7);
+    assert_eq!(ctx.client.get_volume_window(), 7);
+
+    pay(&ctx, 10_000);
+    advance_days(&ctx.env, 6);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        10_000
+    );
+
+    advance_days(&ctx.env, 1);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        0
+    );
+}
+
+#[test]
+fn test_refund_reduces_window_volume() {
+    let ctx = setup();
+
+    let invoice_id = pay(&ctx, 10_000);
+
+    ctx.client
+        .refund_invoice_partial(&ctx.merchant, &invoice_id, &4_000);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        6_000
+    );
+    assert_eq!(
+        ctx.client.calculate_fee(&ctx.merchant, &ctx.token, &1_000),
+        100
+    );
+    assert_eq!(
+        ctx.client.get_merchant_volume(&ctx.merchant, &ctx.token),
+        10_000
+    );
+
+    // The 1_000 fee is not refunded, so that much volume stays counted.
+    ctx.client
+        .refund_invoice_partial(&ctx.merchant, &invoice_id, &5_000);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        1_000
+    );
+}
+
+#[test]
+fn test_refund_comes_off_the_day_it_was_paid() {
+    let ctx = setup();
+
+    let invoice_id = pay(&ctx, 10_000);
+    advance_days(&ctx.env, 2);
+    pay(&ctx, 5_000);
+
+    ctx.client
+        .refund_invoice_partial(&ctx.merchant, &invoice_id, &4_000);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        11_000
+    );
+
+    // Only the 6,000 left from the first day leaves the window.
+    advance_days(&ctx.env, 28);
+    assert_eq!(
+        ctx.client
+            .get_merchant_window_volume(&ctx.merchant, &ctx.token),
+        5_000
+    );
+}
+
+#[test]
+fn test_setting_volume_window_emits_event() {
+    let ctx = setup();
+    ctx.client.set_volume_window(&ctx.admin, &7);
+
+    let event = ctx.env.events().all().last().unwrap();
+    let data: Map<Symbol, Val> = event.2.try_into_val(&ctx.env).unwrap();
+    let days: u32 = data
+        .get(Symbol::new(&ctx.env, "days"))
+        .unwrap()
+        .try_into_val(&ctx.env)
+        .unwrap();
+    assert_eq!(days, 7);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #203)")]
+fn test_zero_volume_window_rejected() {
+    let ctx = setup();
+    ctx.client.set_volume_window(&ctx.admin, &0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #203)")]
+fn test_volume_window_over_a_year_rejected() {
+    let ctx = setup();
+    ctx.client.set_volume_window(&ctx.admin, &366);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1)")]
+fn test_non_admin_cannot_set_volume_window() {
+    let ctx = setup();
+    ctx.client.set_volume_window(&ctx.merchant, &7);
+}
@@ -6,7 +6,6 @@ pub enum DataKey {
     PendingAdmin,
     Paused,
     FeeInBasisPoints(Address),
-    FeeAmount(Address),
     ContractInfo,
     AcceptedTokens,
     Merchant(u64),
@@ -49,6 +48,69 @@ pub enum DataKey {
     TokenVolume(Address),
 }
 
+// A contract type union can hold at most 50 cases in the spec and `DataKey` is
+// nearly there, so keys added for newer invoice features are kept separately.
+#[contracttype]
+pub enum InvoiceDataKey {
+    MerchantTaxRate(u64, String),
+    MerchantTaxAccount(u64),
+    MerchantExpiryPolicy(u64),
+    InvoiceInstallments(u64),
+    FiatQuoteWindow,
+    FiatQuote(u64, Address),
+    RefundRequest(u64),
+    RefundWindowBounds,
+    MerchantRefundWindow(u64),
+    MerchantTokenRefundWindow(u64, Address),
+    InvoiceTemplate(u64),
+    InvoiceTemplateCount,
+    InvoiceByReference(u64, String),
+    InvoiceContributions(u64),
+    MerchantTipConfig(u64),
+    CreditNote(u64),
+    CreditNoteCount,
+    InvoiceRevision(u64, u32),
+    InvoiceCreditRedemptions(u64),
+}
+
+/// A secondary invoice index. `Open` holds Pending and PartiallyPaid invoices
+/// for the expiry sweep.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvoiceIndexKey {
+    Merchant(u64),
+    Payer(Address),
+    Status(InvoiceStatus),
+    Open,
+}
+
+// Each index is stored as numbered entries plus a length, so no single entry
+// grows with the number of invoices. `Position` maps an invoice back to its
+// entry for removals.
+#[contracttype]
+pub enum InvoiceIndexDataKey {
+    Length(InvoiceIndexKey),
+    Entry(InvoiceIndexKey, u32),
+    Position(InvoiceIndexKey, u64),
+}
+
+// Fee configuration keys. These also stay out of `DataKey` to leave it room.
+#[contracttype]
+pub enum FeeDataKey {
+    FeeSchedule(Address),
+    MerchantFeeOverride(Address, Address),
+    VolumeDiscounts(Address),
+    PendingVolumeDiscounts(Address),
+    VolumeWindowDays,
+    MerchantVolumeBuckets(Address, Address),
+    ReferralConfig,
+    MerchantReferral(Address),
+    ReferralEarnings(Address, Address),
+    FeeAccrualEnabled,
+    AccruedFees(Address),
+    TreasurySplits,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ContractInfo {
@@ -79,6 +141,57 @@ pub enum InvoiceStatus {
     PartiallyRefunded = 4,
     PartiallyPaid = 5,
     Draft = 6,
+    Expired = 7,
+}
+
+/// Admin limits on merchant refund windows, in seconds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RefundWindowBounds {
+    pub min: u64,
+    pub max: u64,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum RefundRequestStatus {
+    /// Waiting for the merchant to respond.
+    Requested = 0,
+    Accepted = 1,
+    /// Rejected by the merchant; open to arbitration.
+    Rejected = 2,
+    /// Granted by an arbiter.
+    Approved = 3,
+    /// Turned down by an arbiter.
+    Denied = 4,
+}
+
+/// A payer's request to have part of a paid invoice refunded.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RefundRequest {
+    pub invoice_id: u64,
+    pub payer: Address,
+    pub amount: i128,
+    /// Hash of the off-chain reason the payer gave.
+    pub reason_hash: BytesN<32>,
+    pub status: RefundRequestStatus,
+    pub requested_at: u64,
+    /// After this, an unanswered request can go to arbitration.
+    pub respond_by: u64,
+    pub resolved_at: Option<u64>,
+}
+
+/// What happens to partial payments when a merchant's invoice expires.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ExpiryRefundPolicy {
+    /// Return what the merchant received so far to the payer (default).
+    RefundPayer = 0,
+    /// Keep partial payments with the merchant.
+    RetainPayments = 1,
 }
 
 #[contracttype]
@@ -126,25 +239,144 @@ pub struct Invoice {
     pub expires_at: Option<u64>,
     pub pricing_mode: InvoicePricingMode,
     pub fiat_pricing: FiatPricingData,
+    /// Empty for single-amount invoices.
+    pub line_items: Vec<LineItem>,
+    /// Pre-tax total. Equals `amount` when the invoice carries no tax.
+    pub subtotal: i128,
+    pub tax_amount: i128,
+    /// Jurisdiction code of the merchant tax rate applied with `apply_invoice_tax`.
+    pub tax_code: Option<String>,
+    /// Invoice-level tax rate in basis points, charged on `subtotal` on top of
+    /// any per-line tax.
+    pub tax_rate_bps: u32,
+    /// Currency both tokens are valued in when a reference-token invoice is
+    /// paid in another token. `None` for single-token invoices.
+    pub quote_currency: Option<String>,
+    /// Set when the invoice was settled in a token other than `token`;
+    /// `amount_paid`, `amount_refunded`, `fee_amount`, `tax_paid`,
+    /// `discount_amount` and `penalty_amount` are then in this token.
+    pub paid_token: Option<Address>,
+    pub payment_terms: PaymentTermsData,
+    /// Early-payment discount granted when the terms were resolved.
+    pub discount_amount: i128,
+    /// Late-payment penalty charged under the terms, once, after the due date.
+    pub penalty_amount: i128,
+    /// Platform fees taken across all payments on the invoice.
+    pub fee_amount: i128,
+    /// Tax passed through to the merchant's tax account across all payments.
+    pub tax_paid: i128,
+    /// Recurring template the invoice was issued from, if any.
+    pub template_id: Option<u64>,
+    /// Merchant's own identifier for the invoice, unique per merchant.
+    pub external_ref: Option<String>,
+    /// Several payers may contribute; `payer` then stays unset and each
+    /// payer's share is tracked separately.
+    pub split_bill: bool,
+    /// Tips paid on top of the invoice, outside `amount_paid`.
+    pub tip_amount: i128,
+    /// Part of `amount_paid` settled with credit notes rather than tokens.
+    pub credit_applied: i128,
+    /// Credit notes issued against the invoice after it was paid.
+    pub credit_issued: i128,
+    /// Number of amendments made; 0 while the invoice is as created.
+    pub revision: u32,
 }
 
+/// Merchant's tip routing. `tip_account` defaults to the merchant account.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Invoice {
-    pub id: u64,
-    pub description: soroban_sdk::String,
+pub struct TipConfig {
+    pub tip_account: Option<Address>,
+    pub fee_exempt: bool,
+}
+
+/// One payer's share of a split-bill invoice, in the settlement token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayerContribution {
+    pub payer: Address,
+    pub amount_paid: i128,
+    pub amount_refunded: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineItem {
+    pub sku: String,
+    pub quantity: u32,
+    /// Price per unit in token base units.
+    pub unit_price: i128,
+    /// Tax applied to this line in basis points (10_000 = 100%).
+    pub tax_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Installment {
+    pub due_date: u64,
     pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InstallmentPlan {
+    pub installments: Vec<Installment>,
+    /// Installments fully covered by payments so far, counted from the first.
+    pub paid_count: u32,
+    /// Set once the next unpaid installment is past its due date; cleared
+    /// when payments catch up.
+    pub overdue: bool,
+}
+
+/// Early-payment discount and late-payment penalty, both charged on the
+/// invoice subtotal. Set through `InvoiceOptions` at creation or with
+/// `set_invoice_terms` while the invoice is a draft or pending. The adjusted
+/// amount is recomputed from the terms for every quote and payment; the first
+/// payment fixes the discount and the penalty is added at most once.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentTerms {
+    /// Applies to a first payment made at or before `discount_deadline`.
+    pub discount_bps: u32,
+    pub discount_deadline: u64,
+    /// Charged once, on whatever is still outstanding at the first payment
+    /// made after `due_date`.
+    pub penalty_bps: u32,
+    pub due_date: u64,
+}
+
+/// Token amount of a fiat invoice pinned for one payer by `lock_fiat_quote`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FiatQuote {
+    /// Token the invoice will be paid in; `amount` is in this token.
     pub token: Address,
-    pub status: InvoiceStatus,
-    pub merchant_id: u64,
+    /// Pre-tax amount in `token`.
+    pub amount: i128,
+    pub valid_until: u64,
+}
+
+/// Optional `PaymentTerms` on an invoice; see `FiatPricingData`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum PaymentTermsData {
+    #[default]
+    None,
+    Some(PaymentTerms),
+}
+
+/// Optional settings shared by `create_invoice`, `create_multi_token_invoice`
+/// and `create_fiat_invoice`. `InvoiceOptions::default()` sets none of them.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InvoiceOptions {
+    /// Only this address may pay the invoice.
     pub payer: Option<Address>,
-    pub date_created: u64,
-    pub date_paid: Option<u64>,
-    pub amount_paid: i128,
-    pub amount_refunded: i128,
+    /// The merchant's own order ID, unique per merchant.
+    pub external_ref: Option<String>,
     pub expires_at: Option<u64>,
-    pub pricing_mode: InvoicePricingMode,
-    pub fiat_pricing: Option<FiatPricing>,
+    /// Discount and penalty terms, as `set_invoice_terms` would set them.
+    pub terms: PaymentTermsData,
 }
 
 #[contracttype]
@@ -165,6 +397,25 @@ pub struct InvoiceFilter {
     pub end_date: Option<u64>,
 }
 
+/// Secondary index used to page through invoices without scanning every ID.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvoiceIndex {
+    All,
+    Merchant(Address),
+    Payer(Address),
+    Status(InvoiceStatus),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoicePage {
+    pub invoices: Vec<Invoice>,
+    /// Position in the index just after this page; pass it back as the cursor
+    /// to fetch the next page. `None` once the index is exhausted.
+    pub next_cursor: Option<u64>,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Role {
@@ -173,6 +424,9 @@ pub enum Role {
     Operator,
 }
 
+/// Fee discount for merchants whose volume in a token has reached
+/// `min_volume`, in that token's smallest units. `discount_bps` is taken off
+/// the fee rate, so 2_500 turns a 4% fee into 3%.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VolumeDiscount {
@@ -180,6 +434,23 @@ pub struct VolumeDiscount {
     pub discount_bps: i128,
 }
 
+/// Net payment volume a merchant took in a token on one day, counted in days
+/// since the Unix epoch.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VolumeBucket {
+    pub day: u64,
+    pub volume: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingVolumeDiscounts {
+    pub token: Address,
+    pub tiers: Vec<VolumeDiscount>,
+    pub proposed_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct OracleConfig {
@@ -195,6 +466,10 @@ pub struct MerchantAnalytics {
     pub token: Address,
     pub total_volume: i128,
     pub total_fees: i128,
+    /// Tax collected on invoice payments and routed to the tax account.
+    pub total_tax: i128,
+    /// Invoice amounts settled by redeeming credit notes, not in `total_volume`.
+    pub total_credit: i128,
     pub transaction_count: u64,
     pub last_updated: u64,
 }
@@ -205,6 +480,8 @@ pub struct MerchantAnalyticsSummary {
     pub merchant: Address,
     pub total_volume: i128,
     pub total_fees: i128,
+    pub total_tax: i128,
+    pub total_credit: i128,
     pub transaction_count: u64,
     pub last_updated: u64,
 }
@@ -229,10 +506,70 @@ pub struct CrossChainBridgePayload {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PendingFee {
     pub token: Address,
-    pub fee: i128,
+    pub schedule: FeeSchedule,
     pub proposed_at: u64,
 }
 
+/// Platform fee charged on each payment in a token: `flat_fee` plus
+/// `fee_bps` of the amount, raised to `min_fee` and limited to `max_fee`
+/// when it is non-zero. Volume discounts only reduce the `fee_bps` part.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeSchedule {
+    pub flat_fee: i128,
+    pub fee_bps: i128,
+    pub min_fee: i128,
+    pub max_fee: i128,
+}
+
+/// How a merchant's negotiated fee combines with the token's volume discounts.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum FeeOverridePriority {
+    /// The override alone prices the payment.
+    OverrideOnly = 0,
+    /// Volume discounts still reduce the override's bps rate.
+    StackVolumeDiscount = 1,
+    /// Whichever is lower of the override and the discounted token fee.
+    LowestFee = 2,
+}
+
+/// Fee schedule negotiated for one merchant in one token, in force from
+/// `effective_from` until `expires_at`, if set.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerchantFeeOverride {
+    pub schedule: FeeSchedule,
+    pub priority: FeeOverridePriority,
+    pub effective_from: u64,
+    pub expires_at: Option<u64>,
+}
+
+/// Share of the platform fee paid to a merchant's referrer, for `duration`
+/// seconds after the merchant registered.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralConfig {
+    pub share_bps: i128,
+    pub duration: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerchantReferral {
+    pub referrer: Address,
+    pub referred_at: u64,
+}
+
+/// One treasury account and its share of each fee sweep, in basis points.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreasurySplit {
+    pub account: Address,
+    pub share_bps: i128,
+}
+
 // --- Subscription engine ---
 
 #[contracttype]
@@ -270,6 +607,35 @@ pub struct Subscription {
     pub last_charged: u64,
 }
 
+/// Blueprint for invoices billed to a fixed payer every `interval` seconds.
+/// Unlike a subscription nothing is pulled from the payer; each period just
+/// produces a new invoice for them to pay.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceTemplate {
+    pub id: u64,
+    pub merchant_id: u64,
+    pub merchant: Address,
+    pub payer: Address,
+    pub description: soroban_sdk::String,
+    pub amount: i128,
+    pub token: Address,
+    pub interval: u64,
+    /// Earliest timestamp at which the next invoice can be issued.
+    pub next_issue_at: u64,
+    pub issued_count: u32,
+    pub status: InvoiceTemplateStatus,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum InvoiceTemplateStatus {
+    Active = 0,
+    Paused = 1,
+    Ended = 2,
+}
+
 #[contracttype]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u32)]
@@ -349,6 +715,25 @@ pub struct SwapRoute {
     pub path: Vec<Address>,
 }
 
+/// Invoice parameters a merchant signs off-chain for `create_invoice_signed`.
+/// With `fiat_pricing` set the token amount is resolved from the oracle and
+/// `amount` must be zero.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignedInvoicePayload {
+    pub version: u32,
+    pub merchant: Address,
+    pub description: String,
+    pub amount: i128,
+    pub token: Address,
+    pub payer: Option<Address>,
+    pub expires_at: Option<u64>,
+    pub fiat_pricing: FiatPricingData,
+    pub nonce: BytesN<32>,
+    /// Last ledger timestamp at which the signature is accepted.
+    pub deadline: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PaymentPayload {
@@ -357,3 +742,53 @@ pub struct PaymentPayload {
     pub route: PaymentRoute,
     pub max_slippage_bps: Option<u32>,
 }
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum CreditNoteStatus {
+    Active = 0,
+    Redeemed = 1,
+    Voided = 2,
+}
+
+/// Store credit a merchant owes a customer, redeemable on the merchant's
+/// later invoices in `token`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditNote {
+    pub id: u64,
+    pub merchant_id: u64,
+    pub merchant: Address,
+    pub customer: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub amount_redeemed: i128,
+    /// Paid invoice the credit was issued against; `None` for standalone credit.
+    pub invoice_id: Option<u64>,
+    pub expires_at: Option<u64>,
+    pub issued_at: u64,
+    pub status: CreditNoteStatus,
+}
+
+/// Credit drawn from one note towards an invoice, kept so it can be given
+/// back if the invoice is cancelled or expires before it is paid in full.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditRedemption {
+    pub credit_note_id: u64,
+    pub amount: i128,
+}
+
+/// One amendment of an invoice: what changed, when and by whom.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceRevision {
+    pub revision: u32,
+    pub old_amount: i128,
+    pub new_amount: i128,
+    pub old_description: String,
+    pub new_description: String,
+    pub amended_by: Address,
+    pub timestamp: u64,
+}